@@ -4,6 +4,8 @@ use rust_decimal::Decimal;
 use serde_json::json;
 use tracing::{info, warn};
 
+use crate::types::Asset;
+
 /// Alert client supporting Discord and Telegram
 pub struct AlertClient {
     client: Client,
@@ -86,12 +88,18 @@ impl AlertClient {
     }
 
     /// Alert: Bot started
-    pub async fn bot_started(&self, dry_run: bool) {
+    pub async fn bot_started(&self, assets: &[Asset], dry_run: bool) {
         let mode = if dry_run { "🔵 DRY RUN" } else { "🟢 LIVE" };
-        let msg = format!("🤖 <b>BTC 15m Bot Started</b>\n{}", mode);
+        let msg = format!("🤖 <b>{} 15m Bot Started</b>\n{}", asset_label(assets), mode);
         let _ = self.send(&msg, 0x00FF00).await;
     }
 
+    /// Alert: Bot stopped
+    pub async fn bot_stopped(&self, assets: &[Asset], reason: &str) {
+        let msg = format!("🛑 <b>{} 15m Bot Stopped</b>\n{}", asset_label(assets), reason);
+        let _ = self.send(&msg, 0x808080).await;
+    }
+
     /// Alert: New market found
     pub async fn market_found(&self, title: &str, _end_time: &str) {
         // Extract time from title (e.g., "11:30PM-11:45PM ET")
@@ -195,22 +203,27 @@ impl AlertClient {
     }
 
     /// Alert: Market skipped (no entry)
-    pub async fn market_skipped(&self, market_time: &str, reason: &str, btc_change: Decimal) {
-        let direction = if btc_change > Decimal::ZERO { "📈" } else { "📉" };
+    pub async fn market_skipped(&self, asset: Asset, market_time: &str, reason: &str, spot_change: Decimal) {
+        let direction = if spot_change > Decimal::ZERO { "📈" } else { "📉" };
         let msg = format!(
-            "⏭️ <b>SKIP</b>: {}\n{} BTC: {:+.3}%\n💡 {}",
-            market_time, direction, btc_change, reason
+            "⏭️ <b>SKIP</b>: {}\n{} {}: {:+.3}%\n💡 {}",
+            market_time, direction, asset.symbol(), spot_change, reason
         );
         let _ = self.send(&msg, 0xFFA500).await; // Orange
     }
 
     /// Alert: Market summary (entry taken)
-    pub async fn market_entry(&self, market_time: &str, direction: &str, entry_price: Decimal, shares: Decimal, btc_change: Decimal) {
+    pub async fn market_entry(&self, asset: Asset, market_time: &str, direction: &str, entry_price: Decimal, shares: Decimal, spot_change: Decimal) {
         let emoji = if direction == "UP" { "🟢" } else { "🔴" };
         let msg = format!(
-            "{} <b>ENTRY</b>: {}\n{} {} @ {}¢\nBTC: {:+.3}%",
-            emoji, market_time, shares.round_dp(0), direction, (entry_price * Decimal::from(100)).round_dp(1), btc_change
+            "{} <b>ENTRY</b>: {}\n{} {} @ {}¢\n{}: {:+.3}%",
+            emoji, market_time, shares.round_dp(0), direction, (entry_price * Decimal::from(100)).round_dp(1), asset.symbol(), spot_change
         );
         let _ = self.send(&msg, 0x00FF00).await;
     }
 }
+
+/// "BTC" or "BTC/ETH" for the bot's start/stop alerts
+fn asset_label(assets: &[Asset]) -> String {
+    assets.iter().map(|a| a.symbol()).collect::<Vec<_>>().join("/")
+}
@@ -0,0 +1,81 @@
+//! Fair-value calibration
+//!
+//! Joins logged market snapshots with settled window outcomes, fits the
+//! correction on the older windows, prints the uncalibrated model's Brier
+//! score and reliability table next to the fit's on the latest (held-out)
+//! windows, and saves the fit for the directional bot to load.
+//! Run with: cargo run --bin calibrate -- [asset] [log_dir] [output_path]
+//! (asset defaults to btc; the paths default to that asset's data dir and
+//! calibration path, as the bots use them)
+
+use anyhow::Result;
+use btc_arb_bot::calibration::{self, ReliabilityBin};
+use btc_arb_bot::config::AssetConfig;
+use btc_arb_bot::datalog::DataLogger;
+use btc_arb_bot::fair_value::FairValueModel;
+use btc_arb_bot::types::Asset;
+
+fn main() -> Result<()> {
+    dotenvy::dotenv().ok();
+    let mut args = std::env::args().skip(1).peekable();
+    let asset = match args.peek().and_then(|a| a.parse::<Asset>().ok()) {
+        Some(asset) => {
+            args.next();
+            asset
+        }
+        None => Asset::Btc,
+    };
+    let log_dir = args.next().unwrap_or_else(|| AssetConfig::data_dir(asset));
+    let output = args.next().unwrap_or_else(|| AssetConfig::calibration_path(asset));
+
+    println!("╔═══════════════════════════════════════════════════════════╗");
+    println!("║           FAIR-VALUE CALIBRATION ({})                    ║", asset);
+    println!("╚═══════════════════════════════════════════════════════════╝\n");
+
+    let mut snapshots = Vec::new();
+    for session in DataLogger::list_sessions(&log_dir)? {
+        snapshots.extend(DataLogger::read_snapshots(&log_dir, &session)?);
+    }
+    let outcomes = DataLogger::read_window_outcomes(&log_dir)?;
+    let samples = calibration::build_samples(&snapshots, &outcomes, &FairValueModel::default());
+    println!(
+        "{} snapshots, {} settled windows → {} samples\n",
+        snapshots.len(),
+        outcomes.len(),
+        samples.len()
+    );
+
+    let params = calibration::fit(&samples)?;
+    let (_, holdout) = calibration::split_by_time(&samples, calibration::HOLDOUT_FRACTION);
+    println!(
+        "Fit on the oldest {} windows: logit P(up) = {:.4} + {:.4}·z + {:.4}·z·minute/15",
+        params.windows, params.intercept, params.move_coef, params.move_minute_coef
+    );
+    println!(
+        "Out-of-sample Brier on the latest {} windows: model {:.4} → fitted {:.4}\n",
+        params.holdout_windows, params.holdout_brier_model, params.holdout_brier_fitted
+    );
+
+    print_reliability("Model (normal CDF), held out", &calibration::reliability(&calibration::model_predictions(&holdout), 10));
+    print_reliability("Fitted, held out", &calibration::reliability(&calibration::fitted_predictions(&holdout, &params), 10));
+
+    params.save(&output)?;
+    println!("Saved calibration to {}", output);
+    Ok(())
+}
+
+fn print_reliability(title: &str, bins: &[ReliabilityBin]) {
+    println!("═══ {} ═══", title);
+    println!("  bin          n    predicted  observed");
+    for bin in bins {
+        println!(
+            "  {:.1}-{:.1}  {:>6}    {:>6.1}%   {:>6.1}%",
+            bin.lo,
+            bin.hi,
+            bin.count,
+            bin.predicted * 100.0,
+            bin.observed * 100.0
+        );
+    }
+    println!();
+}
@@ -2,106 +2,57 @@
 //!
 //! This is the PRO TRADER REPLICATION strategy.
 //! Instead of buying BOTH sides (arbitrage), we buy ONE side
-//! based on observed BTC price direction.
+//! based on the observed spot price direction.
 //!
 //! Run with: cargo run --bin directional_bot --release
 //!
+//! Trades every asset in ASSETS (BTC by default) concurrently; each asset
+//! gets its own price feed, oracle tracker, position limit and data logs,
+//! and shares the CLOB client, signer, market socket and trade db.
+//!
 //! HOW IT WORKS:
-//! 1. Connect to Binance WebSocket for real-time BTC price
-//! 2. When new 15-min market opens, record BTC price
+//! 1. Stream the asset's spot price from the configured exchanges
+//! 2. When new 15-min market opens, record the spot price
 //! 3. Wait until late in the period (minute 10-13)
-//! 4. Observe if BTC is UP or DOWN from market open
+//! 4. Observe if the asset is UP or DOWN from market open
 //! 5. Buy the winning outcome BEFORE prices hit $1
 
 use anyhow::Result;
 use btc_arb_bot::{
     alerts::AlertClient,
-    btc_price::{BtcPriceFeed, spawn_btc_price_feed},
     clob::ClobClient,
-    config::Config,
+    config::{AssetConfig, Config},
+    datalog::{DataLogger, MarketSnapshot, PriceLevel, WindowOutcome},
+    event_bus::MarketStream,
+    fair_value::{edge, FairValueModel},
     market::MarketMonitor,
-    orderbook::OrderbookManager,
+    market_ws::MarketFeed,
+    orderbook::{OrderbookDepth, OrderbookManager},
+    reference_price::{self, spawn_reference_tracker, ReferenceTracker},
     signer::OrderSigner,
+    spot_price::{spawn_price_feed, SpotPriceFeed},
     strategies::directional::DirectionalConfig,
+    supervisor::Supervisor,
     trade_db::{TradeDb, TradeRecord},
-    types::BtcMarket,
-    websocket::{spawn_websocket_with_orderbook, WsEvent},
+    trade_tape::TradeTape,
+    types::{Asset, UpDownMarket},
+    websocket::WsEvent,
 };
+use futures_util::future::join_all;
 use parking_lot::Mutex;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
-use std::collections::VecDeque;
 use std::sync::Arc;
-use tracing::{debug, error, info, warn, Level};
+use tracing::{debug, error, info, info_span, warn, Instrument, Level};
 use tracing_subscriber::FmtSubscriber;
 
-/// Volatility tracker - stores recent BTC prices for volatility calculation
-struct VolatilityTracker {
-    prices: VecDeque<Decimal>,
-    max_samples: usize,
-}
-
-impl VolatilityTracker {
-    fn new(max_samples: usize) -> Self {
-        Self {
-            prices: VecDeque::with_capacity(max_samples),
-            max_samples,
-        }
-    }
-
-    fn add_price(&mut self, price: Decimal) {
-        if self.prices.len() >= self.max_samples {
-            self.prices.pop_front();
-        }
-        self.prices.push_back(price);
-    }
-
-    /// Calculate volatility as coefficient of variation (stddev/mean * 100)
-    fn volatility_pct(&self) -> Option<Decimal> {
-        if self.prices.len() < 10 {
-            return None;
-        }
-
-        let sum: Decimal = self.prices.iter().copied().sum();
-        let mean = sum / Decimal::from(self.prices.len());
-
-        if mean == Decimal::ZERO {
-            return None;
-        }
-
-        let variance_sum: Decimal = self.prices
-            .iter()
-            .map(|p| (*p - mean) * (*p - mean))
-            .sum();
-        let variance = variance_sum / Decimal::from(self.prices.len());
-
-        // Approximate sqrt using Newton's method
-        let stddev = sqrt_approx(variance);
-
-        Some(stddev / mean * dec!(100))
-    }
-}
-
-/// Approximate square root using Newton's method
-fn sqrt_approx(n: Decimal) -> Decimal {
-    if n <= Decimal::ZERO {
-        return Decimal::ZERO;
-    }
-
-    let mut x = n;
-    for _ in 0..10 {
-        x = (x + n / x) / dec!(2);
-    }
-    x
-}
-
 /// Calculate position size based on confidence level
 /// 15m-a4 STRATEGY: Enter with small moves, scale position by confidence
 fn confidence_position_sizing(
-    btc_change_pct: Decimal,
+    spot_change_pct: Decimal,
     account_balance: Decimal,
 ) -> (Decimal, &'static str, Decimal) {
-    let abs_change = btc_change_pct.abs();
+    let abs_change = spot_change_pct.abs();
 
     // Dynamic position sizing based on account balance and confidence
     // Uses conservative Kelly-inspired percentages:
@@ -220,11 +171,17 @@ async fn main() -> Result<()> {
     info!("╠═══════════════════════════════════════════════════╣");
     info!("║ Mode: {:42} ║", if config.dry_run { "DRY RUN (no real orders)" } else { "LIVE TRADING" });
     info!("║ Max position: ${:36} ║", config.max_position_usd);
+    let asset_list = config.assets.iter().map(|a| a.asset.symbol()).collect::<Vec<_>>().join(", ");
+    info!("║ Assets: {:40} ║", asset_list);
     info!("╚═══════════════════════════════════════════════════╝");
 
+    // Ctrl-C / SIGTERM stop entries, cancel resting orders and flush the trade db
+    let supervisor = Supervisor::install();
+
     // Initialize alert client
     let alerts = Arc::new(AlertClient::new(config.discord_webhook.clone()));
-    alerts.bot_started(config.dry_run).await;
+    let traded_assets: Vec<_> = config.assets.iter().map(|a| a.asset).collect();
+    alerts.bot_started(&traded_assets, config.dry_run).await;
 
     // Initialize trade database
     let db_path = std::env::var("TRADE_DB_PATH").unwrap_or_else(|_| "trades.db".to_string());
@@ -239,93 +196,179 @@ async fn main() -> Result<()> {
         }
     };
 
-    // Initialize volatility tracker (300 samples = ~5 minutes at 1/sec)
-    let volatility_tracker = Arc::new(Mutex::new(VolatilityTracker::new(300)));
+    // Per-asset price feed (KEY COMPONENT!), oracle tracker, strategy and logs
+    let mut assets = Vec::new();
+    for asset_config in &config.assets {
+        let asset = asset_config.asset;
+        info!("Connecting to {} price sources: {}", asset, asset_config.price_sources.join(", "));
+        let spot_feed = spawn_price_feed(asset, &asset_config.price_sources, &supervisor)?;
+
+        // Wait for the feed to connect; a signal meanwhile ends the wait
+        match supervisor.run(spot_feed.wait_for_price(std::time::Duration::from_secs(5))).await {
+            Some(true) => info!("{} price feed connected: ${}", asset, spot_feed.get_price().round_dp(2)),
+            Some(false) => warn!("{} price feed not connected yet, continuing...", asset),
+            None => {}
+        }
+
+        // Track the resolution oracle alongside the exchanges
+        let reference = spawn_reference_tracker(
+            reference_price::from_config(&config, asset_config, spot_feed.clone()).await?,
+            &supervisor,
+        );
 
-    // Initialize BTC price feed (KEY COMPONENT!)
-    info!("Connecting to Coinbase for real-time BTC price...");
-    let btc_feed = spawn_btc_price_feed();
+        let strategy_config = directional_config(&config, asset_config);
+        info!("{} strategy config:", asset);
+        info!("  Entry window: minute {:.0}-{:.0}", strategy_config.entry_minute_min, strategy_config.entry_minute_max);
+        info!("  Min confidence: {}%", strategy_config.min_confidence_pct);
+        info!("  Max entry price: ${}", strategy_config.max_entry_price);
+        info!("  Min edge: {}¢ after a {}bps taker fee", strategy_config.min_edge * dec!(100), config.taker_fee_bps);
+        info!("  Position size: 8-25% of ${} balance, capped at ${}", strategy_config.account_balance, strategy_config.max_position);
+        info!("  Laddering: {} levels @ {}¢ spacing", strategy_config.ladder_levels, strategy_config.ladder_spacing * dec!(100));
+        info!("  Single entry: minute 8-12, 100% position (like 15m-a4)");
+
+        // Fair value (calibrated if a fit exists) and the logs the next fit is built from
+        let fair_value = FairValueModel::load(&asset_config.calibration_path);
+        let data_logger = DataLogger::new(&asset_config.data_dir)?;
+        info!("{} data logging to {} (session: {})", asset, asset_config.data_dir, data_logger.session_id());
+
+        assets.push(AssetState {
+            asset,
+            spot_feed,
+            reference,
+            strategy_config,
+            market_monitor: MarketMonitor::new(config.clone(), asset),
+            fair_value,
+            data_logger,
+            // Aggressor flow on both tokens (last_trade_price prints)
+            trade_tape: TradeTape::new(std::time::Duration::from_secs(300)),
+        });
+    }
 
-    // Wait for BTC feed to connect
-    for _ in 0..50 {
-        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-        if btc_feed.get_price() > Decimal::ZERO {
-            break;
+    // Shared components
+    let orderbook_manager = Arc::new(OrderbookManager::new());
+    let clob = ClobClient::new(config.clone())?;
+    orderbook_manager.clone().spawn_resync_task(
+        Arc::new(ClobClient::new(config.clone())?),
+        std::time::Duration::from_secs(1),
+        &supervisor,
+    );
+    let signer = OrderSigner::from_config(&config).await?;
+
+    // Market channel connection(s) shared by every market we trade
+    let market_feed = MarketFeed::spawn_with_routes(orderbook_manager.clone(), &config.market_ws_route_list())?;
+
+    // One trading loop per asset, each log line tagged with its asset
+    let market_feed = &market_feed;
+    let loops = assets.iter().map(|state| {
+        let span = info_span!("asset", asset = %state.asset);
+        let ctx = DirectionalContext {
+            asset: state.asset,
+            config: &config,
+            clob: &clob,
+            signer: &signer,
+            alerts: &alerts,
+            trade_db: trade_db.as_deref(),
+            supervisor: &supervisor,
+            orderbook_manager: &orderbook_manager,
+            spot_feed: &state.spot_feed,
+            reference: &state.reference,
+            strategy_config: &state.strategy_config,
+            fair_value: &state.fair_value,
+            data_logger: &state.data_logger,
+            trade_tape: &state.trade_tape,
+        };
+        async move { run_directional_loop(&ctx, &state.market_monitor, market_feed).await }.instrument(span)
+    });
+    join_all(loops).await;
+    for state in &assets {
+        if let Err(e) = state.data_logger.flush() {
+            warn!("Failed to flush {} data logs: {}", state.asset, e);
         }
     }
 
-    if btc_feed.get_price() > Decimal::ZERO {
-        info!("BTC price feed connected: ${}", btc_feed.get_price().round_dp(2));
-    } else {
-        warn!("BTC price feed not connected yet, continuing...");
+    // Shutdown: the sessions have already cancelled their orders
+    info!("Shutting down...");
+    market_feed.shutdown().await;
+    if let Some(db) = &trade_db {
+        if let Err(e) = db.lock().flush() {
+            warn!("Failed to flush trade database: {}", e);
+        }
     }
+    let reason = supervisor.reason().unwrap_or_else(|| "shutdown".to_string());
+    alerts.bot_stopped(&traded_assets, &format!("Stopped on {}", reason)).await;
+    supervisor.shutdown(std::time::Duration::from_secs(5)).await;
+    info!("Shutdown complete");
+    Ok(())
+}
 
-    // Initialize strategy - 15m-a4 EXACT LOGIC
-    // Analysis: 15m-a4 trades 1,822 times with $161K profit = $88.50/trade
-    // Entry prices 5-74¢ = they enter as EARLY as minute 3
-    // Fractional avgPrice = LADDERING (multiple orders at different prices)
-    let strategy_config = DirectionalConfig {
+/// Strategy settings for one asset - 15m-a4 EXACT LOGIC
+/// Analysis: 15m-a4 trades 1,822 times with $161K profit = $88.50/trade
+/// Entry prices 5-74¢ = they enter as EARLY as minute 3
+/// Fractional avgPrice = LADDERING (multiple orders at different prices)
+fn directional_config(config: &Config, asset_config: &AssetConfig) -> DirectionalConfig {
+    DirectionalConfig {
         entry_minute_min: 3.0,   // Enter from minute 3 (catches 15-50¢ prices)
         entry_minute_max: 13.5,  // Stop by minute 13.5 (safety buffer)
         min_confidence_pct: dec!(0.03), // Balanced: 0.03% catches most wins, avoids weak signals
         max_entry_price: dec!(0.90), // Allow up to 90¢ (15m-a4 trades 5-74¢, compensates with size)
-        position_size: config.max_position_usd,
-        max_position: config.max_position_usd,
+        position_size: asset_config.max_position_usd,
+        max_position: asset_config.max_position_usd,
+        account_balance: asset_config.account_balance,
         use_limit_orders: true,
         limit_offset: dec!(0.02), // 2 cents below best ask
         ladder_levels: 5,        // 5 price levels like pro traders
         ladder_spacing: dec!(0.02), // 2¢ between levels
-    };
-
-    info!("Strategy config:");
-    info!("  Entry window: minute {:.0}-{:.0}", strategy_config.entry_minute_min, strategy_config.entry_minute_max);
-    info!("  Min confidence: {}%", strategy_config.min_confidence_pct);
-    info!("  Max entry price: ${}", strategy_config.max_entry_price);
-    info!("  Position size: ${}", strategy_config.position_size);
-    info!("  Laddering: {} levels @ {}¢ spacing", strategy_config.ladder_levels, strategy_config.ladder_spacing * dec!(100));
-    info!("  Single entry: minute 8-12, 100% position (like 15m-a4)");
-
-    // Initialize components
-    let market_monitor = MarketMonitor::new(config.clone());
-    let orderbook_manager = Arc::new(OrderbookManager::new());
-    let clob = ClobClient::new(config.clone())?;
-    let signer = OrderSigner::new(&config.private_key, &config.address)?;
-
-    // Main trading loop
-    run_directional_loop(
-        config,
-        btc_feed,
-        strategy_config,
-        market_monitor,
-        orderbook_manager,
-        clob,
-        signer,
-        alerts,
-        trade_db,
-        volatility_tracker,
-    ).await
+        max_adverse_flow: dec!(0.6), // Skip if 60%+ of recent volume is selling our side
+        min_flow_trades: 5,
+        min_edge: dec!(0.03), // Fair value must beat the ask plus fee by 3¢
+        taker_fee_rate: config.taker_fee_bps / dec!(10000),
+        max_source_divergence_bps: dec!(20), // Skip if a USD exchange is 0.2% off the composite
+    }
 }
 
-/// Main directional trading loop
-async fn run_directional_loop(
-    config: Config,
-    btc_feed: Arc<BtcPriceFeed>,
+/// One traded asset's feeds, strategy and logs
+struct AssetState {
+    asset: Asset,
+    spot_feed: Arc<SpotPriceFeed>,
+    reference: Arc<ReferenceTracker>,
     strategy_config: DirectionalConfig,
     market_monitor: MarketMonitor,
-    orderbook_manager: Arc<OrderbookManager>,
-    clob: ClobClient,
-    signer: OrderSigner,
-    alerts: Arc<AlertClient>,
-    trade_db: Option<Arc<Mutex<TradeDb>>>,
-    volatility_tracker: Arc<Mutex<VolatilityTracker>>,
-) -> Result<()> {
-    loop {
+    fair_value: FairValueModel,
+    data_logger: DataLogger,
+    trade_tape: TradeTape,
+}
+
+/// Shared clients plus one asset's state, as a trading loop and its sessions see them
+struct DirectionalContext<'a> {
+    asset: Asset,
+    config: &'a Config,
+    clob: &'a ClobClient,
+    signer: &'a OrderSigner,
+    alerts: &'a AlertClient,
+    trade_db: Option<&'a Mutex<TradeDb>>,
+    supervisor: &'a Supervisor,
+    orderbook_manager: &'a OrderbookManager,
+    spot_feed: &'a SpotPriceFeed,
+    reference: &'a ReferenceTracker,
+    strategy_config: &'a DirectionalConfig,
+    fair_value: &'a FairValueModel,
+    data_logger: &'a DataLogger,
+    trade_tape: &'a TradeTape,
+}
+
+/// Trading loop for one asset's markets
+async fn run_directional_loop(ctx: &DirectionalContext<'_>, market_monitor: &MarketMonitor, market_feed: &MarketFeed) {
+    let DirectionalContext { asset, alerts, supervisor, spot_feed, trade_tape, .. } = *ctx;
+
+    while !supervisor.is_shutting_down() {
         info!("═══════════════════════════════════════════════════");
-        info!("Searching for active BTC 15-min market...");
-        info!("Current BTC price: ${}", btc_feed.get_price().round_dp(2));
+        info!("Searching for active {} 15-min market...", asset);
+        info!("Current {} price: ${}", asset, spot_feed.get_price().round_dp(2));
 
         // Poll for market
-        let market = market_monitor.wait_for_next_market().await;
+        let Some(market) = supervisor.run(market_monitor.wait_for_next_market()).await else {
+            break;
+        };
 
         info!("Found market: {}", market.title);
         info!("  UP token:   {}", market.up_token_id);
@@ -334,54 +377,69 @@ async fn run_directional_loop(
 
         alerts.market_found(&market.title, &market.end_time.to_string()).await;
 
-        // Start WebSocket for this market's orderbooks
-        let market_ws_rx = spawn_websocket_with_orderbook(
-            config.clone(),
-            vec![market.up_token_id.clone(), market.down_token_id.clone()],
-            orderbook_manager.clone(),
-        );
+        // Stream this market's orderbooks on the shared connection
+        let market_ws_rx = market_feed.subscribe(&market);
 
         // Run directional trading session
-        if let Err(e) = run_directional_session(
-            &config,
-            btc_feed.clone(),
-            &strategy_config,
-            &market,
-            orderbook_manager.clone(),
-            &clob,
-            &signer,
-            alerts.clone(),
-            trade_db.clone(),
-            volatility_tracker.clone(),
-            market_ws_rx,
-        ).await {
+        if let Err(e) = run_directional_session(ctx, &market, market_ws_rx).await {
             error!("Session error: {}", e);
             alerts.error("Session failed", &e.to_string()).await;
         }
+        market_feed.unsubscribe(&market.condition_id);
+        market_feed.log_route_stats();
+        spot_feed.log_source_stats();
+        // Market rolled over: its tokens never trade again
+        trade_tape.clear(&market.up_token_id);
+        trade_tape.clear(&market.down_token_id);
 
         // Wait before next market
-        tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+        supervisor.run(tokio::time::sleep(tokio::time::Duration::from_secs(5))).await;
     }
 }
 
 /// Run a directional trading session
-async fn run_directional_session(
-    config: &Config,
-    btc_feed: Arc<BtcPriceFeed>,
-    strategy_config: &DirectionalConfig,
-    market: &BtcMarket,
-    orderbook_manager: Arc<OrderbookManager>,
-    clob: &ClobClient,
-    signer: &OrderSigner,
-    alerts: Arc<AlertClient>,
-    trade_db: Option<Arc<Mutex<TradeDb>>>,
-    volatility_tracker: Arc<Mutex<VolatilityTracker>>,
-    mut ws_rx: tokio::sync::mpsc::Receiver<WsEvent>,
-) -> Result<()> {
-    // Mark market open BTC price
-    btc_feed.mark_market_open();
-    let open_price = btc_feed.get_price();
-    info!("Market open BTC price: ${}", open_price.round_dp(2));
+async fn run_directional_session(ctx: &DirectionalContext<'_>, market: &UpDownMarket, mut ws_rx: MarketStream) -> Result<()> {
+    let DirectionalContext {
+        asset,
+        config,
+        clob,
+        signer,
+        alerts,
+        trade_db,
+        supervisor,
+        orderbook_manager,
+        spot_feed,
+        reference,
+        strategy_config,
+        fair_value,
+        data_logger,
+        trade_tape,
+    } = *ctx;
+
+    // Mark market open spot price
+    spot_feed.mark_market_open(market.window_start());
+    let open_price = spot_feed.get_market_open_price().unwrap_or_else(|| spot_feed.get_price());
+    info!("Market open {} price: ${}", asset, open_price.round_dp(2));
+    if let Some(last) = reference.last_settled() {
+        let print = |r: &Option<reference_price::OracleReport>| {
+            r.as_ref().map(|r| format!("${}", r.price.round_dp(2))).unwrap_or_else(|| "-".to_string())
+        };
+        info!(
+            "Oracle last window: open {} → close {} = {}",
+            print(&last.open),
+            print(&last.close),
+            match last.outcome() { Some(true) => "UP", Some(false) => "DOWN", None => "?" }
+        );
+        if let (Some(open), Some(close)) = (&last.open, &last.close) {
+            let _ = data_logger.log_window_outcome(&WindowOutcome {
+                window_start: last.window_start,
+                open: open.price,
+                close: close.price,
+                up_won: close.price >= open.price,
+                source: config.reference_price_source.clone(),
+            });
+        }
+    }
 
     // Wait for WebSocket connection
     let mut connected = false;
@@ -400,16 +458,20 @@ async fn run_directional_session(
                         connected = true;
                         info!("WebSocket connected");
                     }
-                    WsEvent::OrderbookUpdate { .. } => {
+                    WsEvent::Book(_) => {
                         connected = true;
                     }
                     _ => {}
                 }
             }
+            _ = supervisor.cancelled() => break,
             _ = tokio::time::sleep(tokio::time::Duration::from_millis(100)) => {}
         }
     }
 
+    // Orders are signed on the market's current grid, which moves near the extremes
+    let mut tick_size = orderbook_manager.market_tick_size(market);
+
     // State
     let mut has_entered = false;
     let mut position_shares = Decimal::ZERO;
@@ -424,7 +486,7 @@ async fn run_directional_session(
     // Entry 1: minute 5-6 (33% of position)
     // SINGLE ENTRY (like 15m-a4) - wait until confident, then go all-in
     // 15m-a4 doesn't use DCA - they make ONE concentrated entry when outcome is clear
-    // By minute 8-12, BTC direction is 90%+ certain
+    // By minute 8-12, the spot direction is 90%+ certain
     let mut entry_count = 0u32;
     const DCA_WINDOWS: [(f64, f64, Decimal); 1] = [
         (8.0, 12.0, dec!(1.00)),  // Single entry: minute 8-12, 100% of position
@@ -445,6 +507,8 @@ async fn run_directional_session(
 
     // Timer for entry checks (every 500ms)
     let mut entry_check_interval = tokio::time::interval(tokio::time::Duration::from_millis(500));
+    let mut entry_checks = 0u64;
+    let mut last_status_log = 0u64;
 
     loop {
         let now = chrono::Utc::now();
@@ -457,19 +521,24 @@ async fn run_directional_session(
         let seconds_to_end = (end_time - now).num_seconds();
         let minute_of_period = 15.0 - (seconds_to_end as f64 / 60.0);
 
-        // Get current BTC state
-        let btc_price = btc_feed.get_price();
-        let _btc_change = btc_feed.get_price_change();
-        let btc_change_pct = btc_feed.get_price_change_pct();
-        let btc_is_up = btc_feed.get_predicted_outcome();
+        // Get current spot state
+        let spot_price = spot_feed.get_price();
+        let _spot_change = spot_feed.get_price_change();
+        let spot_change_pct = spot_feed.get_price_change_pct();
+        let spot_is_up = spot_feed.get_predicted_outcome();
 
         tokio::select! {
+            _ = supervisor.cancelled() => {
+                info!("Shutdown requested, no new entries");
+                break;
+            }
+
             // Process WebSocket events (for orderbook updates)
             Some(event) = ws_rx.recv() => {
                 match event {
-                    WsEvent::OrderbookUpdate { asset_id, .. } => {
-                        // Just update orderbook manager (already done in websocket.rs)
-                        debug!("Orderbook update for {}", asset_id);
+                    WsEvent::Book(top) => {
+                        // Books are read from the orderbook manager at entry time
+                        debug!("Book {}: bid={:?}, ask={:?} (v{})", top.asset_id, top.best_bid, top.best_ask, top.version);
                     }
                     WsEvent::TradeFill(fill) => {
                         info!("FILL: {} shares @ ${}", fill.size, fill.price);
@@ -477,20 +546,47 @@ async fn run_directional_session(
                     WsEvent::Disconnected => {
                         warn!("WebSocket disconnected");
                     }
-                    WsEvent::PriceChange { asset_id, best_bid, best_ask } => {
-                        debug!("Price change for {}: bid={}, ask={}", asset_id, best_bid, best_ask);
+                    WsEvent::BookUnreliable { asset_id, reason } => {
+                        warn!("Book {} unreliable ({}), waiting for resync", asset_id, reason);
+                    }
+                    WsEvent::LastTrade(print) => {
+                        trade_tape.record(print);
+                    }
+                    WsEvent::TickSizeChange(change) => {
+                        // The manager has already recorded the new tick
+                        let new_tick = orderbook_manager.market_tick_size(market);
+                        if new_tick == tick_size {
+                            continue;
+                        }
+                        info!("Tick size {} -> {} ({} -> {} on {})", tick_size, new_tick,
+                            change.old_tick_size, change.new_tick_size, &change.asset_id[..change.asset_id.len().min(16)]);
+                        tick_size = new_tick;
+
+                        // Resting ladder was priced on the old grid
+                        if has_entered && !config.dry_run {
+                            match requote_on_tick(clob, signer, market, tick_size).await {
+                                Ok(placed) => info!("Re-quoted {} resting orders on the {} tick", placed, tick_size),
+                                Err(e) => {
+                                    error!("Re-quote after tick size change failed: {}", e);
+                                    alerts.error("Ladder re-quote failed", &e.to_string()).await;
+                                }
+                            }
+                        }
                     }
                     _ => {}
                 }
             }
 
             // TIMER-BASED ENTRY CHECK (runs every 500ms)
-            _ = entry_check_interval.tick() => {
-                // Update volatility tracker
-                volatility_tracker.lock().add_price(btc_price);
+            _ = entry_check_interval.tick(), if !supervisor.is_shutting_down() => {
+                // Snapshot book + spot state every 5s for fair-value calibration
+                entry_checks += 1;
+                if entry_checks.is_multiple_of(10) {
+                    log_snapshot(data_logger, market, orderbook_manager, spot_feed, fair_value, seconds_to_end);
+                }
 
                 // PRO TRADER: Track direction reversals
-                let was_reversal = reversal_tracker.update(btc_is_up);
+                let was_reversal = reversal_tracker.update(spot_is_up);
                 if was_reversal {
                     debug!("Direction reversal detected! Count: {}", reversal_tracker.reversal_count);
                 }
@@ -514,7 +610,7 @@ async fn run_directional_session(
                     // For DCA entries 2 & 3: Check if direction still matches first entry
                     if entry_count > 0 {
                         if let Some(first_direction) = predicted_outcome {
-                            let current_direction = btc_is_up.unwrap_or(false);
+                            let current_direction = spot_is_up.unwrap_or(false);
                             if current_direction != first_direction {
                                 info!("⏭️ DCA SKIP: Direction reversed (was {}, now {})",
                                     if first_direction { "UP" } else { "DOWN" },
@@ -538,12 +634,16 @@ async fn run_directional_session(
                     if let Some(spread) = orderbook_manager.get_combined_spread(
                         &market.up_token_id, &market.down_token_id
                     ) {
-                        // Check BTC direction
-                        if let Some(is_up) = btc_is_up {
-                            let pct = btc_change_pct.unwrap_or(Decimal::ZERO);
-
-                            // VOLATILITY CHECK
-                            let volatility = volatility_tracker.lock().volatility_pct();
+                        // Check spot direction
+                        if let Some(is_up) = spot_is_up {
+                            let pct = spot_change_pct.unwrap_or(Decimal::ZERO);
+
+                            // VOLATILITY CHECK (realized 5m volatility from 1s candles)
+                            // Thresholds are 5m realised vol in %, not the CV% of recent
+                            // prices this check used before: typical BTC runs ~0.15%
+                            // (FairValueModel's default), so 0.5% is roughly 3x normal and
+                            // 0.01% is a dead tape. Not yet retuned on logged data.
+                            let volatility = spot_feed.indicators().vol_5m_pct;
                             if let Some(vol) = volatility {
                                 if vol > dec!(0.5) {
                                     skip_reason = Some(format!("Volatility too high ({:.3}%)", vol));
@@ -558,18 +658,18 @@ async fn run_directional_session(
                             }
 
                             // MOMENTUM CHECK (PRO UPGRADE)
-                            let momentum_aligned = btc_feed.is_momentum_aligned();
-                            let momentum_conf = btc_feed.get_momentum_confidence();
+                            let momentum_aligned = spot_feed.is_momentum_aligned();
+                            let momentum_conf = spot_feed.get_momentum_confidence();
                             if !momentum_aligned && minute_of_period < 10.0 {
                                 skip_reason = Some(format!("Momentum not aligned (min {:.1})", minute_of_period));
                                 info!("⏭️ SKIP: {}", skip_reason.as_ref().unwrap());
                                 continue;
                             }
 
-                            // CONFIDENCE-BASED POSITION SIZING (% of account balance)
+                            // CONFIDENCE-BASED POSITION SIZING (% of this asset's share of the balance)
                             let (full_position_size, confidence_level, risk_pct) = confidence_position_sizing(
                                 pct,
-                                config.account_balance,
+                                strategy_config.account_balance,
                             );
 
                             if full_position_size == Decimal::ZERO {
@@ -589,11 +689,56 @@ async fn run_directional_session(
                                     ("DOWN", spread.down_best_ask, &market.down_token_id)
                                 };
 
+                                // SOURCE CHECK: USD exchanges must agree on the side and the price
+                                if !spot_feed.sources_agree() {
+                                    skip_reason = Some("Exchanges disagree on direction".to_string());
+                                    info!("⏭️ SKIP: {}", skip_reason.as_ref().unwrap());
+                                    continue;
+                                }
+                                if let Some(divergence) = spot_feed.max_divergence_bps() {
+                                    if divergence > strategy_config.max_source_divergence_bps {
+                                        skip_reason = Some(format!("Exchanges diverge by {}bps", divergence));
+                                        info!("⏭️ SKIP: {}", skip_reason.as_ref().unwrap());
+                                        continue;
+                                    }
+                                }
+
+                                // ORDER FLOW CHECK: don't buy into aggressive selling
+                                let flow = trade_tape.stats(token_id, std::time::Duration::from_secs(60));
+                                if flow.is_adverse_for_buyer(strategy_config.max_adverse_flow, strategy_config.min_flow_trades) {
+                                    skip_reason = Some(format!("Adverse {} flow ({:.2} over {} trades)", outcome, flow.imbalance, flow.trades));
+                                    info!("⏭️ SKIP: {}", skip_reason.as_ref().unwrap());
+                                    continue;
+                                }
+
+                                // EDGE CHECK: fair value from move, time left and vol must beat the ask
+                                let fair_up = spot_feed.fair_up_prob(fair_value, seconds_to_end);
+                                let Some(fair) = fair_up.map(|p| if is_up { p } else { Decimal::ONE - p }) else {
+                                    skip_reason = Some("No fair value for this window".to_string());
+                                    info!("⏭️ SKIP: {}", skip_reason.as_ref().unwrap());
+                                    continue;
+                                };
+                                let edge = edge(fair, best_ask, strategy_config.taker_fee_rate);
+                                if edge < strategy_config.min_edge {
+                                    skip_reason = Some(format!("No edge after fees (fair {} vs ask {})", fair, best_ask));
+                                    info!("⏭️ SKIP: {}", skip_reason.as_ref().unwrap());
+                                    continue;
+                                }
+
                                 // Check price is acceptable
                                 if best_ask <= strategy_config.max_entry_price {
                                     // PRICE-SCALED POSITION SIZING
-                                    // Higher entry price = lower ROI = need MORE shares
-                                    let scaled_position = scale_position_by_price(position_size, best_ask);
+                                    // Higher entry price = lower ROI = need MORE shares,
+                                    // but never past this asset's position limit
+                                    let scaled_position = strategy_config.cap_stake(
+                                        scale_position_by_price(position_size, best_ask),
+                                        position_cost,
+                                    );
+                                    if scaled_position == Decimal::ZERO {
+                                        skip_reason = Some(format!("Position limit reached (${})", strategy_config.max_position));
+                                        info!("⏭️ SKIP: {}", skip_reason.as_ref().unwrap());
+                                        continue;
+                                    }
                                     let price_scale = if position_size > Decimal::ZERO {
                                         (scaled_position / position_size * dec!(100)).round_dp(0)
                                     } else {
@@ -603,13 +748,20 @@ async fn run_directional_session(
                                     info!("╔═══════════════════════════════════════════════════╗");
                                     info!("║      DCA ENTRY {} of 2 DETECTED!                   ║", entry_count + 1);
                                     info!("╚═══════════════════════════════════════════════════╝");
-                                    info!("  BTC: ${} ({:+.4}% from open)", btc_price.round_dp(2), pct);
+                                    info!("  {}: ${} ({:+.4}% from open)", asset, spot_price.round_dp(2), pct);
                                     info!("  Direction: {}", outcome);
                                     info!("  Best ask: {}¢ → {}% size", (best_ask * dec!(100)).round_dp(0), price_scale);
                                     info!("  Minute: {:.1}", minute_of_period);
                                     info!("  DCA: Entry {}/2 ({}%)", entry_count + 1, (dca_pct * dec!(100)).round_dp(0));
                                     info!("  Confidence: {} | Momentum: {:.1}", confidence_level, momentum_conf);
+                                    info!("  {}", spot_feed.flow());
+                                    info!("  Fair value: {}¢ → edge {}¢", (fair * dec!(100)).round_dp(1), (edge * dec!(100)).round_dp(1));
+                                    info!("  Flow 60s: {} trades, imbalance {:+.2}, VWAP {}", flow.trades, flow.imbalance,
+                                        flow.vwap.map(|v| v.round_dp(3).to_string()).unwrap_or_else(|| "-".to_string()));
                                     info!("  Position: ${:.2} base → ${:.2} scaled", position_size, scaled_position);
+                                    if let Some(basis) = reference.basis_bps(spot_price) {
+                                        info!("  Oracle basis: {:+}bps", basis);
+                                    }
                                     if let Some(vol) = volatility {
                                         info!("  Volatility: {:.4}%", vol);
                                     }
@@ -630,10 +782,11 @@ async fn run_directional_session(
                                         // Calculate price for this level
                                         let level_offset = strategy_config.ladder_spacing * Decimal::from(level);
                                         let level_price = if strategy_config.use_limit_orders {
-                                            (best_ask - strategy_config.limit_offset - level_offset).max(dec!(0.01))
+                                            best_ask - strategy_config.limit_offset - level_offset
                                         } else {
-                                            (best_ask - level_offset).max(dec!(0.01))
+                                            best_ask - level_offset
                                         };
+                                        let level_price = floor_to_tick(level_price, tick_size);
                                         let level_shares = size_per_level / level_price;
 
                                         if config.dry_run {
@@ -649,7 +802,7 @@ async fn run_directional_session(
                                                 token_id,
                                                 level_price,
                                                 level_shares,
-                                                market.tick_size,
+                                                tick_size,
                                                 market.neg_risk,
                                             ).await {
                                                 Ok(order_id) => {
@@ -695,10 +848,10 @@ async fn run_directional_session(
                                             position_shares.round_dp(0), position_cost);
 
                                         // Send Telegram alert for entry
-                                        alerts.market_entry(&market_time, outcome, this_entry_price, total_shares, pct).await;
+                                        alerts.market_entry(asset, &market_time, outcome, this_entry_price, total_shares, pct).await;
 
                                         // Log to database
-                                        if let Some(ref db) = trade_db {
+                                        if let Some(db) = trade_db {
                                             let record = TradeRecord {
                                                 timestamp: chrono::Utc::now(),
                                                 market_id: market.condition_id.clone(),
@@ -707,7 +860,7 @@ async fn run_directional_session(
                                                 entry_price,
                                                 shares: total_shares,
                                                 btc_open_price: open_price,
-                                                btc_entry_price: btc_price,
+                                                btc_entry_price: spot_price,
                                                 btc_change_pct: pct,
                                                 confidence_score: pct.abs() * dec!(100),
                                                 minute_of_entry,
@@ -731,7 +884,7 @@ async fn run_directional_session(
                                     info!("⏭️ SKIP: {}", skip_reason.as_ref().unwrap());
                                 }
                             } else {
-                                skip_reason = Some(format!("BTC move too small ({:.4}%)", pct.abs()));
+                                skip_reason = Some(format!("{} move too small ({:.4}%)", asset, pct.abs()));
                                 info!("⏭️ SKIP: {}", skip_reason.as_ref().unwrap());
                             }
                         }
@@ -743,31 +896,32 @@ async fn run_directional_session(
             _ = tokio::time::sleep(tokio::time::Duration::from_secs(1)) => {
                 // Periodic status
                 if minute_of_period >= 0.0 {
-                    let btc_dir = match btc_is_up {
+                    let spot_dir = match spot_is_up {
                         Some(true) => "UP",
                         Some(false) => "DOWN",
                         None => "FLAT",
                     };
-                    let pct = btc_change_pct.unwrap_or(Decimal::ZERO);
+                    let pct = spot_change_pct.unwrap_or(Decimal::ZERO);
 
                     // Only log every 30 seconds or when in entry window
-                    static LAST_LOG: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+                    // (per session, so concurrent assets don't throttle each other)
                     let now_secs = std::time::SystemTime::now()
                         .duration_since(std::time::UNIX_EPOCH)
                         .map(|d| d.as_secs())
                         .unwrap_or(0);
 
-                    let should_log = now_secs - LAST_LOG.load(std::sync::atomic::Ordering::Relaxed) >= 30
+                    let should_log = now_secs - last_status_log >= 30
                         || (minute_of_period >= strategy_config.entry_minute_min && !has_entered);
 
                     if should_log {
-                        LAST_LOG.store(now_secs, std::sync::atomic::Ordering::Relaxed);
+                        last_status_log = now_secs;
                         info!(
-                            "Minute {:.1}: BTC ${} ({:+.4}%) = {} | Position: {} shares @ ${}",
+                            "Minute {:.1}: {} ${} ({:+.4}%) = {} | Position: {} shares @ ${}",
                             minute_of_period,
-                            btc_price.round_dp(2),
+                            asset,
+                            spot_price.round_dp(2),
                             pct,
-                            btc_dir,
+                            spot_dir,
                             position_shares.round_dp(0),
                             position_cost.round_dp(2)
                         );
@@ -777,18 +931,48 @@ async fn run_directional_session(
         }
     }
 
+    if supervisor.is_shutting_down() {
+        // Resting ladder orders must not outlive the process
+        if !config.dry_run {
+            info!("Cancelling open orders for {}...", market.condition_id);
+            if let Err(e) = clob.cancel_market_orders(&market.condition_id).await {
+                error!("Failed to cancel orders: {}", e);
+                alerts.error("Order cancellation on shutdown failed", &e.to_string()).await;
+            }
+        }
+        spot_feed.clear_market_open();
+        return Ok(());
+    }
+
     // Final summary
     info!("╔═══════════════════════════════════════════════════╗");
     info!("║           SESSION SUMMARY                         ║");
     info!("╚═══════════════════════════════════════════════════╝");
 
-    let btc_final = btc_feed.get_price();
-    let btc_change = btc_feed.get_price_change().unwrap_or(Decimal::ZERO);
-    let outcome = if btc_change > Decimal::ZERO { "UP" } else { "DOWN" };
+    let spot_final = spot_feed.get_price();
+    let spot_change = spot_feed.get_price_change().unwrap_or(Decimal::ZERO);
+    let outcome = if spot_change > Decimal::ZERO { "UP" } else { "DOWN" };
 
-    info!("BTC: ${} → ${} ({:+.2})", open_price.round_dp(2), btc_final.round_dp(2), btc_change);
+    info!("{}: ${} → ${} ({:+.2})", asset, open_price.round_dp(2), spot_final.round_dp(2), spot_change);
     info!("Actual outcome: {}", outcome);
 
+    // The market settles on the oracle: flag when it's leaning the other way
+    let window_start = market.window_start();
+    let oracle_open = reference.window(window_start).and_then(|w| w.open);
+    if let (Some(open), Some(latest)) = (oracle_open, reference.latest()) {
+        let oracle_outcome = if latest.price >= open.price { "UP" } else { "DOWN" };
+        info!(
+            "Oracle: ${} → ${} = {} | basis {}",
+            open.price.round_dp(2),
+            latest.price.round_dp(2),
+            oracle_outcome,
+            reference.basis_bps(spot_final).map(|b| format!("{:+}bps", b)).unwrap_or_else(|| "-".to_string())
+        );
+        if oracle_outcome != outcome {
+            warn!("Oracle and exchanges disagree on the outcome ({} vs {})", oracle_outcome, outcome);
+        }
+    }
+
     if has_entered {
         let predicted = if predicted_outcome.unwrap_or(false) { "UP" } else { "DOWN" };
         let won = predicted == outcome;
@@ -810,7 +994,7 @@ async fn run_directional_session(
         };
 
         // Update trade database with outcome
-        if let (Some(ref db), Some(id)) = (&trade_db, trade_record_id) {
+        if let (Some(db), Some(id)) = (trade_db, trade_record_id) {
             let outcome_str = if won { "WIN" } else { "LOSS" };
             if let Err(e) = db.lock().update_outcome(id, outcome_str, profit) {
                 warn!("Failed to update trade outcome: {}", e);
@@ -827,27 +1011,113 @@ async fn run_directional_session(
     } else {
         info!("No position taken this session");
         // Send skip reason to Telegram
-        let final_btc_change = btc_feed.get_price_change_pct().unwrap_or(Decimal::ZERO);
+        let final_spot_change = spot_feed.get_price_change_pct().unwrap_or(Decimal::ZERO);
         let reason = skip_reason.unwrap_or_else(|| "No clear signal".to_string());
-        alerts.market_skipped(&market_time, &reason, final_btc_change).await;
+        alerts.market_skipped(asset, &market_time, &reason, final_spot_change).await;
     }
 
     // Clear market open price
-    btc_feed.clear_market_open();
+    spot_feed.clear_market_open();
 
     // Wait for resolution
     let time_to_resolution = (end_time - chrono::Utc::now()).num_seconds();
     if time_to_resolution > 0 {
         info!("Waiting {} seconds for resolution...", time_to_resolution);
-        tokio::time::sleep(tokio::time::Duration::from_secs(
+        supervisor.run(tokio::time::sleep(tokio::time::Duration::from_secs(
             (time_to_resolution + 30) as u64
-        )).await;
+        ))).await;
     }
 
     Ok(())
 }
 
+/// Log the market's book with the spot state behind our fair value
+fn log_snapshot(
+    data_logger: &DataLogger,
+    market: &UpDownMarket,
+    orderbook_manager: &OrderbookManager,
+    spot_feed: &SpotPriceFeed,
+    fair_value: &FairValueModel,
+    seconds_to_end: i64,
+) {
+    let Some(spread) = orderbook_manager.get_combined_spread(&market.up_token_id, &market.down_token_id) else {
+        return;
+    };
+    let depth = orderbook_manager.get_depth(&market.up_token_id, &market.down_token_id, 5);
+    let levels = |side: fn(&OrderbookDepth) -> &Vec<(Decimal, Decimal)>| {
+        depth.as_ref()
+            .map(|d| side(d).iter().map(|(p, s)| PriceLevel { price: *p, size: *s }).collect())
+            .unwrap_or_default()
+    };
+    let state = spot_feed.state();
+    let (spot_price, spot_open) = {
+        let s = state.read();
+        (s.current_price, s.market_open_price)
+    };
+    let _ = data_logger.log_market_snapshot(&MarketSnapshot {
+        timestamp: chrono::Utc::now(),
+        market_id: market.condition_id.clone(),
+        market_title: market.title.clone(),
+        end_time: market.end_time,
+        up_token_id: market.up_token_id.clone(),
+        down_token_id: market.down_token_id.clone(),
+        up_best_bid: depth.as_ref().and_then(|d| d.up_bids.first().map(|(p, _)| *p)),
+        up_best_ask: Some(spread.up_best_ask),
+        down_best_bid: depth.as_ref().and_then(|d| d.down_bids.first().map(|(p, _)| *p)),
+        down_best_ask: Some(spread.down_best_ask),
+        combined_ask: Some(spread.up_best_ask + spread.down_best_ask),
+        spread_pct: Some(spread.spread_pct),
+        up_asks: levels(|d| &d.up_asks),
+        up_bids: levels(|d| &d.up_bids),
+        down_asks: levels(|d| &d.down_asks),
+        down_bids: levels(|d| &d.down_bids),
+        spot_price: Some(spot_price),
+        spot_open,
+        spot_vol_5m_pct: spot_feed.indicators().vol_5m_pct,
+        fair_up_prob: spot_feed.fair_up_prob(fair_value, seconds_to_end),
+    });
+}
+
 /// Create and submit an order
+/// Highest price on the `tick_size` grid at or below `price`, at least one tick
+fn floor_to_tick(price: Decimal, tick_size: Decimal) -> Decimal {
+    ((price / tick_size).floor() * tick_size).max(tick_size)
+}
+
+/// Cancel the market's resting orders and place their unfilled remainder on
+/// the `tick_size` grid (buys round down), returning how many were placed
+async fn requote_on_tick(clob: &ClobClient, signer: &OrderSigner, market: &UpDownMarket, tick_size: Decimal) -> Result<usize> {
+    let decimal = |o: &serde_json::Value, key: &str| o[key].as_str().and_then(|v| v.parse::<Decimal>().ok());
+    let resting: Vec<(String, Decimal, Decimal)> = clob
+        .get_open_orders()
+        .await?
+        .iter()
+        .filter(|o| o["market"].as_str() == Some(market.condition_id.as_str()))
+        .filter_map(|o| {
+            let remaining = decimal(o, "original_size")? - decimal(o, "size_matched").unwrap_or_default();
+            Some((o["asset_id"].as_str()?.to_string(), decimal(o, "price")?, remaining))
+        })
+        .filter(|(_, _, remaining)| *remaining > Decimal::ZERO)
+        .collect();
+    if resting.is_empty() {
+        return Ok(0);
+    }
+
+    clob.cancel_market_orders(&market.condition_id).await?;
+    let mut placed = 0;
+    for (token_id, price, remaining) in resting {
+        let price = floor_to_tick(price, tick_size);
+        match create_and_submit_order(clob, signer, &token_id, price, remaining, tick_size, market.neg_risk).await {
+            Ok(order_id) => {
+                info!("  Re-quoted {}: {} shares @ {}¢", order_id, remaining.round_dp(0), price * dec!(100));
+                placed += 1;
+            }
+            Err(e) => warn!("  Re-quote of {} shares @ {}¢ failed: {}", remaining.round_dp(0), price * dec!(100), e),
+        }
+    }
+    Ok(placed)
+}
+
 async fn create_and_submit_order(
     clob: &ClobClient,
     signer: &OrderSigner,
@@ -4,6 +4,9 @@
 //! Run with: cargo run --bin latency_bench --release
 
 use anyhow::Result;
+use btc_arb_bot::eip712::{typed_data_hash, Exchange, OrderData};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::{Address, H256, U256};
 use reqwest::Client;
 use std::time::{Duration, Instant};
 
@@ -115,28 +118,64 @@ async fn main() -> Result<()> {
     }
     println!();
 
-    // Test 3: Signing benchmark (CPU-only)
-    println!("═══ TEST 3: Order Signing (simulated) ═══");
-    let sign_start = Instant::now();
-    let iterations = 1000;
-
-    // Simulate HMAC-SHA256 signing workload
-    use std::collections::hash_map::DefaultHasher;
-    use std::hash::{Hash, Hasher};
-
+    // Test 3: EIP-712 order hashing + signing (CPU-only)
+    println!("═══ TEST 3: Order Signing (EIP-712, 1000 orders) ═══");
+    let iterations = 1000u32;
+
+    // Throwaway dev key - signatures are never submitted
+    let wallet: LocalWallet = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80".parse()?;
+    let order = OrderData {
+        salt: U256::from(123456789u64),
+        maker: wallet.address(),
+        signer: wallet.address(),
+        taker: Address::zero(),
+        token_id: U256::from_dec_str(TEST_TOKEN_ID)?,
+        maker_amount: U256::from(48_000_000u64),
+        taker_amount: U256::from(100_000_000u64),
+        expiration: U256::from(1_700_000_000u64),
+        nonce: U256::zero(),
+        fee_rate_bps: U256::zero(),
+        side: 0,
+        signature_type: 0,
+    };
+
+    // Original path: build TypedData JSON, parse, hash
+    let typed_start = Instant::now();
     for i in 0..iterations {
-        let mut hasher = DefaultHasher::new();
-        format!("order_{}_{}", i, TEST_TOKEN_ID).hash(&mut hasher);
-        let _ = hasher.finish();
+        let mut o = order.clone();
+        o.salt = U256::from(i);
+        let hash = typed_data_hash(&o.to_json(), Exchange::NegRisk)?;
+        let _ = wallet.sign_hash(H256::from(hash))?;
     }
+    let typed_per_sign = typed_start.elapsed() / iterations;
 
-    let sign_elapsed = sign_start.elapsed();
-    let per_sign = sign_elapsed / iterations;
+    // Native path: struct hash against precomputed domain separator
+    let native_start = Instant::now();
+    for i in 0..iterations {
+        let mut o = order.clone();
+        o.salt = U256::from(i);
+        let hash = o.signing_hash(Exchange::NegRisk);
+        let _ = wallet.sign_hash(H256::from(hash))?;
+    }
+    let per_sign = native_start.elapsed() / iterations;
 
-    println!("Signing Simulation Results:");
-    println!("  {} iterations in {:?}", iterations, sign_elapsed);
-    println!("  Per-sign avg: {:?}", per_sign);
-    println!("  Note: Real EIP-712 signing is ~0.1ms/order (from logs)");
+    // Hash-only cost (what the native path removed)
+    let hash_start = Instant::now();
+    for i in 0..iterations {
+        let mut o = order.clone();
+        o.salt = U256::from(i);
+        let _ = o.signing_hash(Exchange::NegRisk);
+    }
+    let per_hash = hash_start.elapsed() / iterations;
+
+    println!("Signing Results:");
+    println!("  TypedData path:  {:>8.2?}/order", typed_per_sign);
+    println!("  Native path:     {:>8.2?}/order", per_sign);
+    println!("  Native hash only:{:>8.2?}/order", per_hash);
+    println!(
+        "  Speedup: {:.1}x",
+        typed_per_sign.as_nanos() as f64 / per_sign.as_nanos().max(1) as f64
+    );
     println!();
 
     // Summary
@@ -145,10 +184,10 @@ async fn main() -> Result<()> {
     println!("╠═══════════════════════════════════════════════════════════╣");
     println!("║ Orderbook Fetch P95:  {:>8.2?} (target: <100ms)         ║", p95);
     println!("║ POST Round-trip P50:  {:>8.2?} (target: <150ms)         ║", post_p50);
-    println!("║ Order Signing:        ~0.1ms/order (from production)     ║");
+    println!("║ Order Signing:        {:>8.2?} (native EIP-712)          ║", per_sign);
     println!("╠═══════════════════════════════════════════════════════════╣");
 
-    let total_estimate = p95 + post_p50 + Duration::from_micros(100);
+    let total_estimate = p95 + post_p50 + per_sign;
     println!("║ Estimated End-to-End: {:>8.2?}                          ║", total_estimate);
 
     if total_estimate < Duration::from_millis(300) {
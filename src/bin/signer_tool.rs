@@ -0,0 +1,128 @@
+//! Key management for the order signer
+//!
+//! Encrypt a raw key into a keystore (reads PRIVATE_KEY or prompts):
+//!   cargo run --bin signer_tool -- import ./keystore
+//!
+//! Run a remote signer holding the key in its own process:
+//!   cargo run --bin signer_tool -- serve /run/arb/signer.sock --keystore ./keystore/<uuid>
+//! then start the bots with REMOTE_SIGNER_SOCKET=/run/arb/signer.sock
+//!
+//! Show on-chain order nonces, or kill every open order on both exchanges
+//! with incrementNonce (needs POLYGON_RPC_URL and the configured signing key):
+//!   cargo run --bin signer_tool -- nonce
+//!   cargo run --bin signer_tool -- cancel-all
+//!
+//! Passphrases come from KEYSTORE_PASSWORD_FILE if set, otherwise a prompt.
+
+use anyhow::{Context, Result};
+use std::sync::Arc;
+use tracing::Level;
+use tracing_subscriber::FmtSubscriber;
+
+use btc_arb_bot::config::Config;
+use btc_arb_bot::eip712::Exchange;
+use btc_arb_bot::nonce::NonceClient;
+use btc_arb_bot::signer::OrderSigner;
+use btc_arb_bot::signing_backend::{
+    prompt_passphrase, read_passphrase_file, serve_remote_signer, KeystoreBackend,
+};
+
+const USAGE: &str =
+    "usage: signer_tool import <keystore_dir> | serve <socket_path> --keystore <path> | nonce | cancel-all";
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenvy::dotenv().ok();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    match args.first().map(String::as_str) {
+        Some("import") => run_import(&args[1..]),
+        Some("serve") => run_serve(&args[1..]).await,
+        Some("nonce") => run_nonce(false).await,
+        Some("cancel-all") => run_nonce(true).await,
+        _ => anyhow::bail!(USAGE),
+    }
+}
+
+fn passphrase(prompt: &str) -> Result<String> {
+    match std::env::var("KEYSTORE_PASSWORD_FILE") {
+        Ok(file) => read_passphrase_file(&file),
+        Err(_) => prompt_passphrase(prompt),
+    }
+}
+
+fn run_import(args: &[String]) -> Result<()> {
+    let dir = args.first().context(USAGE)?;
+
+    let private_key = match std::env::var("PRIVATE_KEY") {
+        Ok(key) => key,
+        Err(_) => prompt_passphrase("Private key (hex): ")?,
+    };
+
+    let pass = passphrase("New keystore passphrase: ")?;
+    if std::env::var("KEYSTORE_PASSWORD_FILE").is_err() && pass != prompt_passphrase("Repeat passphrase: ")? {
+        anyhow::bail!("Passphrases do not match");
+    }
+    if pass.is_empty() {
+        anyhow::bail!("Refusing to create a keystore with an empty passphrase");
+    }
+
+    let (path, address) = KeystoreBackend::create(dir, &private_key, &pass)?;
+    println!("✓ Keystore written: {}", path.display());
+    println!("  Address: {:?}", address);
+    println!("\nSet KEYSTORE_PATH={} and remove PRIVATE_KEY from your .env", path.display());
+    Ok(())
+}
+
+async fn run_serve(args: &[String]) -> Result<()> {
+    FmtSubscriber::builder()
+        .with_max_level(Level::INFO)
+        .with_target(false)
+        .init();
+
+    let socket = args.first().context(USAGE)?;
+    let keystore = args
+        .iter()
+        .position(|a| a == "--keystore")
+        .and_then(|i| args.get(i + 1))
+        .context(USAGE)?;
+
+    let pass = passphrase(&format!("Passphrase for {}: ", keystore))?;
+    let backend = KeystoreBackend::open(keystore, &pass)?;
+
+    let server = serve_remote_signer(socket, Arc::new(backend));
+    tokio::select! {
+        result = server => result,
+        _ = tokio::signal::ctrl_c() => {
+            let _ = std::fs::remove_file(socket);
+            Ok(())
+        }
+    }
+}
+
+async fn run_nonce(cancel_all: bool) -> Result<()> {
+    let config = Config::from_env()?;
+    let rpc_url = config.polygon_rpc_url.clone().context("POLYGON_RPC_URL not set")?;
+    let signer = OrderSigner::from_config(&config).await?;
+    let client = NonceClient::connect(&rpc_url).await?;
+
+    println!("Maker:  {:?}", signer.funder());
+    println!("Signer: {:?}", signer.address());
+    for exchange in [Exchange::Ctf, Exchange::NegRisk] {
+        let nonce = client.fetch_nonce(exchange, signer.funder()).await?;
+        println!("{:?} nonce: {}", exchange, nonce);
+    }
+
+    if cancel_all {
+        println!("\nSending incrementNonce on both exchanges...");
+        for receipt in client.cancel_all(&signer).await? {
+            println!("✓ {:?} (block {:?})", receipt.transaction_hash, receipt.block_number);
+        }
+        println!(
+            "All open orders invalidated. New nonces: CTF={} NegRisk={}",
+            signer.nonce(Exchange::Ctf),
+            signer.nonce(Exchange::NegRisk)
+        );
+    }
+    Ok(())
+}
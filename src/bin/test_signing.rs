@@ -1,8 +1,11 @@
-/// Test script to verify EIP-712 signing works correctly
-///
-/// This tests the order signing against Polymarket's API without
-/// actually placing orders. Run with:
-///   cargo run --bin test_signing
+//! Test script to verify EIP-712 signing works correctly
+//!
+//! This tests the order signing against Polymarket's API without
+//! actually placing orders. Run with:
+//!   cargo run --bin test_signing
+//!
+//! Verify a saved order (JSON as posted to /order):
+//!   cargo run --bin test_signing -- verify order.json [--ctf] [--tick-size 0.01]
 
 use anyhow::Result;
 use rust_decimal::Decimal;
@@ -10,11 +13,17 @@ use rust_decimal_macros::dec;
 
 // Import from main crate
 use btc_arb_bot::config::Config;
+use btc_arb_bot::order_verify::{load_order, verify_order};
 use btc_arb_bot::signer::OrderSigner;
 use btc_arb_bot::types::Side;
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("verify") {
+        return run_verify(&args[1..]);
+    }
+
     println!("═══════════════════════════════════════");
     println!("       BTC Arb Bot - Signing Test      ");
     println!("═══════════════════════════════════════\n");
@@ -25,7 +34,7 @@ async fn main() -> Result<()> {
     println!("  Address: {}", config.address);
 
     // Create signer
-    let signer = OrderSigner::new(&config.private_key, &config.address)?;
+    let signer = OrderSigner::from_config(&config).await?;
     println!("✓ Signer created\n");
 
     // Test creating a BUY order
@@ -75,6 +84,13 @@ async fn main() -> Result<()> {
         return Err(anyhow::anyhow!("Amount calculation error"));
     }
 
+    // Verify signature, signer and amounts as the exchange would
+    let verification = verify_order(&order, neg_risk, tick_size)?;
+    println!("\nSignature verification:\n{}", verification);
+    if !verification.is_valid() {
+        println!("\n⚠ WARN: Order would fail exchange checks");
+    }
+
     // Test SELL order too
     println!("\n───────────────────────────────────────\n");
     println!("Creating test SELL order...");
@@ -112,3 +128,33 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
+
+/// Verify a saved order file: `verify <order.json> [--ctf] [--tick-size 0.01]`
+fn run_verify(args: &[String]) -> Result<()> {
+    let path = args
+        .first()
+        .ok_or_else(|| anyhow::anyhow!("Usage: test_signing verify <order.json> [--ctf] [--tick-size 0.01]"))?;
+
+    // BTC markets are neg risk unless told otherwise
+    let neg_risk = !args.iter().any(|a| a == "--ctf");
+    let tick_size: Decimal = match args.iter().position(|a| a == "--tick-size") {
+        Some(i) => args
+            .get(i + 1)
+            .ok_or_else(|| anyhow::anyhow!("--tick-size needs a value"))?
+            .parse()?,
+        None => dec!(0.01),
+    };
+
+    let order = load_order(path)?;
+    println!("Verifying {} ({:?}, tick {})", path, order.order_type, tick_size);
+    println!("  Maker:  {}", order.order.maker);
+    println!("  Signer: {}", order.order.signer);
+
+    let verification = verify_order(&order, neg_risk, tick_size)?;
+    println!("{}", verification);
+
+    if !verification.is_valid() {
+        anyhow::bail!("Order failed verification");
+    }
+    Ok(())
+}
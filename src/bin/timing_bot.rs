@@ -5,22 +5,29 @@
 //! buy and collect the difference.
 //!
 //! Run with: cargo run --bin timing_bot --release
+//!
+//! Watches every asset in ASSETS (BTC by default) concurrently.
 
 use anyhow::Result;
 use btc_arb_bot::{
-    btc_price::spawn_btc_price_feed,
+    alerts::AlertClient,
     clob::ClobClient,
-    config::Config,
+    config::{AssetConfig, Config},
+    event_bus::MarketStream,
     market::MarketMonitor,
+    market_ws::MarketFeed,
     orderbook::OrderbookManager,
     signer::OrderSigner,
-    types::BtcMarket,
-    websocket::{spawn_websocket_with_orderbook, WsEvent},
+    spot_price::{spawn_price_feed, SpotPriceFeed},
+    supervisor::Supervisor,
+    types::UpDownMarket,
+    websocket::WsEvent,
 };
+use futures_util::future::join_all;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use std::sync::Arc;
-use tracing::{debug, error, info, warn, Level};
+use tracing::{debug, error, info, info_span, warn, Instrument, Level};
 use tracing_subscriber::FmtSubscriber;
 
 #[tokio::main]
@@ -42,21 +49,67 @@ async fn main() -> Result<()> {
     info!("║ Target: Buy at 95-99.5¢, collect $1 (Sharky6999) ║");
     info!("╚═══════════════════════════════════════════════════╝");
 
-    // Initialize BTC price feed
-    info!("Connecting to Coinbase for BTC price...");
-    let btc_feed = spawn_btc_price_feed();
+    // Ctrl-C / SIGTERM end the current session and stop the bot
+    let supervisor = Supervisor::install();
+    let alerts = AlertClient::new(config.discord_webhook.clone());
+    let clob = ClobClient::new(config.clone())?;
 
-    // Wait for connection
-    for _ in 0..50 {
-        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-        if btc_feed.get_price() > Decimal::ZERO {
-            break;
-        }
+    // Initialize a price feed per asset
+    let mut feeds = Vec::new();
+    for asset_config in &config.assets {
+        info!("Connecting to {} price sources: {}", asset_config.asset, asset_config.price_sources.join(", "));
+        feeds.push(spawn_price_feed(asset_config.asset, &asset_config.price_sources, &supervisor)?);
+    }
+
+    // Wait for connection; a signal meanwhile ends the wait
+    supervisor
+        .run(join_all(feeds.iter().map(|f| f.wait_for_price(std::time::Duration::from_secs(5)))))
+        .await;
+    for feed in &feeds {
+        info!("{} price: ${}", feed.asset(), feed.get_price().round_dp(2));
     }
-    info!("BTC price: ${}", btc_feed.get_price().round_dp(2));
 
-    let market_monitor = MarketMonitor::new(config.clone());
     let orderbook_manager = Arc::new(OrderbookManager::new());
+    let market_feed = MarketFeed::spawn_with_routes(orderbook_manager.clone(), &config.market_ws_route_list())?;
+
+    // One loop per asset, each log line tagged with its asset
+    let loops = config.assets.iter().zip(feeds).map(|(asset_config, spot_feed)| {
+        let span = info_span!("asset", asset = %asset_config.asset);
+        run_timing_loop(&config, asset_config, spot_feed, &clob, &market_feed, orderbook_manager.clone(), &supervisor)
+            .instrument(span)
+    });
+    let (mut total_entries, mut total_wins, mut total_profit) = (0u32, 0u32, Decimal::ZERO);
+    for (entries, wins, profit) in join_all(loops).await {
+        total_entries += entries;
+        total_wins += wins;
+        total_profit += profit;
+    }
+
+    info!("Shutting down...");
+    market_feed.shutdown().await;
+    let reason = supervisor.reason().unwrap_or_else(|| "shutdown".to_string());
+    let traded_assets: Vec<_> = config.assets.iter().map(|a| a.asset).collect();
+    alerts.bot_stopped(&traded_assets, &format!(
+        "Timing bot stopped on {}: {}/{} wins, ${:.2}",
+        reason, total_wins, total_entries, total_profit
+    )).await;
+    supervisor.shutdown(std::time::Duration::from_secs(5)).await;
+    info!("Shutdown complete");
+    Ok(())
+}
+
+/// Timing loop for one asset's markets; returns (entries, wins, profit)
+async fn run_timing_loop(
+    config: &Config,
+    asset_config: &AssetConfig,
+    spot_feed: Arc<SpotPriceFeed>,
+    clob: &ClobClient,
+    market_feed: &MarketFeed,
+    orderbook_manager: Arc<OrderbookManager>,
+    supervisor: &Supervisor,
+) -> (u32, u32, Decimal) {
+    let asset = asset_config.asset;
+    let market_monitor = MarketMonitor::new(config.clone(), asset);
 
     // Stats tracking
     let mut total_entries = 0u32;
@@ -64,33 +117,42 @@ async fn main() -> Result<()> {
     let mut total_profit = Decimal::ZERO;
 
     // Main loop
-    loop {
+    while !supervisor.is_shutting_down() {
         info!("═══════════════════════════════════════════════════");
-        info!("Searching for active BTC 15-min market...");
+        info!("Searching for active {} 15-min market...", asset);
 
-        let market = market_monitor.wait_for_next_market().await;
+        let Some(market) = supervisor.run(market_monitor.wait_for_next_market()).await else {
+            break;
+        };
         info!("Found: {} (ends {})", market.title, market.end_time);
 
-        // Start WebSocket
-        let _ws_rx = spawn_websocket_with_orderbook(
-            config.clone(),
-            vec![market.up_token_id.clone(), market.down_token_id.clone()],
-            orderbook_manager.clone(),
-        );
+        // Books come in on the shared connection; the session reads prices from the
+        // manager and drains the stream so queued trades and events don't pile up
+        let ws_rx = market_feed.subscribe(&market);
 
         // Mark open price
-        btc_feed.mark_market_open();
-        let open_price = btc_feed.get_price();
-        info!("Market open BTC: ${}", open_price.round_dp(2));
+        spot_feed.mark_market_open(market.window_start());
+        let open_price = spot_feed.get_market_open_price().unwrap_or_else(|| spot_feed.get_price());
+        info!("Market open {}: ${}", asset, open_price.round_dp(2));
 
         // Run timing session
         let result = run_timing_session(
-            &config,
-            &btc_feed,
+            config,
+            &spot_feed,
             &market,
+            asset_config.max_position_usd,
             orderbook_manager.clone(),
+            ws_rx,
+            supervisor,
         ).await;
 
+        if supervisor.is_shutting_down() && !config.dry_run {
+            info!("Cancelling open orders for {}...", market.condition_id);
+            if let Err(e) = clob.cancel_market_orders(&market.condition_id).await {
+                error!("Failed to cancel orders: {}", e);
+            }
+        }
+
         if let Ok((entered, won, profit)) = result {
             if entered {
                 total_entries += 1;
@@ -102,16 +164,24 @@ async fn main() -> Result<()> {
             }
         }
 
-        btc_feed.clear_market_open();
-        tokio::time::sleep(tokio::time::Duration::from_secs(30)).await;
+        market_feed.unsubscribe(&market.condition_id);
+        market_feed.log_route_stats();
+        spot_feed.log_source_stats();
+        spot_feed.clear_market_open();
+        supervisor.run(tokio::time::sleep(tokio::time::Duration::from_secs(30))).await;
     }
+
+    (total_entries, total_wins, total_profit)
 }
 
 async fn run_timing_session(
     config: &Config,
-    btc_feed: &Arc<btc_arb_bot::btc_price::BtcPriceFeed>,
-    market: &BtcMarket,
+    spot_feed: &Arc<SpotPriceFeed>,
+    market: &UpDownMarket,
+    position_size: Decimal,
     orderbook_manager: Arc<OrderbookManager>,
+    mut ws_rx: MarketStream,
+    supervisor: &Supervisor,
 ) -> Result<(bool, bool, Decimal)> {
     let end_time = market.end_time;
     let mut entered = false;
@@ -121,7 +191,28 @@ async fn run_timing_session(
 
     info!("Waiting for timing window (minute 14.8-15.0)...");
 
+    let mut check_interval = tokio::time::interval(tokio::time::Duration::from_millis(100));
     loop {
+        tokio::select! {
+            _ = supervisor.cancelled() => {
+                info!("Shutdown requested, no new entries");
+                break;
+            }
+            Some(event) = ws_rx.recv() => {
+                match event {
+                    WsEvent::BookUnreliable { asset_id, reason } => {
+                        warn!("Book {} unreliable ({}), waiting for resync", asset_id, reason);
+                    }
+                    WsEvent::Disconnected => {
+                        warn!("WebSocket disconnected");
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            _ = check_interval.tick() => {}
+        }
+
         let now = chrono::Utc::now();
         let seconds_to_end = (end_time - now).num_seconds();
 
@@ -133,9 +224,9 @@ async fn run_timing_session(
 
         // Only act in final 12 seconds (minute 14.8-15.0)
         if minute_of_period >= 14.8 && !entered {
-            let btc_price = btc_feed.get_price();
-            let btc_change_pct = btc_feed.get_price_change_pct().unwrap_or(Decimal::ZERO);
-            let is_up = btc_feed.get_predicted_outcome();
+            let spot_price = spot_feed.get_price();
+            let spot_change_pct = spot_feed.get_price_change_pct().unwrap_or(Decimal::ZERO);
+            let is_up = spot_feed.get_predicted_outcome();
 
             // Get orderbook spread
             if let Some(spread) = orderbook_manager.get_combined_spread(
@@ -151,7 +242,6 @@ async fn run_timing_session(
                 // He makes $100K/month doing this at high volume
                 if best_ask >= dec!(0.95) && best_ask <= dec!(0.995) {
                     let potential_profit = dec!(1.0) - best_ask;
-                    let position_size = config.max_position_usd;
                     shares = position_size / best_ask;
                     let expected_profit = shares * potential_profit;
 
@@ -159,7 +249,8 @@ async fn run_timing_session(
                     info!("║   TIMING ENTRY SIGNAL!                            ║");
                     info!("╚═══════════════════════════════════════════════════╝");
                     info!("  Minute: {:.2}", minute_of_period);
-                    info!("  BTC: ${} ({:+.4}%)", btc_price.round_dp(2), btc_change_pct);
+                    info!("  {}: ${} ({:+.4}%)", market.asset, spot_price.round_dp(2), spot_change_pct);
+                    info!("  {}", spot_feed.flow());
                     info!("  Direction: {}", outcome);
                     info!("  Entry price: {}¢", (best_ask * dec!(100)).round_dp(1));
                     info!("  Potential profit: {}¢/share", (potential_profit * dec!(100)).round_dp(1));
@@ -182,23 +273,21 @@ async fn run_timing_session(
 
         // Log status every 2 seconds in final window
         if minute_of_period >= 14.5 && seconds_to_end % 2 == 0 {
-            let btc_change_pct = btc_feed.get_price_change_pct().unwrap_or(Decimal::ZERO);
-            let dir = match btc_feed.get_predicted_outcome() {
+            let spot_change_pct = spot_feed.get_price_change_pct().unwrap_or(Decimal::ZERO);
+            let dir = match spot_feed.get_predicted_outcome() {
                 Some(true) => "UP",
                 Some(false) => "DOWN",
                 None => "FLAT",
             };
-            info!("Min {:.2}: BTC {:+.4}% = {} | Entered: {}",
-                  minute_of_period, btc_change_pct, dir, entered);
+            info!("Min {:.2}: {} {:+.4}% = {} | Entered: {}",
+                  minute_of_period, market.asset, spot_change_pct, dir, entered);
         }
-
-        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
     }
 
     // Calculate result
     if entered {
-        let btc_change = btc_feed.get_price_change().unwrap_or(Decimal::ZERO);
-        let actual_up = btc_change > Decimal::ZERO;
+        let spot_change = spot_feed.get_price_change().unwrap_or(Decimal::ZERO);
+        let actual_up = spot_change > Decimal::ZERO;
         let won = predicted_up == actual_up;
         let profit = if won {
             shares * (dec!(1.0) - entry_price)
@@ -0,0 +1,334 @@
+//! Calibration of the fair-value model against realized outcomes
+//!
+//! Logged [`MarketSnapshot`]s carry the BTC price, window open and
+//! volatility the bot saw; joined with settled window outcomes they give
+//! (normalized move, minute of window, Up won?) samples. We fit
+//! `P(up) = 1 / (1 + exp(-(a + b*z + c*z*m/15)))` by weighted Newton steps,
+//! where `z` is the model's normalized move and `m` the minute of window,
+//! and report Brier scores and reliability tables for the raw model and the
+//! fit. Every window carries the same total weight however many snapshots
+//! it logged, since snapshots within a window share one outcome.
+//!
+//! The fit only sees the older windows: the latest [`HOLDOUT_FRACTION`] of
+//! them are held out, and the Brier scores are measured on those, so they
+//! say how the fit does on windows it hasn't seen.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::datalog::{MarketSnapshot, WindowOutcome};
+use crate::fair_value::{minute_of_window, normal_cdf, FairValueModel};
+use crate::types::WINDOW_SECS;
+
+/// Fewest windows worth fitting on
+pub const MIN_WINDOWS: usize = 20;
+
+/// Share of the most recent windows held out to score the fit
+pub const HOLDOUT_FRACTION: f64 = 0.2;
+
+/// Ridge penalty keeping the fit finite when outcomes separate perfectly
+const RIDGE: f64 = 1e-3;
+
+/// One snapshot joined with its window's outcome
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CalibrationSample {
+    pub window_start: i64,
+    pub z: f64,
+    pub minute: f64,
+    pub up_won: bool,
+    /// 1 / snapshots in this window
+    pub weight: f64,
+}
+
+/// Fitted logistic correction, persisted as JSON
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CalibrationParams {
+    pub intercept: f64,
+    pub move_coef: f64,
+    pub move_minute_coef: f64,
+    /// Samples and windows the fit was trained on
+    pub samples: usize,
+    pub windows: usize,
+    /// Latest windows held out of the fit
+    pub holdout_windows: usize,
+    /// Brier scores on the held-out windows
+    pub holdout_brier_model: f64,
+    pub holdout_brier_fitted: f64,
+    pub fitted_at: DateTime<Utc>,
+}
+
+impl CalibrationParams {
+    /// Calibrated probability of Up
+    pub fn prob(&self, z: f64, minute: f64) -> f64 {
+        sigmoid(self.intercept + self.move_coef * z + self.move_minute_coef * z * minute / 15.0)
+    }
+
+    pub fn load(path: &str) -> Result<Self> {
+        let json = fs::read_to_string(path).with_context(|| format!("reading {}", path))?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    pub fn save(&self, path: &str) -> Result<()> {
+        if let Some(dir) = Path::new(path).parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// Join snapshots with outcomes, skipping any without BTC fields or a settled window
+pub fn build_samples(
+    snapshots: &[MarketSnapshot],
+    outcomes: &[WindowOutcome],
+    model: &FairValueModel,
+) -> Vec<CalibrationSample> {
+    let outcomes: HashMap<i64, bool> = outcomes.iter().map(|o| (o.window_start, o.up_won)).collect();
+
+    let mut samples: Vec<CalibrationSample> = snapshots
+        .iter()
+        .filter_map(|s| {
+            let window_start = s.end_time.timestamp() - WINDOW_SECS;
+            let up_won = *outcomes.get(&window_start)?;
+            let seconds_left = (s.end_time - s.timestamp).num_seconds();
+            let z = model.normalized_move(s.spot_open?, s.spot_price?, seconds_left, s.spot_vol_5m_pct)?;
+            Some(CalibrationSample { window_start, z, minute: minute_of_window(seconds_left), up_won, weight: 1.0 })
+        })
+        .collect();
+
+    let mut per_window: HashMap<i64, usize> = HashMap::new();
+    for s in &samples {
+        *per_window.entry(s.window_start).or_default() += 1;
+    }
+    for s in &mut samples {
+        s.weight = 1.0 / per_window[&s.window_start] as f64;
+    }
+    samples
+}
+
+/// Split samples by time: the latest `fraction` of windows (at least one)
+/// are held out, the rest are for training
+pub fn split_by_time(samples: &[CalibrationSample], fraction: f64) -> (Vec<CalibrationSample>, Vec<CalibrationSample>) {
+    let mut starts: Vec<i64> = samples.iter().map(|s| s.window_start).collect();
+    starts.sort_unstable();
+    starts.dedup();
+    let held_out = ((starts.len() as f64 * fraction).ceil() as usize).max(1);
+    match starts.len().checked_sub(held_out).and_then(|i| starts.get(i)) {
+        Some(&cutoff) => samples.iter().partition(|s| s.window_start < cutoff),
+        None => (samples.to_vec(), Vec::new()),
+    }
+}
+
+/// Fit the logistic correction on the older windows and score it on the
+/// latest [`HOLDOUT_FRACTION`]
+pub fn fit(samples: &[CalibrationSample]) -> Result<CalibrationParams> {
+    let windows = samples.iter().map(|s| s.window_start).collect::<std::collections::HashSet<_>>().len();
+    if windows < MIN_WINDOWS {
+        bail!("{} settled windows, need at least {}", windows, MIN_WINDOWS);
+    }
+    let (train, holdout) = split_by_time(samples, HOLDOUT_FRACTION);
+    let train_windows = train.iter().map(|s| s.window_start).collect::<std::collections::HashSet<_>>().len();
+    let beta = fit_logistic(&train)?;
+
+    let mut params = CalibrationParams {
+        intercept: beta[0],
+        move_coef: beta[1],
+        move_minute_coef: beta[2],
+        samples: train.len(),
+        windows: train_windows,
+        holdout_windows: windows - train_windows,
+        holdout_brier_model: 0.0,
+        holdout_brier_fitted: 0.0,
+        fitted_at: Utc::now(),
+    };
+    params.holdout_brier_model = brier(&model_predictions(&holdout));
+    params.holdout_brier_fitted = brier(&fitted_predictions(&holdout, &params));
+    Ok(params)
+}
+
+/// Weighted Newton iterations for the logistic coefficients
+fn fit_logistic(samples: &[CalibrationSample]) -> Result<[f64; 3]> {
+    let features = |s: &CalibrationSample| [1.0, s.z, s.z * s.minute / 15.0];
+    // Start from the logistic approximation of the normal CDF
+    let mut beta = [0.0, 1.702, 0.0];
+    for _ in 0..50 {
+        let mut grad = [0.0; 3];
+        let mut hess = [[0.0; 3]; 3];
+        for s in samples {
+            let x = features(s);
+            let p = sigmoid(dot(&beta, &x));
+            let y = if s.up_won { 1.0 } else { 0.0 };
+            for i in 0..3 {
+                grad[i] += s.weight * (y - p) * x[i];
+                for j in 0..3 {
+                    hess[i][j] += s.weight * p * (1.0 - p) * x[i] * x[j];
+                }
+            }
+        }
+        for i in 0..3 {
+            grad[i] -= RIDGE * beta[i];
+            hess[i][i] += RIDGE;
+        }
+        let step = solve3(hess, grad).context("singular Hessian")?;
+        for i in 0..3 {
+            beta[i] += step[i];
+        }
+        if step.iter().all(|d| d.abs() < 1e-9) {
+            break;
+        }
+    }
+    Ok(beta)
+}
+
+/// (probability, weight, up won) under the uncalibrated normal CDF
+pub fn model_predictions(samples: &[CalibrationSample]) -> Vec<(f64, f64, bool)> {
+    samples.iter().map(|s| (normal_cdf(s.z), s.weight, s.up_won)).collect()
+}
+
+/// (probability, weight, up won) under the fitted correction
+pub fn fitted_predictions(samples: &[CalibrationSample], params: &CalibrationParams) -> Vec<(f64, f64, bool)> {
+    samples.iter().map(|s| (params.prob(s.z, s.minute), s.weight, s.up_won)).collect()
+}
+
+/// Weighted mean squared error of the probabilities
+pub fn brier(predictions: &[(f64, f64, bool)]) -> f64 {
+    let total: f64 = predictions.iter().map(|(_, w, _)| w).sum();
+    if total == 0.0 {
+        return 0.0;
+    }
+    predictions
+        .iter()
+        .map(|(p, w, won)| w * (p - if *won { 1.0 } else { 0.0 }).powi(2))
+        .sum::<f64>()
+        / total
+}
+
+/// One bucket of a reliability diagram
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReliabilityBin {
+    pub lo: f64,
+    pub hi: f64,
+    pub count: usize,
+    /// Weighted mean predicted probability
+    pub predicted: f64,
+    /// Weighted fraction that resolved Up
+    pub observed: f64,
+}
+
+/// Bucket predictions into `bins` equal-width probability bins (empty ones dropped)
+pub fn reliability(predictions: &[(f64, f64, bool)], bins: usize) -> Vec<ReliabilityBin> {
+    let mut acc = vec![(0usize, 0.0, 0.0, 0.0); bins];
+    for (p, w, won) in predictions {
+        let i = ((p * bins as f64) as usize).min(bins - 1);
+        acc[i].0 += 1;
+        acc[i].1 += w;
+        acc[i].2 += w * p;
+        acc[i].3 += if *won { *w } else { 0.0 };
+    }
+    acc.into_iter()
+        .enumerate()
+        .filter(|(_, (count, ..))| *count > 0)
+        .map(|(i, (count, weight, pred, up))| ReliabilityBin {
+            lo: i as f64 / bins as f64,
+            hi: (i + 1) as f64 / bins as f64,
+            count,
+            predicted: pred / weight,
+            observed: up / weight,
+        })
+        .collect()
+}
+
+fn sigmoid(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+fn dot(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Solve `a * x = b` by Cramer's rule
+fn solve3(a: [[f64; 3]; 3], b: [f64; 3]) -> Option<[f64; 3]> {
+    let det = |m: &[[f64; 3]; 3]| {
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1]) - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    };
+    let d = det(&a);
+    if d.abs() < 1e-12 {
+        return None;
+    }
+    let mut x = [0.0; 3];
+    for (col, xi) in x.iter_mut().enumerate() {
+        let mut m = a;
+        for row in 0..3 {
+            m[row][col] = b[row];
+        }
+        *xi = det(&m) / d;
+    }
+    Some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_brier_and_reliability() {
+        let preds = [(0.9, 1.0, true), (0.8, 1.0, false), (0.15, 1.0, false), (0.1, 0.5, false)];
+        // (0.01 + 0.64 + 0.0225 + 0.5 * 0.01) / 3.5
+        assert!((brier(&preds) - 0.6775 / 3.5).abs() < 1e-12);
+
+        let bins = reliability(&preds, 10);
+        assert_eq!(bins.len(), 3);
+        assert_eq!((bins[0].lo, bins[0].count, bins[0].observed), (0.1, 2, 0.0));
+        assert!((bins[0].predicted - (0.15 + 0.05) / 1.5).abs() < 1e-12);
+        assert_eq!((bins[1].lo, bins[1].count, bins[1].observed), (0.8, 1, 0.0));
+        assert_eq!((bins[2].lo, bins[2].count, bins[2].observed), (0.9, 1, 1.0));
+    }
+
+    #[test]
+    fn test_fit_recovers_overconfident_model() {
+        // Outcomes drawn from a logistic that is half as sharp as the raw
+        // model, with a slight Up bias; deterministic LCG for reproducibility
+        let truth = |z: f64| sigmoid(0.2 + 0.85 * z);
+        let mut seed: u64 = 42;
+        let mut uniform = || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 11) as f64 / (1u64 << 53) as f64
+        };
+        let samples: Vec<CalibrationSample> = (0..20_000)
+            .map(|i| {
+                let z = uniform() * 6.0 - 3.0;
+                let minute = uniform() * 15.0;
+                CalibrationSample { window_start: i, z, minute, up_won: uniform() < truth(z), weight: 1.0 }
+            })
+            .collect();
+
+        let params = fit(&samples).unwrap();
+        assert!((params.intercept - 0.2).abs() < 0.06, "{:?}", params);
+        assert!((params.move_coef - 0.85).abs() < 0.1, "{:?}", params);
+        assert!(params.move_minute_coef.abs() < 0.15, "{:?}", params);
+        assert_eq!((params.windows, params.holdout_windows), (16_000, 4_000));
+        assert!(params.holdout_brier_fitted < params.holdout_brier_model);
+
+        assert!(fit(&samples[..5]).is_err());
+    }
+
+    #[test]
+    fn test_split_holds_out_latest_windows() {
+        let sample = |window_start: i64| CalibrationSample { window_start, z: 0.0, minute: 0.0, up_won: true, weight: 0.5 };
+        // Two snapshots per window, logged out of order
+        let samples: Vec<_> = [900, 0, 2700, 1800, 900, 0, 1800, 2700, 3600, 3600].into_iter().map(sample).collect();
+
+        let (train, holdout) = split_by_time(&samples, 0.2);
+        assert!(train.iter().all(|s| s.window_start < 3600) && train.len() == 8);
+        assert!(holdout.iter().all(|s| s.window_start == 3600) && holdout.len() == 2);
+
+        // Never holds out everything
+        let (train, holdout) = split_by_time(&samples[..2], 0.2);
+        assert_eq!((train.len(), holdout.len()), (1, 1));
+    }
+}
@@ -1,7 +1,105 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use rust_decimal::Decimal;
 use std::env;
 
+use crate::types::Asset;
+
+/// Chainlink BTC/USD aggregator proxy on Polygon
+const CHAINLINK_BTC_FEED: &str = "0xc907E116054Ad103354f2D350FD2514433D57F6f";
+
+/// Per-asset settings for the up/down bots
+#[derive(Debug, Clone)]
+pub struct AssetConfig {
+    pub asset: Asset,
+    // Spot price sources, combined into a median (see price_source::KNOWN_SOURCES)
+    pub price_sources: Vec<String>,
+    pub max_position_usd: Decimal,
+    // This asset's share of ACCOUNT_BALANCE, which stakes are sized against
+    pub account_balance: Decimal,
+    // Chainlink <ASSET>/USD aggregator proxy on Polygon (required for REFERENCE_PRICE_SOURCE=chainlink)
+    pub chainlink_feed: Option<String>,
+    // Session logs and window outcomes
+    pub data_dir: String,
+    // Fitted fair-value calibration (written by the calibrate bin)
+    pub calibration_path: String,
+}
+
+impl AssetConfig {
+    /// Read `<ASSET>_*` overrides; BTC keeps the unprefixed paths it had
+    /// before other assets were supported
+    ///
+    /// Without `<ASSET>_MAX_POSITION_USD` an asset gets an equal share of the
+    /// global MAX_POSITION_USD, so listing more assets never raises total exposure.
+    fn from_env(asset: Asset, default_max_position: Decimal, account_balance: Decimal) -> Result<Self> {
+        let sym = asset.symbol();
+        let var = |suffix: &str| env::var(format!("{}_{}", sym, suffix)).ok();
+
+        Ok(AssetConfig {
+            asset,
+            price_sources: var("PRICE_SOURCES")
+                .unwrap_or_else(|| "coinbase,binance,kraken".to_string())
+                .split(',')
+                .map(|s| s.trim().to_lowercase())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            max_position_usd: match var("MAX_POSITION_USD") {
+                Some(v) => v.parse().with_context(|| format!("Invalid {}_MAX_POSITION_USD", sym))?,
+                None => default_max_position,
+            },
+            account_balance,
+            chainlink_feed: env::var(format!("CHAINLINK_{}_FEED", sym))
+                .ok()
+                .or_else(|| (asset == Asset::Btc).then(|| CHAINLINK_BTC_FEED.to_string())),
+            data_dir: Self::data_dir(asset),
+            calibration_path: Self::calibration_path(asset),
+        })
+    }
+
+    /// ./data for BTC, ./data/<asset> for the rest
+    pub fn data_dir(asset: Asset) -> String {
+        match asset {
+            Asset::Btc => "./data".to_string(),
+            other => format!("./data/{}", other.lower()),
+        }
+    }
+
+    /// <ASSET>_CALIBRATION_PATH (or CALIBRATION_PATH for BTC), else `calibration.json` in the data dir
+    pub fn calibration_path(asset: Asset) -> String {
+        env::var(format!("{}_CALIBRATION_PATH", asset.symbol()))
+            .ok()
+            .or_else(|| if asset == Asset::Btc { env::var("CALIBRATION_PATH").ok() } else { None })
+            .unwrap_or_else(|| format!("{}/calibration.json", Self::data_dir(asset)))
+    }
+}
+
+/// Settings for each asset, splitting MAX_POSITION_USD and ACCOUNT_BALANCE equally between them
+pub fn asset_configs(assets: Vec<Asset>, max_position_usd: Decimal, account_balance: Decimal) -> Result<Vec<AssetConfig>> {
+    let count = Decimal::from(assets.len().max(1));
+    assets
+        .into_iter()
+        .map(|asset| AssetConfig::from_env(asset, max_position_usd / count, account_balance / count))
+        .collect()
+}
+
+/// Parse a comma-separated ASSETS list, rejecting duplicates and empty lists
+pub fn parse_assets(list: &str) -> Result<Vec<Asset>> {
+    let mut assets: Vec<Asset> = Vec::new();
+    for name in list.split(',') {
+        if name.trim().is_empty() {
+            continue;
+        }
+        let asset: Asset = name.parse()?;
+        if assets.contains(&asset) {
+            bail!("{} listed twice in ASSETS", asset);
+        }
+        assets.push(asset);
+    }
+    if assets.is_empty() {
+        bail!("ASSETS is empty");
+    }
+    Ok(assets)
+}
+
 #[derive(Debug, Clone)]
 pub struct Config {
     // API Credentials
@@ -11,11 +109,16 @@ pub struct Config {
 
     // Wallet
     pub address: String,
-    pub private_key: String,
+    pub private_key: Option<String>,
+    pub keystore_path: Option<String>,         // Encrypted JSON keystore
+    pub keystore_password_file: Option<String>, // Passphrase file (prompts if unset)
+    pub remote_signer_socket: Option<String>,  // Unix socket of a signer_tool process
+    pub polygon_rpc_url: Option<String>,       // For order nonces / incrementNonce
 
     // Trading Parameters
     pub max_position_usd: Decimal,
     pub account_balance: Decimal,  // For dynamic position sizing
+    pub taker_fee_bps: Decimal,    // Taker fee, in bps of min(price, 1 - price) per share
     pub target_spread_percent: Decimal,
     pub min_spread_percent: Decimal,
 
@@ -35,6 +138,16 @@ pub struct Config {
     pub ws_url: String,
     pub gamma_url: String,
 
+    // Market data redundancy: parallel connections, first arrival wins
+    pub market_ws_connections: usize,
+    pub market_ws_routes: Vec<String>, // "<url>[ via <local ip>]", cycled across connections
+
+    // Up/down markets to trade (ASSETS=btc,eth), each with its own settings
+    pub assets: Vec<AssetConfig>,
+
+    // Resolution oracle tracking: "chainlink" (needs polygon_rpc_url) or "stub"
+    pub reference_price_source: String,
+
     // Lambda proxy for bypassing Cloudflare (optional)
     pub lambda_proxy_url: Option<String>,
 }
@@ -43,6 +156,19 @@ impl Config {
     pub fn from_env() -> Result<Self> {
         dotenvy::dotenv().ok();
 
+        let max_position_usd: Decimal = env::var("MAX_POSITION_USD")
+            .unwrap_or_else(|_| "1200".to_string())
+            .parse()
+            .context("Invalid MAX_POSITION_USD")?;
+
+        let account_balance: Decimal = env::var("ACCOUNT_BALANCE")
+            .unwrap_or_else(|_| "38".to_string())  // Default $38
+            .parse()
+            .context("Invalid ACCOUNT_BALANCE")?;
+
+        let asset_list = parse_assets(&env::var("ASSETS").unwrap_or_else(|_| "btc".to_string()))?;
+        let assets = asset_configs(asset_list, max_position_usd, account_balance)?;
+
         Ok(Config {
             // API Credentials
             api_key: env::var("POLY_API_KEY").context("POLY_API_KEY not set")?,
@@ -51,17 +177,19 @@ impl Config {
 
             // Wallet
             address: env::var("POLY_ADDRESS").context("POLY_ADDRESS not set")?,
-            private_key: env::var("PRIVATE_KEY").context("PRIVATE_KEY not set")?,
+            private_key: env::var("PRIVATE_KEY").ok(),
+            keystore_path: env::var("KEYSTORE_PATH").ok(),
+            keystore_password_file: env::var("KEYSTORE_PASSWORD_FILE").ok(),
+            remote_signer_socket: env::var("REMOTE_SIGNER_SOCKET").ok(),
+            polygon_rpc_url: env::var("POLYGON_RPC_URL").ok(),
 
             // Trading Parameters
-            max_position_usd: env::var("MAX_POSITION_USD")
-                .unwrap_or_else(|_| "1200".to_string())
+            max_position_usd,
+            account_balance,
+            taker_fee_bps: env::var("TAKER_FEE_BPS")
+                .unwrap_or_else(|_| "1000".to_string())
                 .parse()
-                .context("Invalid MAX_POSITION_USD")?,
-            account_balance: env::var("ACCOUNT_BALANCE")
-                .unwrap_or_else(|_| "38".to_string())  // Default $38
-                .parse()
-                .context("Invalid ACCOUNT_BALANCE")?,
+                .context("Invalid TAKER_FEE_BPS")?,
             target_spread_percent: env::var("TARGET_SPREAD_PERCENT")
                 .unwrap_or_else(|_| "4".to_string())
                 .parse()
@@ -96,8 +224,112 @@ impl Config {
             ws_url: "wss://ws-subscriptions-clob.polymarket.com/ws/market".to_string(),
             gamma_url: "https://gamma-api.polymarket.com".to_string(),
 
+            // Market data redundancy (MARKET_WS_CONNECTIONS=2 for two sockets)
+            market_ws_connections: env::var("MARKET_WS_CONNECTIONS")
+                .unwrap_or_else(|_| "1".to_string())
+                .parse::<usize>()
+                .context("Invalid MARKET_WS_CONNECTIONS")?
+                .max(1),
+            market_ws_routes: env::var("MARKET_WS_ROUTES")
+                .map(|v| v.split(',').map(|r| r.trim().to_string()).filter(|r| !r.is_empty()).collect())
+                .unwrap_or_default(),
+
+            assets,
+
+            reference_price_source: env::var("REFERENCE_PRICE_SOURCE")
+                .unwrap_or_else(|_| "stub".to_string())
+                .to_lowercase(),
+
             // Lambda proxy URL (set LAMBDA_PROXY_URL to enable)
             lambda_proxy_url: env::var("LAMBDA_PROXY_URL").ok(),
         })
     }
+
+    /// Dry-run config with placeholder credentials, for tests
+    #[cfg(test)]
+    pub fn for_tests() -> Self {
+        Config {
+            api_key: "test-key".to_string(),
+            api_secret: "c2VjcmV0".to_string(),
+            api_passphrase: "test-passphrase".to_string(),
+            address: "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266".to_string(),
+            private_key: None,
+            keystore_path: None,
+            keystore_password_file: None,
+            remote_signer_socket: None,
+            polygon_rpc_url: None,
+            max_position_usd: Decimal::from(100),
+            account_balance: Decimal::from(100),
+            taker_fee_bps: Decimal::from(1000),
+            target_spread_percent: Decimal::from(4),
+            min_spread_percent: Decimal::from(2),
+            ladder_levels: 5,
+            order_size_per_level: Decimal::from(5),
+            dry_run: true,
+            log_level: "info".to_string(),
+            discord_webhook: None,
+            clob_url: "http://127.0.0.1:9".to_string(),
+            ws_url: "ws://127.0.0.1:9".to_string(),
+            gamma_url: "http://127.0.0.1:9".to_string(),
+            market_ws_connections: 1,
+            market_ws_routes: Vec::new(),
+            assets: Vec::new(),
+            reference_price_source: "stub".to_string(),
+            lambda_proxy_url: None,
+        }
+    }
+
+    /// Settings for `asset`, if it's in ASSETS
+    pub fn asset(&self, asset: Asset) -> Option<&AssetConfig> {
+        self.assets.iter().find(|a| a.asset == asset)
+    }
+
+    /// One route per market-data connection; defaults to `ws_url`
+    pub fn market_ws_route_list(&self) -> Vec<String> {
+        let routes = if self.market_ws_routes.is_empty() {
+            std::slice::from_ref(&self.ws_url)
+        } else {
+            &self.market_ws_routes[..]
+        };
+        routes.iter().cycle().take(self.market_ws_connections).cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_parse_assets() {
+        assert_eq!(parse_assets("btc").unwrap(), vec![Asset::Btc]);
+        assert_eq!(parse_assets(" ETH, btc ,,sol").unwrap(), vec![Asset::Eth, Asset::Btc, Asset::Sol]);
+
+        let err = parse_assets("btc,eth,BTC").unwrap_err();
+        assert_eq!(err.to_string(), "BTC listed twice in ASSETS");
+        assert_eq!(parse_assets("").unwrap_err().to_string(), "ASSETS is empty");
+        assert_eq!(parse_assets(" , ").unwrap_err().to_string(), "ASSETS is empty");
+        assert!(parse_assets("btc,doge").is_err());
+    }
+
+    #[test]
+    fn test_asset_paths() {
+        // BTC keeps the paths it had before other assets existed
+        assert_eq!(AssetConfig::data_dir(Asset::Btc), "./data");
+        assert_eq!(AssetConfig::data_dir(Asset::Eth), "./data/eth");
+        assert_eq!(AssetConfig::calibration_path(Asset::Xrp), "./data/xrp/calibration.json");
+    }
+
+    #[test]
+    fn test_asset_config_defaults() {
+        let sol = AssetConfig::from_env(Asset::Sol, dec!(600), dec!(50)).unwrap();
+        assert_eq!(sol.max_position_usd, dec!(600));
+        assert_eq!(sol.account_balance, dec!(50));
+        assert_eq!(sol.price_sources, vec!["coinbase", "binance", "kraken"]);
+        assert_eq!(sol.chainlink_feed, None);
+        assert_eq!(sol.data_dir, "./data/sol");
+
+        let btc = AssetConfig::from_env(Asset::Btc, dec!(600), dec!(50)).unwrap();
+        assert_eq!(btc.chainlink_feed.as_deref(), Some(CHAINLINK_BTC_FEED));
+    }
 }
@@ -42,6 +42,25 @@ pub struct MarketSnapshot {
     pub down_asks: Vec<PriceLevel>,  // DOWN token ask levels
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub down_bids: Vec<PriceLevel>,  // DOWN token bid levels
+    // Spot state the bot saw, for fair-value calibration (btc_* in logs from BTC-only builds)
+    #[serde(default, alias = "btc_price", skip_serializing_if = "Option::is_none")]
+    pub spot_price: Option<Decimal>,
+    #[serde(default, alias = "btc_open", skip_serializing_if = "Option::is_none")]
+    pub spot_open: Option<Decimal>,
+    #[serde(default, alias = "btc_vol_5m_pct", skip_serializing_if = "Option::is_none")]
+    pub spot_vol_5m_pct: Option<Decimal>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fair_up_prob: Option<Decimal>,
+}
+
+/// Settled open/close of one 15-minute window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowOutcome {
+    pub window_start: i64, // unix seconds
+    pub open: Decimal,
+    pub close: Decimal,
+    pub up_won: bool,
+    pub source: String, // reference price source the prints came from
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -128,6 +147,26 @@ impl DataLogger {
         self.append_json(&file_path, summary)
     }
 
+    /// Log a settled window outcome
+    pub fn log_window_outcome(&self, outcome: &WindowOutcome) -> Result<()> {
+        let file_path = format!("{}/outcomes.jsonl", self.log_dir);
+        self.append_json(&file_path, outcome)
+    }
+
+    /// Sync this session's log files to disk (call before exiting)
+    pub fn flush(&self) -> Result<()> {
+        let files = ["snapshots", "orders", "fills"]
+            .iter()
+            .map(|kind| format!("{}/{}_{}.jsonl", self.log_dir, kind, self.session_id))
+            .chain(["summaries", "outcomes"].iter().map(|kind| format!("{}/{}.jsonl", self.log_dir, kind)));
+        for file_path in files {
+            if Path::new(&file_path).exists() {
+                OpenOptions::new().append(true).open(&file_path)?.sync_all()?;
+            }
+        }
+        Ok(())
+    }
+
     /// Append JSON line to file
     fn append_json<T: Serialize>(&self, file_path: &str, data: &T) -> Result<()> {
         let json = serde_json::to_string(data)?;
@@ -163,6 +202,16 @@ impl DataLogger {
         Self::read_jsonl(&file_path)
     }
 
+    /// Get all settled window outcomes, one per window (latest wins)
+    pub fn read_window_outcomes(log_dir: &str) -> Result<Vec<WindowOutcome>> {
+        let file_path = format!("{}/outcomes.jsonl", log_dir);
+        let mut by_window = std::collections::BTreeMap::new();
+        for outcome in Self::read_jsonl::<WindowOutcome>(&file_path)? {
+            by_window.insert(outcome.window_start, outcome);
+        }
+        Ok(by_window.into_values().collect())
+    }
+
     /// Read JSONL file into vector
     fn read_jsonl<T: for<'de> Deserialize<'de>>(file_path: &str) -> Result<Vec<T>> {
         if !Path::new(file_path).exists() {
@@ -0,0 +1,273 @@
+//! Native EIP-712 hashing for Polymarket CTF Exchange orders
+//!
+//! Building an ethers `TypedData` blob per order means a JSON round-trip
+//! plus type-string parsing on every signature. The order layout never
+//! changes, so we encode the struct hash directly and keep one precomputed
+//! domain separator per exchange contract.
+
+use anyhow::{Context, Result};
+use ethers::types::transaction::eip712::{Eip712, TypedData};
+use ethers::types::{Address, U256};
+use ethers::utils::keccak256;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use crate::types::{Side, SignedOrder};
+
+// Polymarket Exchange contract addresses on Polygon
+// Regular CTF Exchange for non-negRisk markets
+pub const CTF_EXCHANGE_ADDRESS: &str = "0x4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E";
+// NegRisk CTF Exchange for negRisk markets (BTC 15-min markets use this)
+pub const NEG_RISK_CTF_EXCHANGE_ADDRESS: &str = "0xC5d563A36AE78145C45a50134d48A1215220f80a";
+pub const CHAIN_ID: u64 = 137; // Polygon mainnet
+
+const DOMAIN_NAME: &str = "Polymarket CTF Exchange";
+const DOMAIN_VERSION: &str = "1";
+
+const DOMAIN_TYPE: &str =
+    "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)";
+const ORDER_TYPE: &str = "Order(uint256 salt,address maker,address signer,address taker,uint256 tokenId,uint256 makerAmount,uint256 takerAmount,uint256 expiration,uint256 nonce,uint256 feeRateBps,uint8 side,uint8 signatureType)";
+
+/// Exchange contract an order is signed for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Exchange {
+    Ctf,
+    NegRisk,
+}
+
+impl Exchange {
+    pub fn from_neg_risk(neg_risk: bool) -> Self {
+        if neg_risk {
+            Exchange::NegRisk
+        } else {
+            Exchange::Ctf
+        }
+    }
+
+    pub fn address(&self) -> Address {
+        let addr = match self {
+            Exchange::Ctf => CTF_EXCHANGE_ADDRESS,
+            Exchange::NegRisk => NEG_RISK_CTF_EXCHANGE_ADDRESS,
+        };
+        Address::from_str(addr).expect("valid exchange address")
+    }
+
+    /// EIP-712 domain separator (computed once per exchange)
+    pub fn domain_separator(&self) -> [u8; 32] {
+        static CTF: OnceLock<[u8; 32]> = OnceLock::new();
+        static NEG_RISK: OnceLock<[u8; 32]> = OnceLock::new();
+
+        let cell = match self {
+            Exchange::Ctf => &CTF,
+            Exchange::NegRisk => &NEG_RISK,
+        };
+        *cell.get_or_init(|| compute_domain_separator(self.address()))
+    }
+}
+
+fn compute_domain_separator(verifying_contract: Address) -> [u8; 32] {
+    let mut buf = [0u8; 32 * 5];
+    buf[0..32].copy_from_slice(&keccak256(DOMAIN_TYPE));
+    buf[32..64].copy_from_slice(&keccak256(DOMAIN_NAME));
+    buf[64..96].copy_from_slice(&keccak256(DOMAIN_VERSION));
+    U256::from(CHAIN_ID).to_big_endian(&mut buf[96..128]);
+    buf[140..160].copy_from_slice(verifying_contract.as_bytes());
+    keccak256(buf)
+}
+
+fn order_typehash() -> [u8; 32] {
+    static TYPEHASH: OnceLock<[u8; 32]> = OnceLock::new();
+    *TYPEHASH.get_or_init(|| keccak256(ORDER_TYPE))
+}
+
+/// Order fields in their on-chain types
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderData {
+    pub salt: U256,
+    pub maker: Address,
+    pub signer: Address,
+    pub taker: Address,
+    pub token_id: U256,
+    pub maker_amount: U256,
+    pub taker_amount: U256,
+    pub expiration: U256,
+    pub nonce: U256,
+    pub fee_rate_bps: U256,
+    pub side: u8,
+    pub signature_type: u8,
+}
+
+impl OrderData {
+    /// Parse the string fields of an API order back into on-chain types
+    pub fn from_signed_order(order: &SignedOrder) -> Result<Self> {
+        let uint = |name: &str, value: &str| {
+            U256::from_dec_str(value).with_context(|| format!("Invalid {}: {}", name, value))
+        };
+        let addr = |name: &str, value: &str| {
+            Address::from_str(value).with_context(|| format!("Invalid {}: {}", name, value))
+        };
+
+        Ok(Self {
+            salt: uint("salt", &order.salt)?,
+            maker: addr("maker", &order.maker)?,
+            signer: addr("signer", &order.signer)?,
+            taker: addr("taker", &order.taker)?,
+            token_id: uint("tokenId", &order.token_id)?,
+            maker_amount: uint("makerAmount", &order.maker_amount)?,
+            taker_amount: uint("takerAmount", &order.taker_amount)?,
+            expiration: uint("expiration", &order.expiration)?,
+            nonce: uint("nonce", &order.nonce)?,
+            fee_rate_bps: uint("feeRateBps", &order.fee_rate_bps)?,
+            side: match order.side {
+                Side::Buy => 0,
+                Side::Sell => 1,
+            },
+            signature_type: order.signature_type,
+        })
+    }
+
+    /// hashStruct(Order) per EIP-712
+    pub fn struct_hash(&self) -> [u8; 32] {
+        let mut buf = [0u8; 32 * 13];
+        buf[0..32].copy_from_slice(&order_typehash());
+        self.salt.to_big_endian(&mut buf[32..64]);
+        buf[76..96].copy_from_slice(self.maker.as_bytes());
+        buf[108..128].copy_from_slice(self.signer.as_bytes());
+        buf[140..160].copy_from_slice(self.taker.as_bytes());
+        self.token_id.to_big_endian(&mut buf[160..192]);
+        self.maker_amount.to_big_endian(&mut buf[192..224]);
+        self.taker_amount.to_big_endian(&mut buf[224..256]);
+        self.expiration.to_big_endian(&mut buf[256..288]);
+        self.nonce.to_big_endian(&mut buf[288..320]);
+        self.fee_rate_bps.to_big_endian(&mut buf[320..352]);
+        buf[383] = self.side;
+        buf[415] = self.signature_type;
+        keccak256(buf)
+    }
+
+    /// Final digest to sign: keccak256(0x1901 || domainSeparator || structHash)
+    pub fn signing_hash(&self, exchange: Exchange) -> [u8; 32] {
+        let mut buf = [0u8; 66];
+        buf[0] = 0x19;
+        buf[1] = 0x01;
+        buf[2..34].copy_from_slice(&exchange.domain_separator());
+        buf[34..66].copy_from_slice(&self.struct_hash());
+        keccak256(buf)
+    }
+
+    /// Order message in the JSON shape used by `typed_data_hash`
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "salt": self.salt.to_string(),
+            "maker": format!("{:?}", self.maker),
+            "signer": format!("{:?}", self.signer),
+            "taker": format!("{:?}", self.taker),
+            "tokenId": self.token_id.to_string(),
+            "makerAmount": self.maker_amount.to_string(),
+            "takerAmount": self.taker_amount.to_string(),
+            "expiration": self.expiration.to_string(),
+            "nonce": self.nonce.to_string(),
+            "feeRateBps": self.fee_rate_bps.to_string(),
+            "side": self.side,
+            "signatureType": self.signature_type
+        })
+    }
+}
+
+/// Reference hash through ethers `TypedData` (the original signing path)
+///
+/// Much slower than `OrderData::signing_hash`; kept to cross-check the
+/// native encoding in tests and to compare timings in `latency_bench`.
+pub fn typed_data_hash(order: &serde_json::Value, exchange: Exchange) -> Result<[u8; 32]> {
+    let typed_data = json!({
+        "types": {
+            "EIP712Domain": [
+                {"name": "name", "type": "string"},
+                {"name": "version", "type": "string"},
+                {"name": "chainId", "type": "uint256"},
+                {"name": "verifyingContract", "type": "address"}
+            ],
+            "Order": [
+                {"name": "salt", "type": "uint256"},
+                {"name": "maker", "type": "address"},
+                {"name": "signer", "type": "address"},
+                {"name": "taker", "type": "address"},
+                {"name": "tokenId", "type": "uint256"},
+                {"name": "makerAmount", "type": "uint256"},
+                {"name": "takerAmount", "type": "uint256"},
+                {"name": "expiration", "type": "uint256"},
+                {"name": "nonce", "type": "uint256"},
+                {"name": "feeRateBps", "type": "uint256"},
+                {"name": "side", "type": "uint8"},
+                {"name": "signatureType", "type": "uint8"}
+            ]
+        },
+        "primaryType": "Order",
+        "domain": {
+            "name": DOMAIN_NAME,
+            "version": DOMAIN_VERSION,
+            "chainId": CHAIN_ID,
+            "verifyingContract": format!("{:?}", exchange.address())
+        },
+        "message": order
+    });
+
+    let typed_data: TypedData = serde_json::from_value(typed_data)?;
+    Ok(typed_data.encode_eip712()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TEST_KEY;
+    use ethers::signers::{LocalWallet, Signer};
+    use ethers::types::H256;
+
+    fn sample_order(wallet: &LocalWallet) -> OrderData {
+        OrderData {
+            salt: U256::from(123456789u64),
+            maker: wallet.address(),
+            signer: wallet.address(),
+            taker: Address::zero(),
+            token_id: U256::from_dec_str(
+                "21742633143463906290569050155826241533067272736897614950488156847949938836455",
+            )
+            .unwrap(),
+            maker_amount: U256::from(48_000_000u64),
+            taker_amount: U256::from(100_000_000u64),
+            expiration: U256::from(1_700_000_000u64),
+            nonce: U256::zero(),
+            fee_rate_bps: U256::zero(),
+            side: 0,
+            signature_type: 0,
+        }
+    }
+
+    #[test]
+    fn test_native_hash_matches_typed_data() {
+        let wallet: LocalWallet = TEST_KEY.parse().unwrap();
+        let order = sample_order(&wallet);
+
+        for exchange in [Exchange::Ctf, Exchange::NegRisk] {
+            let native = order.signing_hash(exchange);
+            let reference = typed_data_hash(&order.to_json(), exchange).unwrap();
+            assert_eq!(native, reference);
+
+            let sig_native = wallet.sign_hash(H256::from(native)).unwrap();
+            let sig_reference = wallet.sign_hash(H256::from(reference)).unwrap();
+            assert_eq!(sig_native.to_vec(), sig_reference.to_vec());
+        }
+    }
+
+    #[test]
+    fn test_domains_differ_per_exchange() {
+        assert_ne!(
+            Exchange::Ctf.domain_separator(),
+            Exchange::NegRisk.domain_separator()
+        );
+    }
+}
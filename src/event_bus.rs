@@ -0,0 +1,275 @@
+//! Conflating event bus between the market socket and trading sessions
+//!
+//! Book updates are state, not history: a session that was busy posting
+//! orders only needs the latest top of book when it comes back, not every
+//! level change it missed. Each asset gets a latest-value slot
+//! (`tokio::sync::watch`) that the socket reader overwrites. Everything that
+//! must not be lost (trades, fills, tick size changes, book trust and
+//! connection lifecycle) goes on an unbounded queue. Publishing never
+//! awaits, so a slow consumer can't stall the reader; [`BusStats`] shows how
+//! far behind it is.
+
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::{mpsc, watch};
+
+use crate::websocket::WsEvent;
+
+/// Latest top of book for one asset
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BookTop {
+    pub asset_id: String,
+    pub best_bid: Option<Decimal>,
+    pub best_ask: Option<Decimal>,
+    /// False while the book is waiting for a resync
+    pub trusted: bool,
+    /// Bumped on every publish; a jump of more than one between reads means
+    /// updates were conflated
+    pub version: u64,
+    pub published_at: Option<Instant>,
+}
+
+/// Counters shared by a publisher and its stream
+#[derive(Default)]
+struct BusMetrics {
+    books_published: AtomicU64,
+    books_delivered: AtomicU64,
+    books_conflated: AtomicU64,
+    events_published: AtomicU64,
+    events_delivered: AtomicU64,
+    events_dropped: AtomicU64,
+    max_book_lag_us: AtomicU64,
+    max_event_lag_us: AtomicU64,
+}
+
+/// Point-in-time view of a stream's lag and loss
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BusStats {
+    pub books_published: u64,
+    pub books_delivered: u64,
+    /// Book versions overwritten before the consumer read them
+    pub books_conflated: u64,
+    pub events_published: u64,
+    pub events_delivered: u64,
+    /// Events published after the consumer went away
+    pub events_dropped: u64,
+    /// Events queued but not yet read
+    pub event_backlog: u64,
+    pub max_book_lag_ms: f64,
+    pub max_event_lag_ms: f64,
+}
+
+struct Queued {
+    event: WsEvent,
+    queued_at: Instant,
+}
+
+enum Wake {
+    Event(Option<Queued>),
+    Book(Option<usize>),
+}
+
+/// Create a connected publisher/stream pair for a set of assets
+pub fn market_channel(asset_ids: &[String]) -> (MarketPublisher, MarketStream) {
+    let metrics = Arc::new(BusMetrics::default());
+    let (events_tx, events_rx) = mpsc::unbounded_channel();
+
+    let mut senders = HashMap::new();
+    let mut receivers = Vec::new();
+    for asset_id in asset_ids {
+        let (tx, rx) = watch::channel(BookTop { asset_id: asset_id.clone(), ..Default::default() });
+        senders.insert(asset_id.clone(), tx);
+        receivers.push(rx);
+    }
+
+    (
+        MarketPublisher { books: senders, events: events_tx, metrics: metrics.clone() },
+        MarketStream { seen_versions: vec![0; receivers.len()], books: receivers, events: events_rx, metrics },
+    )
+}
+
+/// Writer side, owned by the socket reader; every method returns immediately
+pub struct MarketPublisher {
+    books: HashMap<String, watch::Sender<BookTop>>,
+    events: mpsc::UnboundedSender<Queued>,
+    metrics: Arc<BusMetrics>,
+}
+
+impl MarketPublisher {
+    pub fn has_asset(&self, asset_id: &str) -> bool {
+        self.books.contains_key(asset_id)
+    }
+
+    /// Overwrite an asset's top of book; unread older values are conflated
+    pub fn publish_book(&self, asset_id: &str, best_bid: Option<Decimal>, best_ask: Option<Decimal>, trusted: bool) {
+        let Some(tx) = self.books.get(asset_id) else { return };
+        tx.send_modify(|top| {
+            top.best_bid = best_bid;
+            top.best_ask = best_ask;
+            top.trusted = trusted;
+            top.version += 1;
+            top.published_at = Some(Instant::now());
+        });
+        self.metrics.books_published.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Queue an event that must be delivered
+    pub fn publish_event(&self, event: WsEvent) {
+        self.metrics.events_published.fetch_add(1, Ordering::Relaxed);
+        if self.events.send(Queued { event, queued_at: Instant::now() }).is_err() {
+            self.metrics.events_dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Reader side, owned by a trading session
+pub struct MarketStream {
+    books: Vec<watch::Receiver<BookTop>>,
+    seen_versions: Vec<u64>,
+    events: mpsc::UnboundedReceiver<Queued>,
+    metrics: Arc<BusMetrics>,
+}
+
+impl MarketStream {
+    /// Next event: queued events first, then [`WsEvent::Book`] for any asset
+    /// whose book changed since it was last read
+    ///
+    /// Returns `None` once the publisher is gone and the queue is drained.
+    pub async fn recv(&mut self) -> Option<WsEvent> {
+        loop {
+            if let Ok(queued) = self.events.try_recv() {
+                return Some(self.deliver_event(queued));
+            }
+            if let Some(top) = self.next_changed_book() {
+                return Some(WsEvent::Book(top));
+            }
+
+            if self.books.is_empty() {
+                return self.events.recv().await.map(|q| self.deliver_event(q));
+            }
+            // `changed()` marks the value seen, so the woken slot is read directly
+            let changed = futures_util::future::select_all(
+                self.books.iter_mut().map(|rx| Box::pin(rx.changed())),
+            );
+            let woken = tokio::select! {
+                biased;
+                queued = self.events.recv() => Wake::Event(queued),
+                (result, idx, _) = changed => Wake::Book(result.ok().map(|_| idx)),
+            };
+            match woken {
+                Wake::Event(queued) => return queued.map(|q| self.deliver_event(q)),
+                Wake::Book(Some(idx)) => return Some(WsEvent::Book(self.take_book(idx))),
+                Wake::Book(None) => {} // Publisher gone; the queue reports the end
+            }
+        }
+    }
+
+    pub fn stats(&self) -> BusStats {
+        let m = &self.metrics;
+        let events_published = m.events_published.load(Ordering::Relaxed);
+        let events_delivered = m.events_delivered.load(Ordering::Relaxed);
+        let events_dropped = m.events_dropped.load(Ordering::Relaxed);
+        BusStats {
+            books_published: m.books_published.load(Ordering::Relaxed),
+            books_delivered: m.books_delivered.load(Ordering::Relaxed),
+            books_conflated: m.books_conflated.load(Ordering::Relaxed),
+            events_published,
+            events_delivered,
+            events_dropped,
+            event_backlog: events_published.saturating_sub(events_delivered + events_dropped),
+            max_book_lag_ms: m.max_book_lag_us.load(Ordering::Relaxed) as f64 / 1000.0,
+            max_event_lag_ms: m.max_event_lag_us.load(Ordering::Relaxed) as f64 / 1000.0,
+        }
+    }
+
+    fn deliver_event(&self, queued: Queued) -> WsEvent {
+        self.metrics.events_delivered.fetch_add(1, Ordering::Relaxed);
+        record_max(&self.metrics.max_event_lag_us, queued.queued_at);
+        queued.event
+    }
+
+    fn next_changed_book(&mut self) -> Option<BookTop> {
+        let idx = self.books.iter().position(|rx| rx.has_changed().unwrap_or(false))?;
+        Some(self.take_book(idx))
+    }
+
+    fn take_book(&mut self, idx: usize) -> BookTop {
+        let top = self.books[idx].borrow_and_update().clone();
+
+        let skipped = top.version.saturating_sub(self.seen_versions[idx] + 1);
+        self.seen_versions[idx] = top.version;
+        self.metrics.books_delivered.fetch_add(1, Ordering::Relaxed);
+        self.metrics.books_conflated.fetch_add(skipped, Ordering::Relaxed);
+        if let Some(published_at) = top.published_at {
+            record_max(&self.metrics.max_book_lag_us, published_at);
+        }
+        top
+    }
+}
+
+fn record_max(slot: &AtomicU64, since: Instant) {
+    let lag = since.elapsed().as_micros() as u64;
+    slot.fetch_max(lag, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[tokio::test]
+    async fn test_books_conflate_and_events_are_lossless() {
+        let (publisher, mut stream) = market_channel(&["up".to_string(), "down".to_string()]);
+
+        // Consumer is busy: 100 book updates and 3 events pile up
+        for i in 0..100 {
+            publisher.publish_book("up", Some(Decimal::from(i) / dec!(1000)), Some(dec!(0.6)), true);
+        }
+        publisher.publish_event(WsEvent::Connected);
+        publisher.publish_event(WsEvent::Error("x".to_string()));
+        publisher.publish_event(WsEvent::Disconnected);
+        publisher.publish_book("unknown", None, None, true);
+
+        assert!(matches!(stream.recv().await, Some(WsEvent::Connected)));
+        assert!(matches!(stream.recv().await, Some(WsEvent::Error(e)) if e == "x"));
+        assert!(matches!(stream.recv().await, Some(WsEvent::Disconnected)));
+        match stream.recv().await {
+            Some(WsEvent::Book(top)) => {
+                assert_eq!(top.asset_id, "up");
+                assert_eq!(top.best_bid, Some(dec!(0.099)));
+                assert_eq!(top.version, 100);
+            }
+            other => panic!("expected book, got {:?}", other),
+        }
+
+        let stats = stream.stats();
+        assert_eq!(stats.books_published, 100);
+        assert_eq!(stats.books_delivered, 1);
+        assert_eq!(stats.books_conflated, 99);
+        assert_eq!(stats.events_delivered, 3);
+        assert_eq!(stats.event_backlog, 0);
+    }
+
+    #[tokio::test]
+    async fn test_stream_wakes_on_book_and_ends_with_publisher() {
+        let (publisher, mut stream) = market_channel(&["up".to_string()]);
+
+        let reader = tokio::spawn(async move {
+            let first = stream.recv().await;
+            let end = stream.recv().await;
+            (first, end, stream.stats())
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        publisher.publish_book("up", Some(dec!(0.45)), Some(dec!(0.47)), false);
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        drop(publisher);
+
+        let (first, end, stats) = reader.await.unwrap();
+        assert!(matches!(first, Some(WsEvent::Book(top)) if !top.trusted && top.best_ask == Some(dec!(0.47))));
+        assert!(end.is_none());
+        assert_eq!(stats.books_conflated, 0);
+    }
+}
@@ -0,0 +1,189 @@
+//! Fair value of the Up outcome
+//!
+//! A 15-minute up/down market is a digital option struck at the window's
+//! open price. With a driftless random walk in log price, the chance the
+//! close lands at or above the open is `N(ln(S/S0) / (sigma * sqrt(T)))`,
+//! where sigma comes from the asset's realized volatility and T is the time left.
+//! Strategies compare that probability with the best asks and trade the
+//! edge rather than a raw percentage move. Once we have history, a fitted
+//! [`CalibrationParams`] replaces the normal CDF (see [`crate::calibration`]).
+
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use tracing::{info, warn};
+
+use crate::calibration::CalibrationParams;
+
+/// Horizon `vol_5m_pct` is quoted over
+const VOL_HORIZON_SECS: f64 = 300.0;
+
+/// Digital-option pricing parameters
+#[derive(Debug, Clone)]
+pub struct FairValueModel {
+    /// 5m volatility (percent) to assume before the candles have enough history
+    pub default_vol_5m_pct: Decimal,
+    /// Floor on 5m volatility so a quiet minute doesn't read as certainty
+    pub min_vol_5m_pct: Decimal,
+    /// Empirical correction fitted from our own logs
+    pub calibration: Option<CalibrationParams>,
+}
+
+impl Default for FairValueModel {
+    fn default() -> Self {
+        Self {
+            // ~50% annualized BTC vol scaled to 5 minutes
+            default_vol_5m_pct: dec!(0.15),
+            min_vol_5m_pct: dec!(0.02),
+            calibration: None,
+        }
+    }
+}
+
+impl FairValueModel {
+    /// Default model with the calibration at `path`, if one has been fitted
+    pub fn load(path: &str) -> Self {
+        let calibration = match CalibrationParams::load(path) {
+            Ok(params) => {
+                info!(
+                    "Loaded fair-value calibration from {} ({} windows; Brier {:.4} vs {:.4} uncalibrated on the {} latest, held out)",
+                    path, params.windows, params.holdout_brier_fitted, params.holdout_brier_model, params.holdout_windows
+                );
+                Some(params)
+            }
+            Err(e) => {
+                warn!("No fair-value calibration at {} ({}), using the uncalibrated model", path, e);
+                None
+            }
+        };
+        Self { calibration, ..Self::default() }
+    }
+
+    /// Move since open in standard deviations of the remaining time's volatility
+    ///
+    /// `None` if either price is non-positive or the window is over.
+    pub fn normalized_move(
+        &self,
+        open: Decimal,
+        current: Decimal,
+        seconds_left: i64,
+        vol_5m_pct: Option<Decimal>,
+    ) -> Option<f64> {
+        if open <= Decimal::ZERO || current <= Decimal::ZERO || seconds_left <= 0 {
+            return None;
+        }
+        let vol = vol_5m_pct.unwrap_or(self.default_vol_5m_pct).max(self.min_vol_5m_pct);
+        let sigma = vol.to_f64()? / 100.0 * (seconds_left as f64 / VOL_HORIZON_SECS).sqrt();
+        Some((current.to_f64()? / open.to_f64()?).ln() / sigma)
+    }
+
+    /// Probability the window closes at or above `open`
+    ///
+    /// `None` if either price is non-positive.
+    pub fn up_prob(
+        &self,
+        open: Decimal,
+        current: Decimal,
+        seconds_left: i64,
+        vol_5m_pct: Option<Decimal>,
+    ) -> Option<Decimal> {
+        if open > Decimal::ZERO && current > Decimal::ZERO && seconds_left <= 0 {
+            return Some(if current >= open { Decimal::ONE } else { Decimal::ZERO });
+        }
+        let z = self.normalized_move(open, current, seconds_left, vol_5m_pct)?;
+        let prob = match &self.calibration {
+            Some(params) => params.prob(z, minute_of_window(seconds_left)),
+            None => normal_cdf(z),
+        };
+        Decimal::from_f64(prob).map(|p| p.round_dp(4))
+    }
+}
+
+/// Minutes into a 15-minute window with `seconds_left` to go
+pub fn minute_of_window(seconds_left: i64) -> f64 {
+    15.0 - seconds_left as f64 / 60.0
+}
+
+/// Taker fee per share for a fill at `price`, charged as `fee_rate * min(price, 1 - price)`
+pub fn taker_fee(price: Decimal, fee_rate: Decimal) -> Decimal {
+    fee_rate * price.min(Decimal::ONE - price)
+}
+
+/// Expected profit per share of taking `ask` when the outcome is worth `prob`,
+/// after the taker fee
+pub fn edge(prob: Decimal, ask: Decimal, fee_rate: Decimal) -> Decimal {
+    prob - ask - taker_fee(ask, fee_rate)
+}
+
+/// Standard normal CDF
+pub fn normal_cdf(x: f64) -> f64 {
+    0.5 * erfc(-x / std::f64::consts::SQRT_2)
+}
+
+/// Complementary error function, fractional error below 1.2e-7
+/// (Numerical Recipes `erfcc`)
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let poly = -1.26551223
+        + t * (1.00002368
+            + t * (0.37409196
+                + t * (0.09678418
+                    + t * (-0.18628806
+                        + t * (0.27886807
+                            + t * (-1.13520398 + t * (1.48851587 + t * (-0.82215223 + t * 0.17087277))))))));
+    let r = t * (-z * z + poly).exp();
+    if x >= 0.0 {
+        r
+    } else {
+        2.0 - r
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normal_cdf() {
+        assert!((normal_cdf(0.0) - 0.5).abs() < 1e-7);
+        assert!((normal_cdf(1.0) - 0.841345).abs() < 1e-6);
+        assert!((normal_cdf(-1.96) - 0.024998).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_up_prob_and_edge() {
+        let model = FairValueModel::default();
+        let open = dec!(100000);
+
+        // Flat: coin flip regardless of time or vol
+        assert_eq!(model.up_prob(open, open, 600, Some(dec!(0.1))), Some(dec!(0.5)));
+
+        // 0.1% 5m vol over 5m left is a 0.1% sigma: +1 sigma in log terms
+        let one_sigma = dec!(100100.05);
+        assert_eq!(model.up_prob(open, one_sigma, 300, Some(dec!(0.1))), Some(dec!(0.8413)));
+        // Same move with four times the time left is only +0.5 sigma
+        assert_eq!(model.up_prob(open, one_sigma, 1200, Some(dec!(0.1))), Some(dec!(0.6915)));
+        // Down move mirrors it
+        let down = dec!(99900.05);
+        assert_eq!(model.up_prob(open, down, 300, Some(dec!(0.1))), Some(dec!(0.1587)));
+
+        // Resolved
+        assert_eq!(model.up_prob(open, open, 0, None), Some(Decimal::ONE));
+        assert_eq!(model.up_prob(open, down, 0, None), Some(Decimal::ZERO));
+
+        assert_eq!(edge(dec!(0.8413), dec!(0.75), Decimal::ZERO), dec!(0.0913));
+        assert_eq!(edge(dec!(0.1587), dec!(0.25), Decimal::ZERO), dec!(-0.0913));
+    }
+
+    #[test]
+    fn test_edge_after_taker_fee() {
+        // 10% of min(p, 1 - p): 2.5c at 75c, 5c at 50c
+        assert_eq!(taker_fee(dec!(0.75), dec!(0.10)), dec!(0.025));
+        assert_eq!(taker_fee(dec!(0.50), dec!(0.10)), dec!(0.05));
+
+        // 4c before fees clears a 3c minimum, 1.5c after doesn't
+        assert_eq!(edge(dec!(0.79), dec!(0.75), Decimal::ZERO), dec!(0.04));
+        assert_eq!(edge(dec!(0.79), dec!(0.75), dec!(0.10)), dec!(0.015));
+    }
+}
@@ -0,0 +1,230 @@
+//! First-arrival deduplication across redundant market-data connections
+//!
+//! With several sockets streaming the same assets, every update arrives
+//! once per connection. Updates are keyed by event type, asset, book hash
+//! and exchange timestamp; the first copy is forwarded and later copies are
+//! dropped. Each arrival is also scored per connection (wins, lag behind
+//! the winner, exchange-to-receive latency) to show which route is faster.
+
+use parking_lot::Mutex;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use crate::websocket::parse_timestamp_ms;
+
+/// Keys are remembered this long; a copy arriving later is treated as new
+const DEDUP_WINDOW: Duration = Duration::from_secs(10);
+
+/// Identity of a market channel update, or `None` if it can't be told apart
+/// from other updates (no hash or timestamp)
+pub fn dedup_key(msg: &serde_json::Value) -> Option<String> {
+    let s = |v: &serde_json::Value, keys: &[&str]| {
+        keys.iter().find_map(|k| v.get(*k).and_then(|x| x.as_str())).unwrap_or("").to_string()
+    };
+
+    let timestamp = parse_timestamp_ms(msg.get("timestamp").or_else(|| msg.get("t")));
+    let event_type = match msg.get("event_type").and_then(|e| e.as_str()) {
+        Some(e) => e,
+        None if msg.get("pc").is_some() => "price_change",
+        None => return None,
+    };
+
+    let mut key = format!("{}|{}", event_type, timestamp.map(|t| t.to_string()).unwrap_or_default());
+    let mut has_hash = false;
+    if let Some(entries) = msg.get("pc").or_else(|| msg.get("price_changes")).and_then(|p| p.as_array()) {
+        // Batched levels: every asset/hash pair in the message
+        for entry in entries {
+            let hash = s(entry, &["h", "hash"]);
+            has_hash |= !hash.is_empty();
+            key.push_str(&format!("|{}:{}", s(entry, &["a", "asset_id"]), hash));
+        }
+    } else {
+        let hash = s(msg, &["hash"]);
+        has_hash = !hash.is_empty();
+        key.push_str(&format!("|{}:{}", s(msg, &["asset_id"]), hash));
+    }
+    if event_type == "last_trade_price" {
+        // Several prints can share a millisecond
+        key.push_str(&format!("|{}|{}|{}", s(msg, &["price"]), s(msg, &["size"]), s(msg, &["side"])));
+    }
+
+    (timestamp.is_some() || has_hash).then_some(key)
+}
+
+/// Arrival statistics for one connection
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RouteStats {
+    pub route: String,
+    pub messages: u64,
+    /// Updates this connection delivered first
+    pub first_arrivals: u64,
+    pub duplicates: u64,
+    /// How far behind the winning connection its duplicates arrived
+    pub mean_lag_ms: f64,
+    pub max_lag_ms: f64,
+    /// Receive time minus exchange timestamp (includes clock skew; compare across routes)
+    pub mean_exchange_latency_ms: Option<f64>,
+}
+
+impl RouteStats {
+    /// Share of keyed updates this connection won
+    pub fn win_rate(&self) -> f64 {
+        let keyed = self.first_arrivals + self.duplicates;
+        if keyed == 0 {
+            0.0
+        } else {
+            self.first_arrivals as f64 / keyed as f64
+        }
+    }
+}
+
+#[derive(Default)]
+struct RouteCounters {
+    messages: u64,
+    first_arrivals: u64,
+    duplicates: u64,
+    lag_sum_ms: f64,
+    max_lag_ms: f64,
+    latency_sum_ms: f64,
+    latency_samples: u64,
+}
+
+struct Inner {
+    first_seen: HashMap<String, Instant>,
+    order: VecDeque<(Instant, String)>,
+    routes: Vec<RouteCounters>,
+}
+
+/// Shared first-arrival filter for a set of connections
+pub struct ArrivalTracker {
+    names: Vec<String>,
+    inner: Mutex<Inner>,
+}
+
+impl ArrivalTracker {
+    /// One entry per connection, in connection index order
+    pub fn new(names: Vec<String>) -> Self {
+        let routes = names.iter().map(|_| RouteCounters::default()).collect();
+        Self {
+            names,
+            inner: Mutex::new(Inner { first_seen: HashMap::new(), order: VecDeque::new(), routes }),
+        }
+    }
+
+    /// Record that connection `conn` received an update; true if it's the first copy
+    ///
+    /// Unkeyed updates (`key` is `None`) are always forwarded.
+    pub fn observe(&self, conn: usize, key: Option<&str>, exchange_ts_ms: Option<i64>) -> bool {
+        self.observe_at(conn, key, exchange_ts_ms, Instant::now(), chrono::Utc::now().timestamp_millis())
+    }
+
+    pub fn observe_at(
+        &self,
+        conn: usize,
+        key: Option<&str>,
+        exchange_ts_ms: Option<i64>,
+        now: Instant,
+        now_ms: i64,
+    ) -> bool {
+        let mut inner = self.inner.lock();
+        let inner = &mut *inner;
+
+        while inner.order.front().is_some_and(|(at, _)| now.duration_since(*at) > DEDUP_WINDOW) {
+            if let Some((_, old)) = inner.order.pop_front() {
+                inner.first_seen.remove(&old);
+            }
+        }
+
+        let Some(counters) = inner.routes.get_mut(conn) else { return true };
+        counters.messages += 1;
+        if let Some(ts) = exchange_ts_ms {
+            counters.latency_sum_ms += (now_ms - ts) as f64;
+            counters.latency_samples += 1;
+        }
+
+        let Some(key) = key else { return true };
+        match inner.first_seen.get(key) {
+            Some(first) => {
+                let lag_ms = now.duration_since(*first).as_secs_f64() * 1000.0;
+                counters.duplicates += 1;
+                counters.lag_sum_ms += lag_ms;
+                counters.max_lag_ms = counters.max_lag_ms.max(lag_ms);
+                false
+            }
+            None => {
+                counters.first_arrivals += 1;
+                inner.first_seen.insert(key.to_string(), now);
+                inner.order.push_back((now, key.to_string()));
+                true
+            }
+        }
+    }
+
+    pub fn stats(&self) -> Vec<RouteStats> {
+        let inner = self.inner.lock();
+        self.names
+            .iter()
+            .zip(&inner.routes)
+            .map(|(name, c)| RouteStats {
+                route: name.clone(),
+                messages: c.messages,
+                first_arrivals: c.first_arrivals,
+                duplicates: c.duplicates,
+                mean_lag_ms: if c.duplicates > 0 { c.lag_sum_ms / c.duplicates as f64 } else { 0.0 },
+                max_lag_ms: c.max_lag_ms,
+                mean_exchange_latency_ms: (c.latency_samples > 0)
+                    .then(|| c.latency_sum_ms / c.latency_samples as f64),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> serde_json::Value {
+        serde_json::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_dedup_key() {
+        let book = parse(r#"{"event_type":"book","asset_id":"1","market":"0xm","hash":"abc","timestamp":"1750000000000","bids":[],"asks":[]}"#);
+        assert_eq!(dedup_key(&book).as_deref(), Some("book|1750000000000|1:abc"));
+
+        let pc = parse(r#"{"m":"0xm","pc":[{"a":"1","p":"0.5","s":"10","si":"BUY","h":"h1"},{"a":"2","p":"0.5","s":"0","si":"SELL","h":"h2"}],"t":"1750000000001"}"#);
+        assert_eq!(dedup_key(&pc).as_deref(), Some("price_change|1750000000001|1:h1|2:h2"));
+
+        // Two prints in the same millisecond stay distinct
+        let t1 = parse(r#"{"event_type":"last_trade_price","asset_id":"1","price":"0.5","size":"10","side":"BUY","timestamp":"5"}"#);
+        let t2 = parse(r#"{"event_type":"last_trade_price","asset_id":"1","price":"0.5","size":"12","side":"BUY","timestamp":"5"}"#);
+        assert_ne!(dedup_key(&t1), dedup_key(&t2));
+
+        assert_eq!(dedup_key(&parse(r#"{"type":"subscribed"}"#)), None);
+        assert_eq!(dedup_key(&parse(r#"{"event_type":"book","asset_id":"1"}"#)), None);
+    }
+
+    #[test]
+    fn test_first_arrival_wins_and_scores_routes() {
+        let tracker = ArrivalTracker::new(vec!["fast".to_string(), "slow".to_string()]);
+        let t0 = Instant::now();
+        let ms = |n: u64| t0 + Duration::from_millis(n);
+
+        assert!(tracker.observe_at(0, Some("k1"), Some(1_000), ms(0), 1_020));
+        assert!(!tracker.observe_at(1, Some("k1"), Some(1_000), ms(8), 1_028));
+        assert!(tracker.observe_at(0, Some("k2"), Some(1_050), ms(50), 1_070));
+        assert!(!tracker.observe_at(1, Some("k2"), Some(1_050), ms(54), 1_074));
+        // Unkeyed messages always pass
+        assert!(tracker.observe_at(1, None, None, ms(60), 1_080));
+        // Keys expire after the window
+        assert!(tracker.observe_at(1, Some("k1"), Some(1_000), ms(11_000), 12_000));
+
+        let stats = tracker.stats();
+        assert_eq!((stats[0].first_arrivals, stats[0].duplicates), (2, 0));
+        assert_eq!((stats[1].first_arrivals, stats[1].duplicates, stats[1].messages), (1, 2, 4));
+        assert_eq!(stats[1].mean_lag_ms, 6.0);
+        assert_eq!(stats[1].max_lag_ms, 8.0);
+        assert_eq!(stats[0].mean_exchange_latency_ms, Some(20.0));
+        assert_eq!(stats[0].win_rate(), 1.0);
+    }
+}
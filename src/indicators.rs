@@ -0,0 +1,375 @@
+//! Candles and technical indicators for the spot price feeds
+//!
+//! Any stream of `(timestamp, price, volume)` (a single [`PriceSource`] or
+//! the composite) can be rolled into fixed-interval OHLCV candles with a
+//! [`CandleSeries`]; [`CandleSet`] keeps the 1s/5s/1m series together. The
+//! indicator functions work on plain slices of closes or candles so strategies
+//! can use them on any series, and [`SpotIndicators`] is the snapshot the
+//! bots read from the feed.
+//!
+//! Prices and averages stay in `Decimal`; volatility and z-score need logs
+//! and square roots, so they're computed in `f64` and converted back.
+//!
+//! [`PriceSource`]: crate::price_source::PriceSource
+
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// One OHLCV bar
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candle {
+    /// Bucket start, unix millis
+    pub start_ms: i64,
+    pub open: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub close: Decimal,
+    /// Traded size (ticker and book updates carry none)
+    pub volume: Decimal,
+    /// Sum of price * size, for VWAP
+    pub turnover: Decimal,
+    pub ticks: u32,
+}
+
+impl Candle {
+    fn new(start_ms: i64, price: Decimal) -> Self {
+        Self {
+            start_ms,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume: Decimal::ZERO,
+            turnover: Decimal::ZERO,
+            ticks: 0,
+        }
+    }
+
+    fn update(&mut self, price: Decimal, volume: Decimal) {
+        self.high = self.high.max(price);
+        self.low = self.low.min(price);
+        self.close = price;
+        self.volume += volume;
+        self.turnover += price * volume;
+        self.ticks += 1;
+    }
+}
+
+/// Rolling candles at one interval
+#[derive(Debug, Clone)]
+pub struct CandleSeries {
+    interval_ms: i64,
+    capacity: usize,
+    candles: VecDeque<Candle>,
+}
+
+impl CandleSeries {
+    pub fn new(interval: Duration, capacity: usize) -> Self {
+        Self { interval_ms: interval.as_millis().max(1) as i64, capacity, candles: VecDeque::with_capacity(capacity) }
+    }
+
+    /// Add a tick; intervals with no ticks are filled with flat candles at the
+    /// previous close so volatility sees the quiet stretches
+    pub fn update(&mut self, ts_ms: i64, price: Decimal, volume: Decimal) {
+        let bucket = ts_ms.div_euclid(self.interval_ms) * self.interval_ms;
+        match self.candles.back_mut() {
+            // Late ticks (out-of-order sources) land in the open candle
+            Some(last) if bucket <= last.start_ms => last.update(price, volume),
+            Some(last) => {
+                let (prev_start, prev_close) = (last.start_ms, last.close);
+                let missing = (bucket - prev_start) / self.interval_ms - 1;
+                if missing as usize >= self.capacity {
+                    self.candles.clear();
+                } else {
+                    for i in 1..=missing {
+                        self.candles.push_back(Candle::new(prev_start + i * self.interval_ms, prev_close));
+                    }
+                }
+                self.push(bucket, price, volume);
+            }
+            None => self.push(bucket, price, volume),
+        }
+        while self.candles.len() > self.capacity {
+            self.candles.pop_front();
+        }
+    }
+
+    fn push(&mut self, bucket: i64, price: Decimal, volume: Decimal) {
+        let mut candle = Candle::new(bucket, price);
+        candle.update(price, volume);
+        self.candles.push_back(candle);
+    }
+
+    /// The candle still being built
+    pub fn current(&self) -> Option<&Candle> {
+        self.candles.back()
+    }
+
+    /// Up to the last `n` finished candles, oldest first
+    pub fn completed(&self, n: usize) -> Vec<Candle> {
+        let done = self.candles.len().saturating_sub(1);
+        self.candles.iter().take(done).skip(done.saturating_sub(n)).cloned().collect()
+    }
+
+    /// Closes of the last `n` finished candles, oldest first
+    pub fn closes(&self, n: usize) -> Vec<Decimal> {
+        self.completed(n).iter().map(|c| c.close).collect()
+    }
+}
+
+/// 1s, 5s and 1m candles over the same ticks
+#[derive(Debug, Clone)]
+pub struct CandleSet {
+    pub s1: CandleSeries,
+    pub s5: CandleSeries,
+    pub m1: CandleSeries,
+}
+
+impl Default for CandleSet {
+    fn default() -> Self {
+        Self {
+            s1: CandleSeries::new(Duration::from_secs(1), 900),
+            s5: CandleSeries::new(Duration::from_secs(5), 720),
+            m1: CandleSeries::new(Duration::from_secs(60), 240),
+        }
+    }
+}
+
+impl CandleSet {
+    pub fn update(&mut self, ts_ms: i64, price: Decimal, volume: Decimal) {
+        self.s1.update(ts_ms, price, volume);
+        self.s5.update(ts_ms, price, volume);
+        self.m1.update(ts_ms, price, volume);
+    }
+}
+
+fn to_f64(d: Decimal) -> Option<f64> {
+    d.to_f64()
+}
+
+fn from_f64(x: f64) -> Option<Decimal> {
+    x.is_finite().then(|| Decimal::from_f64(x)).flatten()
+}
+
+/// Exponential moving average, seeded with the SMA of the first `period` values
+pub fn ema(values: &[Decimal], period: usize) -> Option<Decimal> {
+    if period == 0 || values.len() < period {
+        return None;
+    }
+    let alpha = dec!(2) / Decimal::from(period + 1);
+    let seed = values[..period].iter().copied().sum::<Decimal>() / Decimal::from(period);
+    Some(values[period..].iter().fold(seed, |ema, v| ema + alpha * (*v - ema)))
+}
+
+/// Wilder's RSI (0-100); needs `period + 1` closes
+pub fn rsi(closes: &[Decimal], period: usize) -> Option<Decimal> {
+    if period == 0 || closes.len() <= period {
+        return None;
+    }
+    let n = Decimal::from(period);
+    let changes: Vec<Decimal> = closes.windows(2).map(|w| w[1] - w[0]).collect();
+    let mut gain = changes[..period].iter().map(|c| (*c).max(Decimal::ZERO)).sum::<Decimal>() / n;
+    let mut loss = changes[..period].iter().map(|c| (-*c).max(Decimal::ZERO)).sum::<Decimal>() / n;
+    for c in &changes[period..] {
+        gain = (gain * (n - Decimal::ONE) + (*c).max(Decimal::ZERO)) / n;
+        loss = (loss * (n - Decimal::ONE) + (-*c).max(Decimal::ZERO)) / n;
+    }
+    if loss.is_zero() {
+        return Some(if gain.is_zero() { dec!(50) } else { dec!(100) });
+    }
+    Some(dec!(100) - dec!(100) / (Decimal::ONE + gain / loss))
+}
+
+/// Wilder's average true range; needs `period + 1` candles
+pub fn atr(candles: &[Candle], period: usize) -> Option<Decimal> {
+    if period == 0 || candles.len() <= period {
+        return None;
+    }
+    let n = Decimal::from(period);
+    let tr: Vec<Decimal> = candles
+        .windows(2)
+        .map(|w| {
+            let (prev, c) = (&w[0], &w[1]);
+            (c.high - c.low).max((c.high - prev.close).abs()).max((c.low - prev.close).abs())
+        })
+        .collect();
+    let seed = tr[..period].iter().copied().sum::<Decimal>() / n;
+    Some(tr[period..].iter().fold(seed, |atr, t| (atr * (n - Decimal::ONE) + *t) / n))
+}
+
+/// Close-to-close volatility: sample std dev of log returns per candle
+pub fn close_to_close_vol(closes: &[Decimal]) -> Option<Decimal> {
+    let returns: Vec<f64> = closes
+        .windows(2)
+        .map(|w| Some((to_f64(w[1])? / to_f64(w[0])?).ln()))
+        .collect::<Option<_>>()?;
+    if returns.len() < 2 {
+        return None;
+    }
+    let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+    let var = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (returns.len() - 1) as f64;
+    from_f64(var.sqrt())
+}
+
+/// Parkinson high-low volatility per candle
+pub fn parkinson_vol(candles: &[Candle]) -> Option<Decimal> {
+    if candles.is_empty() {
+        return None;
+    }
+    let sum: f64 = candles
+        .iter()
+        .map(|c| Some((to_f64(c.high)? / to_f64(c.low)?).ln().powi(2)))
+        .sum::<Option<f64>>()?;
+    from_f64((sum / (4.0 * candles.len() as f64 * std::f64::consts::LN_2)).sqrt())
+}
+
+/// How many standard deviations the last value sits from the mean of all of them
+pub fn zscore(values: &[Decimal]) -> Option<Decimal> {
+    let xs: Vec<f64> = values.iter().map(|v| to_f64(*v)).collect::<Option<_>>()?;
+    let last = *xs.last()?;
+    let mean = xs.iter().sum::<f64>() / xs.len() as f64;
+    let sd = (xs.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / xs.len() as f64).sqrt();
+    if sd == 0.0 {
+        return None;
+    }
+    from_f64((last - mean) / sd)
+}
+
+/// Volume-weighted average price; `None` if nothing traded
+pub fn vwap(candles: &[Candle]) -> Option<Decimal> {
+    let volume: Decimal = candles.iter().map(|c| c.volume).sum();
+    if volume.is_zero() {
+        return None;
+    }
+    Some(candles.iter().map(|c| c.turnover).sum::<Decimal>() / volume)
+}
+
+/// Scale a per-candle volatility to `n` candles, in percent
+fn horizon_pct(per_candle: Decimal, n: usize) -> Option<Decimal> {
+    from_f64(to_f64(per_candle)? * (n as f64).sqrt() * 100.0)
+}
+
+/// Indicator snapshot for strategies
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpotIndicators {
+    /// EMA(12) and EMA(26) of 5s closes (~1m and ~2m)
+    pub ema_fast: Option<Decimal>,
+    pub ema_slow: Option<Decimal>,
+    /// RSI(14) of 1m closes
+    pub rsi_14: Option<Decimal>,
+    /// ATR(14) of 1m candles, in dollars
+    pub atr_14: Option<Decimal>,
+    /// Close-to-close volatility of the last 5m of 1s candles, scaled to 5m, in percent
+    pub vol_5m_pct: Option<Decimal>,
+    /// Parkinson volatility of the last 5m of 5s candles, scaled to 5m, in percent
+    pub parkinson_5m_pct: Option<Decimal>,
+    /// Z-score of the latest 1s close against the last minute
+    pub zscore_1m: Option<Decimal>,
+    /// VWAP over the last 15 1m candles (traded volume only)
+    pub vwap_15m: Option<Decimal>,
+}
+
+impl SpotIndicators {
+    pub fn from_candles(candles: &CandleSet) -> Self {
+        let s5_closes = candles.s5.closes(120);
+        let m1 = candles.m1.completed(60);
+        let m1_closes: Vec<Decimal> = m1.iter().map(|c| c.close).collect();
+        let s1_5m = candles.s1.closes(301);
+        let s5_5m = candles.s5.completed(60);
+        let mut recent_m1 = candles.m1.completed(14);
+        recent_m1.extend(candles.m1.current().cloned());
+
+        Self {
+            ema_fast: ema(&s5_closes, 12),
+            ema_slow: ema(&s5_closes, 26),
+            rsi_14: rsi(&m1_closes, 14),
+            atr_14: atr(&m1, 14),
+            vol_5m_pct: close_to_close_vol(&s1_5m).and_then(|v| horizon_pct(v, s1_5m.len() - 1)),
+            parkinson_5m_pct: parkinson_vol(&s5_5m).and_then(|v| horizon_pct(v, s5_5m.len())),
+            zscore_1m: zscore(&candles.s1.closes(60)),
+            vwap_15m: vwap(&recent_m1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decs(xs: &[f64]) -> Vec<Decimal> {
+        xs.iter().map(|x| Decimal::from_f64(*x).unwrap()).collect()
+    }
+
+    fn close_to(a: Option<Decimal>, b: f64, tol: f64) -> bool {
+        a.and_then(|a| a.to_f64()).is_some_and(|a| (a - b).abs() < tol)
+    }
+
+    fn bar(high: f64, low: f64, close: f64) -> Candle {
+        let mut c = Candle::new(0, Decimal::from_f64(close).unwrap());
+        c.high = Decimal::from_f64(high).unwrap();
+        c.low = Decimal::from_f64(low).unwrap();
+        c
+    }
+
+    #[test]
+    fn test_indicators_match_reference_values() {
+        let values: Vec<Decimal> = (1..=10).map(Decimal::from).collect();
+        assert_eq!(ema(&values, 3), Some(dec!(9)));
+        assert_eq!(ema(&values[..2], 3), None);
+
+        // Wilder's RSI worked example (StockCharts): 70.46 on the 15th close, 57.92 on the 20th
+        let closes = decs(&[
+            44.34, 44.09, 44.15, 43.61, 44.33, 44.83, 45.10, 45.42, 45.84, 46.08, 45.89, 46.03, 45.61, 46.28, 46.28,
+            46.00, 46.03, 46.41, 46.22, 45.64,
+        ]);
+        assert!(close_to(rsi(&closes[..15], 14), 70.4641, 1e-3));
+        assert!(close_to(rsi(&closes, 14), 57.9150, 1e-3));
+
+        // ATR worked example (StockCharts): 0.5679 after the first 14 true ranges
+        let highs = [48.70, 48.72, 48.90, 48.87, 48.82, 49.05, 49.20, 49.35, 49.92, 50.19, 50.12, 49.66, 49.88, 50.19, 50.36];
+        let lows = [47.79, 48.14, 48.39, 48.37, 48.24, 48.64, 48.94, 48.86, 49.50, 49.87, 49.20, 48.90, 49.43, 49.73, 49.26];
+        let cls = [48.16, 48.61, 48.75, 48.63, 48.74, 49.03, 49.07, 49.32, 49.91, 50.13, 49.53, 49.50, 49.75, 50.03, 50.31];
+        let bars: Vec<Candle> = (0..15).map(|i| bar(highs[i], lows[i], cls[i])).collect();
+        assert!(close_to(atr(&bars, 14), 0.567857, 1e-4));
+        assert!(close_to(atr(&bars, 5), 0.656171, 1e-4));
+
+        assert!(close_to(close_to_close_vol(&decs(&[100.0, 101.0, 100.5, 102.0, 101.0])), 0.0117678, 1e-6));
+        let hl = [bar(101.0, 99.0, 100.0), bar(102.0, 100.0, 101.0), bar(100.5, 99.5, 100.0)];
+        assert!(close_to(parkinson_vol(&hl), 0.0103567, 1e-6));
+        assert!(close_to(zscore(&decs(&[1.0, 2.0, 3.0, 4.0, 10.0])), 1.897367, 1e-5));
+        assert_eq!(zscore(&decs(&[5.0, 5.0])), None);
+    }
+
+    #[test]
+    fn test_candles_roll_fill_gaps_and_vwap() {
+        let mut series = CandleSeries::new(Duration::from_secs(1), 10);
+        series.update(1_000, dec!(100), dec!(1));
+        series.update(1_400, dec!(103), dec!(0));
+        series.update(1_900, dec!(99), dec!(3));
+        // Nothing in the 2s bucket; the 3s bucket opens
+        series.update(3_200, dec!(101), dec!(2));
+
+        let done = series.completed(10);
+        assert_eq!(done.len(), 2);
+        assert_eq!(
+            (done[0].open, done[0].high, done[0].low, done[0].close, done[0].ticks),
+            (dec!(100), dec!(103), dec!(99), dec!(99), 3)
+        );
+        assert_eq!((done[1].start_ms, done[1].close, done[1].ticks), (2_000, dec!(99), 0));
+        assert_eq!(series.current().map(|c| c.start_ms), Some(3_000));
+        assert_eq!(series.closes(1), vec![dec!(99)]);
+
+        // (100*1 + 99*3 + 101*2) / 6
+        let mut all = done.clone();
+        all.extend(series.current().cloned());
+        assert_eq!(vwap(&all), Some(dec!(599) / dec!(6)));
+        assert_eq!(vwap(&done[1..]), None);
+
+        // A gap longer than the series restarts it
+        series.update(60_000, dec!(105), dec!(0));
+        assert!(series.completed(10).is_empty());
+    }
+}
@@ -7,20 +7,40 @@
 
 pub mod alerts;
 pub mod auth;
-pub mod btc_price;
+pub mod calibration;
 pub mod clob;
 pub mod config;
 pub mod datalog;
+pub mod eip712;
+pub mod fair_value;
+pub mod event_bus;
+pub mod feed_dedup;
+pub mod indicators;
 pub mod market;
+pub mod market_ws;
 pub mod ml_client;
 pub mod multi_strategy;
+pub mod nonce;
+pub mod order_builder;
+pub mod order_verify;
 pub mod orderbook;
 pub mod position;
+pub mod price_history;
+pub mod price_source;
+pub mod reference_price;
 pub mod presigned_cache;
 pub mod retry;
 pub mod signer;
+pub mod signing_backend;
+pub mod spot_flow;
+pub mod spot_price;
 pub mod strategies;
 pub mod strategy;
+pub mod supervisor;
+#[cfg(test)]
+pub(crate) mod test_support;
 pub mod trade_db;
+pub mod trade_tape;
 pub mod types;
+pub mod user_ws;
 pub mod websocket;
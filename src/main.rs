@@ -3,15 +3,27 @@ mod auth;
 mod clob;
 mod config;
 mod datalog;
+mod eip712;
+mod event_bus;
+mod feed_dedup;
 mod market;
+mod market_ws;
 mod ml_client;
+mod nonce;
+mod order_builder;
 mod orderbook;
 mod position;
 mod presigned_cache;
 mod retry;
 mod signer;
+mod signing_backend;
 mod strategy;
+mod supervisor;
+#[cfg(test)]
+mod test_support;
+mod trade_tape;
 mod types;
+mod user_ws;
 mod websocket;
 
 use anyhow::Result;
@@ -27,12 +39,16 @@ use crate::config::Config;
 use crate::datalog::{DataLogger, MarketSnapshot, OrderLog, FillLog, SessionSummary, PriceLevel};
 use crate::market::MarketMonitor;
 use crate::ml_client::MlClient;
-use crate::orderbook::OrderbookManager;
+use crate::orderbook::{OrderbookManager, SpreadState};
 use crate::position::PositionManager;
 use crate::signer::OrderSigner;
 use crate::strategy::LadderStrategy;
-use crate::types::BtcMarket;
-use crate::websocket::{spawn_websocket_with_orderbook, WsEvent};
+use crate::supervisor::Supervisor;
+use crate::types::{Asset, UpDownMarket};
+use crate::user_ws::{spawn_user_channel, UserChannelHandle, UserEvent};
+use crate::event_bus::MarketStream;
+use crate::market_ws::MarketFeed;
+use crate::websocket::WsEvent;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -62,9 +78,12 @@ async fn main() -> Result<()> {
     info!("║ Ladder levels: {:24} ║", config.ladder_levels);
     info!("╚═══════════════════════════════════════╝");
 
+    // Ctrl-C / SIGTERM stop entries, cancel resting orders and flush logs
+    let supervisor = Supervisor::install();
+
     // Initialize alert client
     let alerts = Arc::new(AlertClient::new(config.discord_webhook.clone()));
-    alerts.bot_started(config.dry_run).await;
+    alerts.bot_started(&[Asset::Btc], config.dry_run).await;
 
     // Initialize data logger for ML analysis
     let data_logger = Arc::new(DataLogger::new("./data")?);
@@ -81,13 +100,23 @@ async fn main() -> Result<()> {
 
     // Initialize components
     let clob = ClobClient::new(config.clone())?;
-    let signer = OrderSigner::new(&config.private_key, &config.address)?;
+    let signer = OrderSigner::from_config(&config).await?;
     let strategy = LadderStrategy::new(config.clone(), clob, signer)
         .with_ml_client(ml_client.clone());  // Wire up ML client!
-    let market_monitor = MarketMonitor::new(config.clone());
+    let market_monitor = MarketMonitor::new(config.clone(), Asset::Btc);
     let position_manager = Arc::new(Mutex::new(PositionManager::new()));
     let orderbook_manager = Arc::new(OrderbookManager::new());
 
+    // Books that fail integrity checks get re-fetched over REST
+    let resync_clob = Arc::new(ClobClient::new(config.clone())?);
+    orderbook_manager.clone().spawn_resync_task(resync_clob, std::time::Duration::from_secs(1), &supervisor);
+
+    // Market channel connection(s) shared by every market we trade
+    let market_feed = MarketFeed::spawn_with_routes(orderbook_manager.clone(), &config.market_ws_route_list())?;
+
+    // Authenticated user channel for our own order/trade updates
+    let (user_channel, user_rx) = spawn_user_channel(config.clone(), position_manager.clone(), &supervisor);
+
     // Pre-warm connections
     info!("Pre-warming connections...");
     prewarm_connections(&config).await;
@@ -102,6 +131,10 @@ async fn main() -> Result<()> {
         alerts,
         data_logger,
         ml_client,
+        market_feed,
+        user_channel,
+        user_rx,
+        supervisor,
     ).await
 }
 
@@ -131,13 +164,19 @@ async fn run_trading_loop(
     alerts: Arc<AlertClient>,
     data_logger: Arc<DataLogger>,
     ml_client: Arc<MlClient>,
+    market_feed: MarketFeed,
+    user_channel: UserChannelHandle,
+    mut user_rx: tokio::sync::mpsc::Receiver<UserEvent>,
+    supervisor: Arc<Supervisor>,
 ) -> Result<()> {
-    loop {
+    while !supervisor.is_shutting_down() {
         info!("═══════════════════════════════════════");
         info!("Searching for active BTC 15-min market...");
 
         // Poll for market (REST API)
-        let market = market_monitor.wait_for_next_market().await;
+        let Some(mut market) = supervisor.run(market_monitor.wait_for_next_market()).await else {
+            break;
+        };
 
         info!("Found market: {}", market.title);
         info!("  UP token:   {}", market.up_token_id);
@@ -147,49 +186,66 @@ async fn run_trading_loop(
 
         alerts.market_found(&market.title, &market.end_time.to_string()).await;
 
-        // Start WebSocket for this market's orderbooks
-        let market_ws_rx = spawn_websocket_with_orderbook(
-            config.clone(),
-            vec![market.up_token_id.clone(), market.down_token_id.clone()],
-            orderbook_manager.clone(),
-        );
+        // Stream this market's orderbooks on the shared connection
+        let market_ws_rx = market_feed.subscribe(&market);
+
+        // Fills for this market arrive on the user channel
+        position_manager.lock().register_market(&market.condition_id, &market.up_token_id, &market.down_token_id);
+        user_channel.subscribe(&market.condition_id);
 
         // Run trading session
         if let Err(e) = run_market_session(
             &config,
             &strategy,
-            &market,
+            &mut market,
             position_manager.clone(),
             orderbook_manager.clone(),
             alerts.clone(),
             data_logger.clone(),
             ml_client.clone(),
             market_ws_rx,
+            &mut user_rx,
+            &supervisor,
         ).await {
             error!("Market session error: {}", e);
             alerts.error("Market session failed", &e.to_string()).await;
         }
+        user_channel.unsubscribe(&market.condition_id);
+        market_feed.unsubscribe(&market.condition_id);
+        market_feed.log_route_stats();
 
         // Wait before next market
-        tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+        supervisor.run(tokio::time::sleep(tokio::time::Duration::from_secs(5))).await;
+    }
+
+    // Shutdown: sessions cancel their orders before returning
+    info!("Shutting down...");
+    market_feed.shutdown().await;
+    if let Err(e) = data_logger.flush() {
+        warn!("Failed to flush data logs: {}", e);
     }
+    let reason = supervisor.reason().unwrap_or_else(|| "shutdown".to_string());
+    alerts.bot_stopped(&[Asset::Btc], &format!("Stopped on {}", reason)).await;
+    supervisor.shutdown(std::time::Duration::from_secs(5)).await;
+    info!("Shutdown complete");
+    Ok(())
 }
 
 /// Run a trading session for a single market
 async fn run_market_session(
     config: &Config,
     strategy: &LadderStrategy,
-    market: &BtcMarket,
+    market: &mut UpDownMarket,
     position_manager: Arc<Mutex<PositionManager>>,
     orderbook_manager: Arc<OrderbookManager>,
     alerts: Arc<AlertClient>,
     data_logger: Arc<DataLogger>,
     ml_client: Arc<MlClient>,
-    mut ws_rx: tokio::sync::mpsc::Receiver<WsEvent>,
+    mut ws_rx: MarketStream,
+    user_rx: &mut tokio::sync::mpsc::Receiver<UserEvent>,
+    supervisor: &Supervisor,
 ) -> Result<()> {
     let session_start = chrono::Utc::now();
-    let mut orders_placed: u32 = 0;
-    let mut fills_received: u32 = 0;
     // Wait for WebSocket connection and initial orderbook
     let mut connected = false;
     let mut orderbook_received = false;
@@ -202,17 +258,24 @@ async fn run_market_session(
         }
 
         tokio::select! {
+            _ = supervisor.cancelled() => {
+                // No new entries; pull everything resting on this market
+                info!("Shutdown requested, cancelling all open orders...");
+                if let Err(e) = strategy.cancel_all_orders(&market.condition_id).await {
+                    error!("Failed to cancel orders: {}", e);
+                    alerts.error("Order cancellation on shutdown failed", &e.to_string()).await;
+                }
+                break;
+            }
             Some(event) = ws_rx.recv() => {
                 match event {
                     WsEvent::Connected => {
                         connected = true;
                         info!("WebSocket connected");
                     }
-                    WsEvent::OrderbookUpdate { asset_id, .. } => {
-                        if asset_id == market.up_token_id || asset_id == market.down_token_id {
-                            orderbook_received = true;
-                            info!("Orderbook received for {}", asset_id);
-                        }
+                    WsEvent::Book(top) => {
+                        orderbook_received = true;
+                        info!("Orderbook received for {}", top.asset_id);
                     }
                     WsEvent::Error(e) => {
                         warn!("WebSocket error during setup: {}", e);
@@ -224,10 +287,19 @@ async fn run_market_session(
         }
     }
 
+    if supervisor.is_shutting_down() {
+        info!("Shutting down, not entering {}", market.condition_id);
+        return Ok(());
+    }
+
     // Check spread before entering
-    let spread = match orderbook_manager.get_combined_spread(&market.up_token_id, &market.down_token_id) {
-        Some(s) => s,
-        None => {
+    let spread = match orderbook_manager.spread_state(&market.up_token_id, &market.down_token_id) {
+        SpreadState::Ready(s) => s,
+        SpreadState::Unreliable { asset_id, issue } => {
+            warn!("Orderbook {} unreliable ({}), skipping market", &asset_id[..asset_id.len().min(16)], issue);
+            return Ok(());
+        }
+        SpreadState::Unavailable => {
             warn!("No spread data available, skipping market");
             return Ok(());
         }
@@ -301,9 +373,115 @@ async fn run_market_session(
         pm.register_orders(&market.condition_id, &up_order_ids, &down_order_ids);
     }
 
-    // Monitor fills until market closes
+    // Monitor fills until market closes (or shutdown)
     let end_time = market.end_time;
-    let cancel_time = end_time - chrono::Duration::minutes(2);
+    let ctx = SessionContext {
+        config,
+        strategy,
+        position_manager: &position_manager,
+        orderbook_manager: &orderbook_manager,
+        alerts: &alerts,
+        data_logger: &data_logger,
+        supervisor,
+    };
+    let (orders_placed, fills_received) = monitor_fills(&ctx, market, &mut ws_rx, user_rx).await;
+
+    // Final position summary
+    let (final_profit, session_summary) = {
+        let pm = position_manager.lock();
+        info!("╔═══════════════════════════════════════╗");
+        info!("║        FINAL POSITION SUMMARY         ║");
+        info!("╚═══════════════════════════════════════╝");
+        pm.print_summary(&market.condition_id);
+
+        let profit = pm.get_position(&market.condition_id)
+            .map(|pos| pm.calculate_pnl(pos).locked_profit)
+            .unwrap_or_default();
+
+        // Build session summary for ML analysis
+        let summary = pm.get_position(&market.condition_id).map(|pos| {
+            let total_cost = pos.total_cost();
+            SessionSummary {
+                session_id: data_logger.session_id().to_string(),
+                start_time: session_start,
+                end_time: chrono::Utc::now(),
+                market_id: market.condition_id.clone(),
+                market_title: market.title.clone(),
+                total_up_shares: pos.up_shares,
+                total_down_shares: pos.down_shares,
+                total_up_cost: pos.up_cost,
+                total_down_cost: pos.down_cost,
+                total_cost,
+                min_shares: pos.min_shares(),
+                guaranteed_payout: pos.guaranteed_payout(),
+                locked_profit: pos.locked_profit(),
+                profit_pct: if total_cost > rust_decimal::Decimal::ZERO {
+                    pos.locked_profit() / total_cost * rust_decimal_macros::dec!(100)
+                } else {
+                    rust_decimal::Decimal::ZERO
+                },
+                is_dry_run: config.dry_run,
+                orders_placed,
+                fills_received,
+            }
+        });
+
+        (profit, summary)
+    };
+
+    // Log session summary for ML analysis
+    if let Some(summary) = session_summary {
+        info!("Logging session summary: profit=${}, profit_pct={}%", summary.locked_profit, summary.profit_pct);
+        let _ = data_logger.log_session_summary(&summary);
+    }
+
+    // Wait for resolution (orders are already cancelled, so shutdown needn't wait)
+    let time_to_resolution = (end_time - chrono::Utc::now()).num_seconds();
+    if time_to_resolution > 0 {
+        info!("Waiting {} seconds for resolution...", time_to_resolution);
+        supervisor.run(tokio::time::sleep(tokio::time::Duration::from_secs(
+            (time_to_resolution + 30) as u64
+        ))).await;
+    }
+
+    // Log result (main.rs doesn't track position details, so use None)
+    alerts.market_resolved(&market.title, final_profit, None, None).await;
+
+    // Clear position
+    {
+        let mut pm = position_manager.lock();
+        pm.clear_position(&market.condition_id);
+    }
+
+    info!("Market session complete");
+    Ok(())
+}
+
+/// Components a market session shares with its fill monitor
+struct SessionContext<'a> {
+    config: &'a Config,
+    strategy: &'a LadderStrategy,
+    position_manager: &'a Mutex<PositionManager>,
+    orderbook_manager: &'a OrderbookManager,
+    alerts: &'a AlertClient,
+    data_logger: &'a DataLogger,
+    supervisor: &'a Supervisor,
+}
+
+/// Watch books and fills until `cancel_time` or shutdown, then cancel the
+/// market's resting orders
+///
+/// Returns (orders placed by re-quotes, fills received).
+async fn monitor_fills(
+    ctx: &SessionContext<'_>,
+    market: &mut UpDownMarket,
+    ws_rx: &mut MarketStream,
+    user_rx: &mut tokio::sync::mpsc::Receiver<UserEvent>,
+) -> (u32, u32) {
+    let SessionContext { config, strategy, position_manager, orderbook_manager, alerts, data_logger, supervisor } = *ctx;
+    let mut orders_placed: u32 = 0;
+    let mut fills_received: u32 = 0;
+    let cancel_time = market.end_time - chrono::Duration::minutes(2);
     let mut last_status_update = tokio::time::Instant::now();
 
     info!("Monitoring fills until {}...", cancel_time);
@@ -321,54 +499,20 @@ async fn run_market_session(
         }
 
         tokio::select! {
+            _ = supervisor.cancelled() => {
+                // No new entries; pull the resting ladder before unwinding
+                info!("Shutdown requested, cancelling all open orders...");
+                if let Err(e) = strategy.cancel_all_orders(&market.condition_id).await {
+                    error!("Failed to cancel orders: {}", e);
+                    alerts.error("Order cancellation on shutdown failed", &e.to_string()).await;
+                }
+                break;
+            }
             Some(event) = ws_rx.recv() => {
                 match event {
-                    WsEvent::TradeFill(fill) => {
-                        info!("Fill: {} shares @ ${}", fill.size, fill.price);
-                        fills_received += 1;
-
-                        {
-                            let mut pm = position_manager.lock();
-                            pm.process_fill(&fill);
-                        }
-
-                        let side = if fill.asset_id == market.up_token_id { "UP" } else { "DOWN" };
-                        alerts.fill_received(side, &fill.size, &fill.price).await;
-
-                        // Log fill for ML analysis
-                        let _ = data_logger.log_fill(&FillLog {
-                            timestamp: chrono::Utc::now(),
-                            market_id: market.condition_id.clone(),
-                            side: side.to_string(),
-                            price: fill.price.parse().unwrap_or_default(),
-                            size: fill.size.parse().unwrap_or_default(),
-                            order_id: fill.order_id.clone(),
-                        });
-
-                        // Check position balance and rebalance if needed
-                        let should_rebalance = {
-                            let pm = position_manager.lock();
-                            if let Some(pos) = pm.get_position(&market.condition_id) {
-                                if !pos.is_balanced() {
-                                    alerts.position_imbalance(pos.up_shares, pos.down_shares).await;
-                                    Some(pos.clone())
-                                } else {
-                                    None
-                                }
-                            } else {
-                                None
-                            }
-                        };
-
-                        // Trigger rebalancing outside of lock
-                        if let Some(pos) = should_rebalance {
-                            if let Err(e) = strategy.rebalance_if_needed(market, &pos).await {
-                                warn!("Rebalance failed: {}", e);
-                            }
-                        }
-                    }
-                    WsEvent::OrderbookUpdate { asset_id: _, bids: _, asks: _ } => {
-                        // Orderbook already updated by WebSocket client
+                    WsEvent::Book(_) => {
+                        // Orderbook already updated by the feed (snapshot or level deltas); missed
+                        // intermediate updates were conflated, so this is always the latest state
                         // Check for spread opportunities and potentially snipe
                         if let Some(spread) = orderbook_manager.get_combined_spread(
                             &market.up_token_id, &market.down_token_id
@@ -386,9 +530,9 @@ async fn run_market_session(
                                 end_time: market.end_time,
                                 up_token_id: market.up_token_id.clone(),
                                 down_token_id: market.down_token_id.clone(),
-                                up_best_bid: Some(spread.up_best_ask - rust_decimal_macros::dec!(0.01)), // approximate
+                                up_best_bid: depth.as_ref().and_then(|d| d.up_bids.first().map(|(p, _)| *p)),
                                 up_best_ask: Some(spread.up_best_ask),
-                                down_best_bid: Some(spread.down_best_ask - rust_decimal_macros::dec!(0.01)), // approximate
+                                down_best_bid: depth.as_ref().and_then(|d| d.down_bids.first().map(|(p, _)| *p)),
                                 down_best_ask: Some(spread.down_best_ask),
                                 combined_ask: Some(spread.up_best_ask + spread.down_best_ask),
                                 spread_pct: Some(spread.spread_pct),
@@ -397,9 +541,13 @@ async fn run_market_session(
                                 up_bids: depth.as_ref().map(|d| d.up_bids.iter().map(|(p, s)| PriceLevel { price: *p, size: *s }).collect()).unwrap_or_default(),
                                 down_asks: depth.as_ref().map(|d| d.down_asks.iter().map(|(p, s)| PriceLevel { price: *p, size: *s }).collect()).unwrap_or_default(),
                                 down_bids: depth.as_ref().map(|d| d.down_bids.iter().map(|(p, s)| PriceLevel { price: *p, size: *s }).collect()).unwrap_or_default(),
+                                spot_price: None,
+                                spot_open: None,
+                                spot_vol_5m_pct: None,
+                                fair_up_prob: None,
                             });
                             // If spread is large enough, try to snipe
-                            if spread.spread_pct >= config.target_spread_percent {
+                            if spread.spread_pct >= config.target_spread_percent && !supervisor.is_shutting_down() {
                                 info!("Large spread detected: {}%! Attempting snipe...", spread.spread_pct);
 
                                 match strategy.snipe_spread(
@@ -409,8 +557,7 @@ async fn run_market_session(
                                 ).await {
                                     Ok(Some((up_ids, down_ids))) => {
                                         // Register snipe orders for tracking
-                                        let mut pm = position_manager.lock();
-                                        pm.register_orders(&market.condition_id, &up_ids, &down_ids);
+                                        position_manager.lock().register_orders(&market.condition_id, &up_ids, &down_ids);
                                         alerts.orders_submitted(
                                             up_ids.len(),
                                             down_ids.len(),
@@ -425,6 +572,42 @@ async fn run_market_session(
                             }
                         }
                     }
+                    WsEvent::BookUnreliable { asset_id, reason } => {
+                        // Prices withheld until the resync task restores the book
+                        warn!("Book {} unreliable ({}), pausing snipes", &asset_id[..asset_id.len().min(16)], reason);
+                    }
+                    WsEvent::TickSizeChange(change) => {
+                        // Both tokens report the change; act once
+                        let new_tick = orderbook_manager.market_tick_size(market);
+                        if new_tick == market.tick_size || supervisor.is_shutting_down() {
+                            continue;
+                        }
+                        info!("Tick size {} -> {} ({} -> {} on {})", market.tick_size, new_tick,
+                            change.old_tick_size, change.new_tick_size, &change.asset_id[..change.asset_id.len().min(16)]);
+                        market.tick_size = new_tick;
+
+                        // Resting ladder was priced on the old grid
+                        let spent = position_manager.lock()
+                            .get_position(&market.condition_id)
+                            .map(|p| p.total_cost())
+                            .unwrap_or_default();
+                        let Some((up_book, down_book)) = orderbook_manager.get_orderbooks(
+                            &market.up_token_id, &market.down_token_id
+                        ) else {
+                            warn!("Books unavailable, ladder not re-quoted");
+                            continue;
+                        };
+                        match strategy.requote_ladder(market, &up_book, &down_book, spent).await {
+                            Ok((up_ids, down_ids)) => {
+                                orders_placed += (up_ids.len() + down_ids.len()) as u32;
+                                position_manager.lock().register_orders(&market.condition_id, &up_ids, &down_ids);
+                            }
+                            Err(e) => {
+                                error!("Re-quote after tick size change failed: {}", e);
+                                alerts.error("Ladder re-quote failed", &e.to_string()).await;
+                            }
+                        }
+                    }
                     WsEvent::Disconnected => {
                         warn!("WebSocket disconnected during session");
                     }
@@ -434,15 +617,66 @@ async fn run_market_session(
                     _ => {}
                 }
             }
+            Some(user_event) = user_rx.recv() => {
+                match user_event {
+                    UserEvent::Fill(fill) if fill.market == market.condition_id => {
+                        // Already applied to PositionManager by the user channel
+                        info!("Fill: {} shares @ ${}", fill.size, fill.price);
+                        fills_received += 1;
+
+                        let side = if fill.asset_id == market.up_token_id { "UP" } else { "DOWN" };
+                        alerts.fill_received(side, &fill.size, &fill.price).await;
+
+                        // Log fill for ML analysis
+                        let _ = data_logger.log_fill(&FillLog {
+                            timestamp: chrono::Utc::now(),
+                            market_id: market.condition_id.clone(),
+                            side: side.to_string(),
+                            price: fill.price.parse().unwrap_or_default(),
+                            size: fill.size.parse().unwrap_or_default(),
+                            order_id: fill.order_id.clone(),
+                        });
+
+                        // Check position balance and rebalance if needed; the user channel
+                        // also locks the position manager, so never hold it across an await
+                        let should_rebalance = position_manager.lock()
+                            .get_position(&market.condition_id)
+                            .filter(|pos| !pos.is_balanced())
+                            .cloned();
+
+                        if let Some(pos) = should_rebalance {
+                            alerts.position_imbalance(pos.up_shares, pos.down_shares).await;
+                            if let Err(e) = strategy.rebalance_if_needed(market, &pos).await {
+                                warn!("Rebalance failed: {}", e);
+                            }
+                        }
+                    }
+                    UserEvent::FillReverted(fill) if fill.market == market.condition_id => {
+                        warn!("Fill reverted (trade failed on-chain): {} shares @ ${}", fill.size, fill.price);
+                        fills_received = fills_received.saturating_sub(1);
+                    }
+                    UserEvent::Disconnected => {
+                        warn!("User channel disconnected during session");
+                    }
+                    _ => {}
+                }
+            }
             _ = tokio::time::sleep(tokio::time::Duration::from_secs(30)) => {
                 // Periodic status update
                 if last_status_update.elapsed() > tokio::time::Duration::from_secs(60) {
-                    let pm = position_manager.lock();
-                    pm.print_summary(&market.condition_id);
-
-                    if let Some(pos) = pm.get_position(&market.condition_id) {
-                        let report = pm.calculate_pnl(pos);
-                        alerts.position_update(pos.up_shares, pos.down_shares, report.locked_profit).await;
+                    let bus = ws_rx.stats();
+                    info!("Market data: {} book updates ({} conflated, max lag {:.1}ms), {} events (backlog {}, max lag {:.1}ms)",
+                        bus.books_published, bus.books_conflated, bus.max_book_lag_ms,
+                        bus.events_published, bus.event_backlog, bus.max_event_lag_ms);
+
+                    let update = {
+                        let pm = position_manager.lock();
+                        pm.print_summary(&market.condition_id);
+                        pm.get_position(&market.condition_id)
+                            .map(|pos| (pos.up_shares, pos.down_shares, pm.calculate_pnl(pos).locked_profit))
+                    };
+                    if let Some((up_shares, down_shares, locked_profit)) = update {
+                        alerts.position_update(up_shares, down_shares, locked_profit).await;
                     }
 
                     last_status_update = tokio::time::Instant::now();
@@ -451,73 +685,72 @@ async fn run_market_session(
         }
     }
 
-    // Final position summary
-    let (final_profit, session_summary) = {
-        let pm = position_manager.lock();
-        info!("╔═══════════════════════════════════════╗");
-        info!("║        FINAL POSITION SUMMARY         ║");
-        info!("╚═══════════════════════════════════════╝");
-        pm.print_summary(&market.condition_id);
-
-        let profit = pm.get_position(&market.condition_id)
-            .map(|pos| pm.calculate_pnl(pos).locked_profit)
-            .unwrap_or_default();
+    (orders_placed, fills_received)
+}
 
-        // Build session summary for ML analysis
-        let summary = pm.get_position(&market.condition_id).map(|pos| {
-            let total_cost = pos.total_cost();
-            SessionSummary {
-                session_id: data_logger.session_id().to_string(),
-                start_time: session_start,
-                end_time: chrono::Utc::now(),
-                market_id: market.condition_id.clone(),
-                market_title: market.title.clone(),
-                total_up_shares: pos.up_shares,
-                total_down_shares: pos.down_shares,
-                total_up_cost: pos.up_cost,
-                total_down_cost: pos.down_cost,
-                total_cost,
-                min_shares: pos.min_shares(),
-                guaranteed_payout: pos.guaranteed_payout(),
-                locked_profit: pos.locked_profit(),
-                profit_pct: if total_cost > rust_decimal::Decimal::ZERO {
-                    pos.locked_profit() / total_cost * rust_decimal_macros::dec!(100)
-                } else {
-                    rust_decimal::Decimal::ZERO
-                },
-                is_dry_run: config.dry_run,
-                orders_placed,
-                fills_received,
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{test_market, TEST_ADDRESS, TEST_KEY};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn test_shutdown_mid_session_cancels_resting_orders() {
+        // One-request CLOB stand-in that reports the request line it got
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (seen_tx, seen_rx) = tokio::sync::oneshot::channel();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 4096];
+            let n = socket.read(&mut buf).await.unwrap();
+            let request = String::from_utf8_lossy(&buf[..n]).to_string();
+            let _ = socket.write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\n\r\n{}").await;
+            let _ = seen_tx.send(request.lines().next().unwrap_or_default().to_string());
         });
 
-        (profit, summary)
-    };
-
-    // Log session summary for ML analysis
-    if let Some(summary) = session_summary {
-        info!("Logging session summary: profit=${}, profit_pct={}%", summary.locked_profit, summary.profit_pct);
-        let _ = data_logger.log_session_summary(&summary);
-    }
-
-    // Wait for resolution
-    let time_to_resolution = (end_time - chrono::Utc::now()).num_seconds();
-    if time_to_resolution > 0 {
-        info!("Waiting {} seconds for resolution...", time_to_resolution);
-        tokio::time::sleep(tokio::time::Duration::from_secs(
-            (time_to_resolution + 30) as u64
-        )).await;
-    }
-
-    // Log result (main.rs doesn't track position details, so use None)
-    alerts.market_resolved(&market.title, final_profit, None, None).await;
-
-    // Clear position
-    {
-        let mut pm = position_manager.lock();
-        pm.clear_position(&market.condition_id);
+        let mut config = Config::for_tests();
+        config.dry_run = false;
+        config.clob_url = format!("http://{}", addr);
+        let strategy = LadderStrategy::new(
+            config.clone(),
+            ClobClient::new(config.clone()).unwrap(),
+            OrderSigner::new(TEST_KEY, TEST_ADDRESS).unwrap(),
+        );
+        let mut market = test_market();
+        let log_dir = std::env::temp_dir().join(format!("btc-arb-bot-test-{}", std::process::id()));
+        let data_logger = DataLogger::new(log_dir.to_str().unwrap()).unwrap();
+        let (_publisher, mut ws_rx) = event_bus::market_channel(&["1".to_string(), "2".to_string()]);
+        let (_user_tx, mut user_rx) = tokio::sync::mpsc::channel(8);
+        let supervisor = Arc::new(Supervisor::new());
+
+        // Ladder is resting and the session is waiting on events when the signal lands
+        let sv = supervisor.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+            sv.trigger("SIGINT");
+        });
+        let monitored = tokio::time::timeout(
+            tokio::time::Duration::from_secs(5),
+            monitor_fills(
+                &SessionContext {
+                    config: &config,
+                    strategy: &strategy,
+                    position_manager: &Mutex::new(PositionManager::new()),
+                    orderbook_manager: &OrderbookManager::new(),
+                    alerts: &AlertClient::new(None),
+                    data_logger: &data_logger,
+                    supervisor: &supervisor,
+                },
+                &mut market,
+                &mut ws_rx,
+                &mut user_rx,
+            ),
+        )
+        .await;
+
+        assert_eq!(monitored.unwrap(), (0, 0));
+        assert_eq!(seen_rx.await.unwrap(), "DELETE /cancel-market-orders HTTP/1.1");
+        let _ = std::fs::remove_dir_all(log_dir);
     }
-
-    info!("Market session complete");
-    Ok(())
 }
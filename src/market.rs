@@ -6,26 +6,32 @@ use rust_decimal::prelude::FromPrimitive;
 use tracing::{debug, info, warn};
 
 use crate::config::Config;
-use crate::types::{BtcMarket, Event, Market};
+use crate::order_builder::DEFAULT_MIN_ORDER_SIZE;
+use crate::types::{Asset, UpDownMarket, Event, Market};
 
-/// Find active BTC 15-minute markets
+/// Find active 15-minute up/down markets for one asset
 pub struct MarketMonitor {
     client: Client,
     config: Config,
+    asset: Asset,
 }
 
 impl MarketMonitor {
-    pub fn new(config: Config) -> Self {
+    pub fn new(config: Config, asset: Asset) -> Self {
         let client = Client::builder()
             .tcp_nodelay(true)
             .build()
             .unwrap();
 
-        Self { client, config }
+        Self { client, config, asset }
     }
 
-    /// Find the current active BTC 15-min market
-    pub async fn find_active_btc_market(&self) -> Result<Option<BtcMarket>> {
+    pub fn asset(&self) -> Asset {
+        self.asset
+    }
+
+    /// Find the current active 15-min market
+    pub async fn find_active_market(&self) -> Result<Option<UpDownMarket>> {
         // Get current time and calculate current 15-min window timestamp
         let now = Utc::now();
         let current_ts = now.timestamp();
@@ -36,7 +42,7 @@ impl MarketMonitor {
         // Try current and next windows
         for offset in [0, 900] {
             let ts = window_start + offset;
-            let slug = format!("btc-updown-15m-{}", ts);
+            let slug = format!("{}{}", self.asset.slug_prefix(), ts);
 
             if let Some(market) = self.fetch_market_by_slug(&slug).await? {
                 if market.end_time > now {
@@ -46,12 +52,12 @@ impl MarketMonitor {
             }
         }
 
-        // Fallback: search for any active BTC 15-min market
-        self.search_active_btc_markets().await
+        // Fallback: search for any active 15-min market of this asset
+        self.search_active_markets().await
     }
 
     /// Fetch market by event slug
-    async fn fetch_market_by_slug(&self, slug: &str) -> Result<Option<BtcMarket>> {
+    async fn fetch_market_by_slug(&self, slug: &str) -> Result<Option<UpDownMarket>> {
         let url = format!("{}/events?slug={}", self.config.gamma_url, slug);
 
         let response = self.client
@@ -75,14 +81,15 @@ impl MarketMonitor {
             return Ok(None);
         }
 
-        self.parse_btc_market(event).await
+        self.parse_market(event).await
     }
 
-    /// Search for active BTC 15-min markets
-    async fn search_active_btc_markets(&self) -> Result<Option<BtcMarket>> {
+    /// Search for active 15-min markets of this asset
+    async fn search_active_markets(&self) -> Result<Option<UpDownMarket>> {
         let url = format!(
-            "{}/events?slug_contains=btc-updown-15m&active=true&closed=false&limit=5",
-            self.config.gamma_url
+            "{}/events?slug_contains={}&active=true&closed=false&limit=5",
+            self.config.gamma_url,
+            self.asset.slug_prefix().trim_end_matches('-')
         );
 
         let response = self.client
@@ -97,7 +104,7 @@ impl MarketMonitor {
             .unwrap_or_default();
 
         for event in events {
-            if let Ok(Some(market)) = self.parse_btc_market(&event).await {
+            if let Ok(Some(market)) = self.parse_market(&event).await {
                 if market.end_time > Utc::now() {
                     return Ok(Some(market));
                 }
@@ -107,8 +114,12 @@ impl MarketMonitor {
         Ok(None)
     }
 
-    /// Parse event into BtcMarket struct
-    async fn parse_btc_market(&self, event: &Event) -> Result<Option<BtcMarket>> {
+    /// Parse event into UpDownMarket struct
+    async fn parse_market(&self, event: &Event) -> Result<Option<UpDownMarket>> {
+        // slug_contains can match other series; only take this asset's
+        let Some(ts_str) = event.slug.strip_prefix(&self.asset.slug_prefix()) else {
+            return Ok(None);
+        };
         if event.markets.is_empty() {
             return Ok(None);
         }
@@ -154,14 +165,10 @@ impl MarketMonitor {
             return Ok(None);
         }
 
-        // Parse end time from slug (btc-updown-15m-{timestamp})
-        let end_time = if let Some(ts_str) = event.slug.strip_prefix("btc-updown-15m-") {
-            if let Ok(ts) = ts_str.parse::<i64>() {
-                // Add 15 minutes to get end time
-                Utc.timestamp_opt(ts + 900, 0).single().unwrap_or(Utc::now())
-            } else {
-                Utc::now()
-            }
+        // Parse end time from slug (<asset>-updown-15m-{timestamp})
+        let end_time = if let Ok(ts) = ts_str.parse::<i64>() {
+            // Add 15 minutes to get end time
+            Utc.timestamp_opt(ts + 900, 0).single().unwrap_or(Utc::now())
         } else {
             Utc::now()
         };
@@ -169,12 +176,19 @@ impl MarketMonitor {
         // Get tick size
         let tick_size = market
             .tick_size
-            .map(|t| Decimal::from_f64_retain(t).unwrap_or(Decimal::from_str_exact("0.01").unwrap()))
+            .map(|t| Decimal::from_f64(t).unwrap_or(Decimal::from_str_exact("0.01").unwrap()))
             .unwrap_or(Decimal::from_str_exact("0.01").unwrap());
 
+        // Get minimum order size (shares)
+        let min_order_size = market
+            .min_order_size
+            .and_then(Decimal::from_f64)
+            .unwrap_or(DEFAULT_MIN_ORDER_SIZE);
+
         let neg_risk = market.neg_risk.unwrap_or(false);
 
-        Ok(Some(BtcMarket {
+        Ok(Some(UpDownMarket {
+            asset: self.asset,
             event_slug: event.slug.clone(),
             condition_id: market.condition_id.clone(),
             title: market.question.clone(),
@@ -182,29 +196,30 @@ impl MarketMonitor {
             down_token_id,
             end_time,
             tick_size,
+            min_order_size,
             neg_risk,
         }))
     }
 
     /// Get time remaining until market resolution
-    pub fn time_until_resolution(market: &BtcMarket) -> chrono::Duration {
+    pub fn time_until_resolution(market: &UpDownMarket) -> chrono::Duration {
         market.end_time - Utc::now()
     }
 
     /// Check if it's too late to enter a market (less than 2 minutes remaining)
-    pub fn is_too_late(market: &BtcMarket) -> bool {
+    pub fn is_too_late(market: &UpDownMarket) -> bool {
         Self::time_until_resolution(market) < chrono::Duration::minutes(2)
     }
 
     /// Wait for next market window
-    pub async fn wait_for_next_market(&self) -> BtcMarket {
+    pub async fn wait_for_next_market(&self) -> UpDownMarket {
         loop {
-            match self.find_active_btc_market().await {
+            match self.find_active_market().await {
                 Ok(Some(market)) => {
                     if !Self::is_too_late(&market) {
                         return market;
                     }
-                    info!("Market too close to resolution, waiting for next...");
+                    info!("{} market too close to resolution, waiting for next...", self.asset);
                 }
                 Ok(None) => {
                     debug!("No active market found, waiting...");
@@ -219,3 +234,47 @@ impl MarketMonitor {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(slug: &str) -> Event {
+        serde_json::from_value(serde_json::json!({
+            "id": "1",
+            "slug": slug,
+            "title": "Up or Down",
+            "active": true,
+            "closed": false,
+            "markets": [{
+                "conditionId": "0xcondition",
+                "question": "Up or Down?",
+                "outcomes": "[\"Up\", \"Down\"]",
+                "clobTokenIds": "[\"111\", \"222\"]",
+                "active": true,
+                "closed": false,
+                "negRisk": true,
+                "orderPriceMinTickSize": 0.01,
+                "orderMinSize": 5
+            }]
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_parse_market_only_takes_own_asset() {
+        let monitor = MarketMonitor::new(Config::for_tests(), Asset::Eth);
+
+        let market = monitor.parse_market(&event("eth-updown-15m-1736949600")).await.unwrap().unwrap();
+        assert_eq!(market.asset, Asset::Eth);
+        assert_eq!(market.up_token_id, "111");
+        assert_eq!(market.down_token_id, "222");
+        assert_eq!(market.window_start(), 1_736_949_600);
+        assert!(market.neg_risk);
+
+        // slug_contains search also returns other series
+        for slug in ["btc-updown-15m-1736949600", "eth-updown-1h-1736949600", "xeth-updown-15m-1736949600"] {
+            assert!(monitor.parse_market(&event(slug)).await.unwrap().is_none(), "{}", slug);
+        }
+    }
+}
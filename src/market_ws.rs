@@ -0,0 +1,562 @@
+//! Persistent market channel connection shared by all markets
+//!
+//! One socket to `/ws/market` for the whole process. Markets are added with
+//! [`MarketFeed::subscribe`] (each gets its own receiver) and removed with
+//! [`MarketFeed::unsubscribe`]; the asset set on the live connection is
+//! changed in place with `operation: subscribe/unsubscribe` messages, and
+//! the full set is re-sent after a reconnect. Markets are also retired
+//! automatically a little after they end, so nothing leaks if a caller
+//! forgets.
+//!
+//! Delivery goes through the conflating [`crate::event_bus`]: book updates
+//! overwrite a per-asset slot and everything else is queued, so a session
+//! that is busy posting orders never holds up the socket reader.
+//!
+//! [`MarketFeed::spawn_with_routes`] runs several identical connections,
+//! optionally over different routes, and forwards whichever copy of each
+//! update arrives first (see [`crate::feed_dedup`]). The feed only counts
+//! as disconnected, and books as gapped, when every connection is down.
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use futures_util::{SinkExt, StreamExt};
+use parking_lot::RwLock;
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpSocket;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+use tracing::{debug, error, info, warn};
+
+use crate::event_bus::{market_channel, MarketPublisher, MarketStream};
+use crate::feed_dedup::{dedup_key, ArrivalTracker, RouteStats};
+use crate::orderbook::{BookStatus, OrderbookManager};
+use crate::types::UpDownMarket;
+use crate::websocket::{handle_ws_value, parse_timestamp_ms, WsEvent};
+
+/// Markets are dropped this long after their end time
+const RETIRE_GRACE_SECS: i64 = 60;
+
+/// Where one connection goes: an endpoint, optionally from a specific local address
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarketRoute {
+    pub url: String,
+    pub bind: Option<IpAddr>,
+}
+
+impl MarketRoute {
+    /// Parse `"<url>"` or `"<url> via <local ip>"`
+    pub fn parse(spec: &str) -> Result<Self> {
+        let (url, bind) = match spec.split_once(" via ") {
+            Some((url, ip)) => {
+                let ip = ip.trim().parse().with_context(|| format!("Invalid local address in route '{}'", spec))?;
+                (url, Some(ip))
+            }
+            None => (spec, None),
+        };
+        Ok(Self { url: url.trim().to_string(), bind })
+    }
+
+    fn name(&self, conn: usize) -> String {
+        match self.bind {
+            Some(ip) => format!("#{} {} via {}", conn, self.url, ip),
+            None => format!("#{} {}", conn, self.url),
+        }
+    }
+}
+
+struct MarketSub {
+    asset_ids: Vec<String>,
+    end_time: chrono::DateTime<Utc>,
+    publisher: MarketPublisher,
+}
+
+struct FeedState {
+    markets: RwLock<HashMap<String, MarketSub>>, // condition_id -> subscription
+    live: AtomicUsize,                           // Connections currently streaming
+    changed: watch::Sender<()>,                  // Market set changed
+    arrivals: ArrivalTracker,
+}
+
+impl FeedState {
+    fn new(route_names: Vec<String>) -> Self {
+        Self {
+            markets: RwLock::new(HashMap::new()),
+            live: AtomicUsize::new(0),
+            changed: watch::channel(()).0,
+            arrivals: ArrivalTracker::new(route_names),
+        }
+    }
+
+    fn asset_ids(&self) -> HashSet<String> {
+        self.markets
+            .read()
+            .values()
+            .flat_map(|m| m.asset_ids.iter().cloned())
+            .collect()
+    }
+
+    /// Queue an event for every market
+    fn broadcast(&self, event: WsEvent) {
+        for sub in self.markets.read().values() {
+            sub.publisher.publish_event(event.clone());
+        }
+    }
+
+    fn notify_changed(&self) {
+        self.changed.send_replace(());
+    }
+}
+
+/// Handle to the shared market channel connection
+#[derive(Clone)]
+pub struct MarketFeed {
+    state: Arc<FeedState>,
+    orderbook_manager: Arc<OrderbookManager>,
+    shutdown: watch::Sender<bool>,
+    tasks: Arc<parking_lot::Mutex<Vec<JoinHandle<()>>>>,
+}
+
+impl MarketFeed {
+    /// Start one connection per route (`"<url>[ via <local ip>]"`); first arrival wins
+    pub fn spawn_with_routes(orderbook_manager: Arc<OrderbookManager>, routes: &[String]) -> Result<Self> {
+        let routes = routes.iter().map(|r| MarketRoute::parse(r)).collect::<Result<Vec<_>>>()?;
+        anyhow::ensure!(!routes.is_empty(), "No market data routes");
+        Ok(Self::spawn_routes(orderbook_manager, routes))
+    }
+
+    fn spawn_routes(orderbook_manager: Arc<OrderbookManager>, routes: Vec<MarketRoute>) -> Self {
+        let names = routes.iter().enumerate().map(|(i, r)| r.name(i)).collect();
+        let state = Arc::new(FeedState::new(names));
+        let (shutdown, shutdown_rx) = watch::channel(false);
+
+        if routes.len() > 1 {
+            info!("Market feed: {} redundant connections", routes.len());
+        }
+        let tasks = routes
+            .into_iter()
+            .enumerate()
+            .map(|(conn, route)| {
+                tokio::spawn(run_feed(state.clone(), orderbook_manager.clone(), conn, route, shutdown_rx.clone()))
+            })
+            .collect();
+
+        Self {
+            state,
+            orderbook_manager,
+            shutdown,
+            tasks: Arc::new(parking_lot::Mutex::new(tasks)),
+        }
+    }
+
+    /// Start streaming a market's books; events for its assets go to the returned stream
+    ///
+    /// Subscribing the same market again replaces its stream.
+    pub fn subscribe(&self, market: &UpDownMarket) -> MarketStream {
+        let asset_ids = vec![market.up_token_id.clone(), market.down_token_id.clone()];
+        let (publisher, stream) = market_channel(&asset_ids);
+
+        // Late joiners on a live socket still get the Connected they wait for
+        if self.is_connected() {
+            publisher.publish_event(WsEvent::Connected);
+        }
+
+        self.state.markets.write().insert(
+            market.condition_id.clone(),
+            MarketSub { asset_ids, end_time: market.end_time, publisher },
+        );
+        self.state.notify_changed();
+        info!("Market feed: subscribed {}", market.condition_id);
+        stream
+    }
+
+    /// Stop streaming a market and drop its local books
+    pub fn unsubscribe(&self, condition_id: &str) {
+        let removed = self.state.markets.write().remove(condition_id);
+        if let Some(sub) = removed {
+            let still_used = self.state.asset_ids();
+            for asset_id in sub.asset_ids.iter().filter(|a| !still_used.contains(*a)) {
+                self.orderbook_manager.remove(asset_id);
+            }
+            self.state.notify_changed();
+            info!("Market feed: unsubscribed {}", condition_id);
+        }
+    }
+
+    /// Markets currently subscribed
+    pub fn markets(&self) -> Vec<String> {
+        self.state.markets.read().keys().cloned().collect()
+    }
+
+    /// True while at least one connection is streaming
+    pub fn is_connected(&self) -> bool {
+        self.state.live.load(Ordering::SeqCst) > 0
+    }
+
+    /// First-arrival wins and latency per connection
+    pub fn route_stats(&self) -> Vec<RouteStats> {
+        self.state.arrivals.stats()
+    }
+
+    /// Log route stats when running redundant connections
+    pub fn log_route_stats(&self) {
+        let stats = self.route_stats();
+        if stats.len() < 2 {
+            return;
+        }
+        for route in stats {
+            info!(
+                "Market feed {}: won {}/{} ({:.0}%), lag behind winner avg {:.1}ms max {:.1}ms, exchange latency {}",
+                route.route,
+                route.first_arrivals,
+                route.first_arrivals + route.duplicates,
+                route.win_rate() * 100.0,
+                route.mean_lag_ms,
+                route.max_lag_ms,
+                route.mean_exchange_latency_ms.map(|l| format!("{:.1}ms", l)).unwrap_or_else(|| "n/a".to_string()),
+            );
+        }
+    }
+
+    /// Close the connections and wait for their tasks to exit
+    ///
+    /// Streams see a final `Disconnected`, then end.
+    pub async fn shutdown(&self) {
+        let _ = self.shutdown.send(true);
+        let tasks = std::mem::take(&mut *self.tasks.lock());
+        for task in tasks {
+            let _ = task.await;
+        }
+        self.state.markets.write().clear();
+    }
+}
+
+async fn run_feed(
+    state: Arc<FeedState>,
+    orderbook_manager: Arc<OrderbookManager>,
+    conn: usize,
+    route: MarketRoute,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    let mut changed = state.changed.subscribe();
+    let mut failures = 0u32;
+    while !*shutdown.borrow() {
+        if state.markets.read().is_empty() {
+            // Nothing to stream; an idle socket would just be dropped by the server
+            tokio::select! {
+                _ = changed.changed() => continue,
+                _ = shutdown.changed() => break,
+            }
+        }
+
+        let mut was_live = false;
+        let result =
+            run_connection(&state, &orderbook_manager, conn, &route, &mut changed, &mut shutdown, &mut was_live).await;
+
+        let still_live = if was_live {
+            state.live.fetch_sub(1, Ordering::SeqCst) - 1
+        } else {
+            state.live.load(Ordering::SeqCst)
+        };
+        if still_live == 0 {
+            // Whatever happened while we were down is lost
+            let assets: Vec<String> = state.asset_ids().into_iter().collect();
+            orderbook_manager.mark_gap(&assets, "websocket reconnect");
+
+            if let Err(e) = &result {
+                state.broadcast(WsEvent::Error(e.to_string()));
+            }
+            state.broadcast(WsEvent::Disconnected);
+        } else if was_live {
+            warn!("Market feed {} down, {} connection(s) still live", route.name(conn), still_live);
+        }
+
+        match result {
+            Ok(()) => failures = 0,
+            Err(e) => {
+                error!("Market feed {} error: {}", route.name(conn), e);
+                failures += 1;
+            }
+        }
+        if *shutdown.borrow() {
+            break;
+        }
+
+        let delay = if failures > 10 {
+            error!("Too many reconnects, backing off...");
+            failures = 0;
+            Duration::from_secs(30)
+        } else {
+            Duration::from_millis(500)
+        };
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            _ = shutdown.changed() => break,
+        }
+    }
+    info!("Market feed {} stopped", route.name(conn));
+}
+
+/// Open a socket over a route, binding the local address if one is set
+async fn connect_route(route: &MarketRoute) -> Result<WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>> {
+    let Some(bind) = route.bind else {
+        return Ok(connect_async(route.url.as_str()).await?.0);
+    };
+
+    let request = route.url.as_str().into_client_request()?;
+    let host = request.uri().host().context("Route URL has no host")?.to_string();
+    let port = request.uri().port_u16().unwrap_or(443);
+    let remote = tokio::net::lookup_host((host.as_str(), port))
+        .await?
+        .find(|addr| addr.is_ipv4() == bind.is_ipv4())
+        .with_context(|| format!("No {} address for {}", if bind.is_ipv4() { "IPv4" } else { "IPv6" }, host))?;
+
+    let socket = if bind.is_ipv4() { TcpSocket::new_v4()? } else { TcpSocket::new_v6()? };
+    socket.bind(SocketAddr::new(bind, 0))?;
+    let stream = socket.connect(remote).await?;
+    stream.set_nodelay(true)?;
+    Ok(tokio_tungstenite::client_async_tls(request, stream).await?.0)
+}
+
+/// One connection: subscribe, then stream until it drops or we shut down
+async fn run_connection(
+    state: &FeedState,
+    orderbook_manager: &OrderbookManager,
+    conn: usize,
+    route: &MarketRoute,
+    changed: &mut watch::Receiver<()>,
+    shutdown: &mut watch::Receiver<bool>,
+    was_live: &mut bool,
+) -> Result<()> {
+    info!("Connecting to WebSocket: {}", route.name(conn));
+    let ws_stream = tokio::time::timeout(Duration::from_secs(10), connect_route(route))
+        .await
+        .context("WebSocket connection timeout")?
+        .context("Failed to connect to WebSocket")?;
+    let (mut write, mut read) = ws_stream.split();
+
+    changed.borrow_and_update();
+    let mut subscribed = state.asset_ids();
+    let subscribe = json!({
+        "assets_ids": subscribed.iter().collect::<Vec<_>>(),
+        "type": "market"
+    });
+    write
+        .send(Message::Text(subscribe.to_string()))
+        .await
+        .context("Failed to subscribe to market")?;
+
+    info!("Market feed {} connected, {} tokens", route.name(conn), subscribed.len());
+    *was_live = true;
+    if state.live.fetch_add(1, Ordering::SeqCst) == 0 {
+        state.broadcast(WsEvent::Connected);
+    }
+
+    let mut ping = tokio::time::interval(Duration::from_secs(10));
+    let mut retire = tokio::time::interval(Duration::from_secs(30));
+
+    loop {
+        tokio::select! {
+            msg = read.next() => {
+                let text = match msg {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Binary(data))) => match String::from_utf8(data) {
+                        Ok(text) => text,
+                        Err(_) => continue,
+                    },
+                    Some(Ok(Message::Ping(data))) => {
+                        let _ = write.send(Message::Pong(data)).await;
+                        continue;
+                    }
+                    Some(Ok(Message::Close(_))) | None => {
+                        info!("Market feed closed by server");
+                        return Ok(());
+                    }
+                    Some(Err(e)) => anyhow::bail!("WebSocket error: {}", e),
+                    Some(Ok(_)) => continue,
+                };
+                if text == "PONG" {
+                    continue;
+                }
+
+                let msg: serde_json::Value = match serde_json::from_str(&text) {
+                    Ok(msg) => msg,
+                    Err(e) => {
+                        debug!("Failed to handle message: {}", e);
+                        continue;
+                    }
+                };
+                // Another connection may already have delivered this update
+                let timestamp = parse_timestamp_ms(msg.get("timestamp").or_else(|| msg.get("t")));
+                if !state.arrivals.observe(conn, dedup_key(&msg).as_deref(), timestamp) {
+                    continue;
+                }
+                for event in handle_ws_value(&msg, orderbook_manager) {
+                    route_event(state, orderbook_manager, event);
+                }
+            }
+            _ = changed.changed() => {
+                let wanted = state.asset_ids();
+                let added: Vec<&String> = wanted.difference(&subscribed).collect();
+                let removed: Vec<&String> = subscribed.difference(&wanted).collect();
+
+                if !added.is_empty() {
+                    let msg = json!({"assets_ids": added, "operation": "subscribe"});
+                    write.send(Message::Text(msg.to_string())).await.context("Failed to subscribe")?;
+                    debug!("Market feed +{} tokens", added.len());
+                }
+                if !removed.is_empty() {
+                    let msg = json!({"assets_ids": removed, "operation": "unsubscribe"});
+                    write.send(Message::Text(msg.to_string())).await.context("Failed to unsubscribe")?;
+                    debug!("Market feed -{} tokens", removed.len());
+                }
+                subscribed = wanted;
+            }
+            _ = ping.tick() => {
+                // Polymarket expects text "PING" not binary ping frames
+                write.send(Message::Text("PING".to_string())).await.context("Ping failed")?;
+            }
+            _ = retire.tick() => {
+                retire_ended(state, orderbook_manager);
+            }
+            _ = shutdown.changed() => {
+                let _ = write.send(Message::Close(None)).await;
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Per-asset events go to the owning market, connection events to everyone
+///
+/// Book updates only refresh the market's top-of-book slot from the local
+/// book; a trust change also queues the event so it can't be conflated away.
+fn route_event(state: &FeedState, orderbook_manager: &OrderbookManager, event: WsEvent) {
+    let (asset_id, is_book) = match &event {
+        WsEvent::OrderbookUpdate { asset_id, .. }
+        | WsEvent::PriceChange { asset_id, .. }
+        | WsEvent::BookUnreliable { asset_id, .. } => (asset_id.as_str(), true),
+        WsEvent::LastTrade(print) => (print.asset_id.as_str(), false),
+        WsEvent::TickSizeChange(change) => (change.asset_id.as_str(), false),
+        WsEvent::TradeFill(fill) => (fill.asset_id.as_str(), false),
+        _ => {
+            state.broadcast(event);
+            return;
+        }
+    };
+
+    let markets = state.markets.read();
+    let Some(sub) = markets.values().find(|m| m.publisher.has_asset(asset_id)) else { return };
+    if is_book {
+        let (best_bid, best_ask) = orderbook_manager.best_bid_ask(asset_id).unwrap_or_default();
+        let trusted = orderbook_manager.book_status(asset_id) == BookStatus::Trusted;
+        sub.publisher.publish_book(asset_id, best_bid, best_ask, trusted);
+    }
+    if !is_book || matches!(event, WsEvent::BookUnreliable { .. }) {
+        sub.publisher.publish_event(event);
+    }
+}
+
+/// Drop markets that ended more than the grace period ago
+fn retire_ended(state: &FeedState, orderbook_manager: &OrderbookManager) {
+    let cutoff = Utc::now() - chrono::Duration::seconds(RETIRE_GRACE_SECS);
+    let ended: Vec<(String, Vec<String>)> = state
+        .markets
+        .read()
+        .iter()
+        .filter(|(_, m)| m.end_time < cutoff)
+        .map(|(id, m)| (id.clone(), m.asset_ids.clone()))
+        .collect();
+    if ended.is_empty() {
+        return;
+    }
+
+    let mut markets = state.markets.write();
+    for (condition_id, asset_ids) in ended {
+        markets.remove(&condition_id);
+        for asset_id in &asset_ids {
+            orderbook_manager.remove(asset_id);
+        }
+        warn!("Market feed: retired ended market {}", condition_id);
+    }
+    drop(markets);
+    state.notify_changed();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn state_with(markets: &[(&str, &[&str], i64)]) -> (FeedState, Vec<MarketStream>) {
+        let state = FeedState::new(vec!["#0".to_string()]);
+        let mut streams = Vec::new();
+        for (id, assets, ends_in_secs) in markets {
+            let asset_ids: Vec<String> = assets.iter().map(|a| a.to_string()).collect();
+            let (publisher, stream) = market_channel(&asset_ids);
+            state.markets.write().insert(
+                id.to_string(),
+                MarketSub {
+                    asset_ids,
+                    end_time: Utc::now() + chrono::Duration::seconds(*ends_in_secs),
+                    publisher,
+                },
+            );
+            streams.push(stream);
+        }
+        (state, streams)
+    }
+
+    #[tokio::test]
+    async fn test_routes_by_asset_and_retires_ended() {
+        let (state, mut rx) = state_with(&[("m1", &["a1", "a2"], 600), ("m2", &["b1", "b2"], -120)]);
+        let books = OrderbookManager::new();
+        books.update("b2", &[("0.4".to_string(), "10".to_string())], &[("0.6".to_string(), "10".to_string())]);
+
+        let price_change = || WsEvent::PriceChange {
+            asset_id: "b2".to_string(),
+            best_bid: "0.4".to_string(),
+            best_ask: "0.6".to_string(),
+        };
+        route_event(&state, &books, price_change());
+        route_event(&state, &books, price_change());
+        route_event(&state, &books, WsEvent::Disconnected);
+
+        assert!(matches!(rx[0].recv().await, Some(WsEvent::Disconnected)));
+        // Queued events come before the (conflated) book
+        assert!(matches!(rx[1].recv().await, Some(WsEvent::Disconnected)));
+        match rx[1].recv().await {
+            Some(WsEvent::Book(top)) => {
+                assert_eq!(top.asset_id, "b2");
+                assert_eq!((top.best_bid, top.best_ask), (Some(dec!(0.4)), Some(dec!(0.6))));
+                assert_eq!(top.version, 2);
+            }
+            other => panic!("expected book, got {:?}", other),
+        }
+        assert_eq!(rx[1].stats().books_conflated, 1);
+
+        // m2 ended two minutes ago
+        books.update("b1", &[], &[]);
+        retire_ended(&state, &books);
+        assert_eq!(state.markets.read().keys().cloned().collect::<Vec<_>>(), vec!["m1".to_string()]);
+        assert_eq!(state.asset_ids(), HashSet::from(["a1".to_string(), "a2".to_string()]));
+        assert_eq!(books.book_status("b1"), crate::orderbook::BookStatus::Missing);
+        // Dropping the publisher ends the retired market's stream
+        assert!(rx[1].recv().await.is_none());
+    }
+
+    #[test]
+    fn test_parse_route() {
+        let route = MarketRoute::parse("wss://relay.example/ws/market via 10.0.1.5").unwrap();
+        assert_eq!(route.url, "wss://relay.example/ws/market");
+        assert_eq!(route.bind, Some("10.0.1.5".parse().unwrap()));
+        assert_eq!(MarketRoute::parse("wss://ws-subscriptions-clob.polymarket.com/ws/market").unwrap().bind, None);
+        assert!(MarketRoute::parse("wss://x/ws/market via eth0").is_err());
+    }
+}
@@ -19,10 +19,10 @@ use crate::strategies::{
     momentum::MomentumStrategy,
     pure_arb::PureArbStrategy,
     scalper::ScalperStrategy,
-    MarketState, Outcome, OrderSide, PositionState, StrategyMetrics,
+    snap_to_tick, MarketState, Outcome, OrderSide, PositionState, StrategyMetrics,
     StrategyOrder, StrategySignal, TradingStrategy,
 };
-use crate::types::BtcMarket;
+use crate::types::UpDownMarket;
 
 /// Virtual position for each strategy
 #[derive(Debug, Clone, Default)]
@@ -139,7 +139,7 @@ impl MultiStrategyRunner {
     }
 
     /// Called when market starts
-    pub async fn on_market_start(&mut self, market: &BtcMarket, state: &MarketState) {
+    pub async fn on_market_start(&mut self, market: &UpDownMarket, state: &MarketState) {
         // Reset positions for new market
         for (_, pos) in self.positions.iter_mut() {
             *pos = VirtualPosition::default();
@@ -166,7 +166,7 @@ impl MultiStrategyRunner {
     }
 
     /// Called on orderbook update
-    pub async fn on_orderbook_update(&mut self, market: &BtcMarket, state: &MarketState) {
+    pub async fn on_orderbook_update(&mut self, market: &UpDownMarket, state: &MarketState) {
         // Simulate fills based on price movements
         self.simulate_fills(state);
 
@@ -198,7 +198,7 @@ impl MultiStrategyRunner {
     /// Called on fill (in real mode, this would come from WebSocket)
     pub async fn on_fill(
         &mut self,
-        market: &BtcMarket,
+        market: &UpDownMarket,
         outcome: Outcome,
         price: Decimal,
         size: Decimal,
@@ -216,7 +216,7 @@ impl MultiStrategyRunner {
     }
 
     /// Called every 30 seconds
-    pub async fn on_tick(&mut self, market: &BtcMarket, state: &MarketState) {
+    pub async fn on_tick(&mut self, market: &UpDownMarket, state: &MarketState) {
         // Collect positions first
         let positions: Vec<_> = self.strategies.iter()
             .map(|s| {
@@ -245,8 +245,39 @@ impl MultiStrategyRunner {
         self.print_standings();
     }
 
+    /// Called when the market's tick size changes (`market.tick_size` is the new tick)
+    pub async fn on_tick_size_change(&mut self, market: &UpDownMarket, state: &MarketState) {
+        info!("Tick size now {}, re-pricing resting orders", market.tick_size);
+
+        // Resting orders off the new grid would be rejected
+        for pos in self.positions.values_mut() {
+            for order in &mut pos.pending_orders {
+                order.price = snap_to_tick(order.price, market.tick_size, order.side);
+            }
+        }
+
+        let positions: Vec<_> = self.strategies.iter()
+            .map(|s| {
+                self.positions
+                    .get(s.name())
+                    .map(|p| p.to_position_state())
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        let mut signals = Vec::new();
+        for (i, strategy) in self.strategies.iter_mut().enumerate() {
+            let signal = strategy.on_tick_size_change(market, state, &positions[i]).await;
+            signals.push((strategy.name().to_string(), signal));
+        }
+
+        for (name, signal) in signals {
+            self.process_signal(&name, signal, state);
+        }
+    }
+
     /// Called before resolution
-    pub async fn on_pre_resolution(&mut self, market: &BtcMarket) {
+    pub async fn on_pre_resolution(&mut self, market: &UpDownMarket) {
         for strategy in &mut self.strategies {
             let position = self.positions
                 .get(strategy.name())
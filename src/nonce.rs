@@ -0,0 +1,396 @@
+//! On-chain order nonce management
+//!
+//! Each exchange contract keeps `nonces[maker]`, and an order is only
+//! fillable while its nonce equals that value. Calling `incrementNonce()`
+//! from the maker bumps it and kills every outstanding order on that
+//! exchange in one transaction: an emergency stop that works even when the
+//! CLOB REST API is unreachable.
+//!
+//! The CTF and NegRisk exchanges track nonces separately, so a full stop
+//! needs one transaction per exchange.
+//!
+//! Only EOA makers (signatureType 0) can do this directly; for proxy/safe
+//! wallets the call has to go through the wallet contract.
+
+use anyhow::{Context, Result};
+use ethers::providers::{Http, Middleware, Provider};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{
+    Address, Bytes, Eip1559TransactionRequest, TransactionReceipt, TransactionRequest, U256,
+};
+use ethers::utils::keccak256;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::eip712::Exchange;
+use crate::signer::OrderSigner;
+use crate::signing_backend::SigningBackend;
+
+/// incrementNonce() costs ~30k gas; leave headroom
+pub const INCREMENT_NONCE_GAS: u64 = 100_000;
+
+/// How long to wait for the transaction to be mined
+const RECEIPT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Narrow a chain value to u64, failing instead of panicking
+pub fn checked_u64(value: U256, what: &str) -> Result<u64> {
+    u64::try_from(value).map_err(|_| anyhow::anyhow!("{} does not fit in u64: {}", what, value))
+}
+
+fn selector(signature: &str) -> [u8; 4] {
+    let hash = keccak256(signature);
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Calldata for `nonces(address)`
+pub fn nonces_calldata(maker: Address) -> Bytes {
+    let mut data = Vec::with_capacity(36);
+    data.extend_from_slice(&selector("nonces(address)"));
+    data.extend_from_slice(&[0u8; 12]);
+    data.extend_from_slice(maker.as_bytes());
+    data.into()
+}
+
+/// Calldata for `incrementNonce()`
+pub fn increment_nonce_calldata() -> Bytes {
+    selector("incrementNonce()").to_vec().into()
+}
+
+/// Unsigned EIP-1559 `incrementNonce()` call to an exchange contract
+pub fn build_increment_nonce_tx(
+    contract: Address,
+    from: Address,
+    tx_nonce: U256,
+    max_fee_per_gas: U256,
+    max_priority_fee_per_gas: U256,
+    chain_id: u64,
+) -> TypedTransaction {
+    Eip1559TransactionRequest::new()
+        .from(from)
+        .to(contract)
+        .data(increment_nonce_calldata())
+        .nonce(tx_nonce)
+        .gas(INCREMENT_NONCE_GAS)
+        .max_fee_per_gas(max_fee_per_gas)
+        .max_priority_fee_per_gas(max_priority_fee_per_gas)
+        .chain_id(chain_id)
+        .into()
+}
+
+/// Check that a transaction is a plain `incrementNonce()` call to one of the exchanges
+///
+/// The remote signer only signs these, so a connecting process can't get an
+/// arbitrary transaction out of it.
+pub fn check_increment_nonce_tx(tx: &TypedTransaction) -> Result<()> {
+    let exchanges = [Exchange::Ctf.address(), Exchange::NegRisk.address()];
+    match tx.to_addr() {
+        Some(to) if exchanges.contains(to) => {}
+        other => anyhow::bail!("Not an exchange contract: {:?}", other),
+    }
+    if tx.data() != Some(&increment_nonce_calldata()) {
+        anyhow::bail!("Calldata is not incrementNonce()");
+    }
+    if tx.value().is_some_and(|v| !v.is_zero()) {
+        anyhow::bail!("incrementNonce() must not send value");
+    }
+    Ok(())
+}
+
+/// Sign a transaction through a signing backend, returning raw RLP bytes
+pub async fn sign_transaction(backend: &dyn SigningBackend, tx: &TypedTransaction) -> Result<Bytes> {
+    // U64, so the low word is the whole value
+    let chain_id = tx.chain_id().context("Transaction has no chain id")?.low_u64();
+
+    let mut signature = backend.sign_transaction(tx).await?;
+    // Transactions want the EIP-155 form; backends may hand back 27/28 or a bare parity
+    let parity = match signature.v {
+        27 | 28 => signature.v - 27,
+        0 | 1 => signature.v,
+        v => anyhow::bail!("Unexpected signature v: {}", v),
+    };
+    signature.v = ethers::signers::to_eip155_v(parity as u8, chain_id);
+
+    Ok(tx.rlp_signed(&signature))
+}
+
+/// JSON-RPC client for reading and bumping exchange nonces
+///
+/// Works against Polygon or a local anvil/hardhat node; contract addresses
+/// default to the Polygon exchanges and can be pointed at stand-ins.
+pub struct NonceClient {
+    provider: Provider<Http>,
+    chain_id: u64,
+    ctf_contract: Address,
+    neg_risk_contract: Address,
+}
+
+impl NonceClient {
+    pub async fn connect(rpc_url: &str) -> Result<Self> {
+        let provider = Provider::<Http>::try_from(rpc_url).context("Invalid RPC URL")?;
+        let chain_id = provider
+            .get_chainid()
+            .await
+            .with_context(|| format!("Failed to reach RPC {}", rpc_url))?;
+        let chain_id = checked_u64(chain_id, "chain id")?;
+
+        Ok(Self {
+            provider,
+            chain_id,
+            ctf_contract: Exchange::Ctf.address(),
+            neg_risk_contract: Exchange::NegRisk.address(),
+        })
+    }
+
+    /// Point an exchange at a different contract (local test deployments)
+    pub fn with_contract(mut self, exchange: Exchange, address: Address) -> Self {
+        match exchange {
+            Exchange::Ctf => self.ctf_contract = address,
+            Exchange::NegRisk => self.neg_risk_contract = address,
+        }
+        self
+    }
+
+    pub fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    pub fn contract(&self, exchange: Exchange) -> Address {
+        match exchange {
+            Exchange::Ctf => self.ctf_contract,
+            Exchange::NegRisk => self.neg_risk_contract,
+        }
+    }
+
+    /// Current order nonce for a maker on an exchange
+    pub async fn fetch_nonce(&self, exchange: Exchange, maker: Address) -> Result<U256> {
+        let call: TypedTransaction = TransactionRequest::new()
+            .to(self.contract(exchange))
+            .data(nonces_calldata(maker))
+            .into();
+
+        let result = self.provider.call(&call, None).await.context("nonces() call failed")?;
+        if result.len() != 32 {
+            anyhow::bail!("nonces() returned {} bytes (no contract at {:?}?)", result.len(), self.contract(exchange));
+        }
+        Ok(U256::from_big_endian(&result))
+    }
+
+    /// Build, sign and send `incrementNonce()`, waiting for the receipt
+    pub async fn increment_nonce(&self, backend: &dyn SigningBackend, exchange: Exchange) -> Result<TransactionReceipt> {
+        let from = backend.address();
+        let tx_nonce = self.provider.get_transaction_count(from, None).await?;
+        let (max_fee, priority_fee) = self
+            .provider
+            .estimate_eip1559_fees(None)
+            .await
+            .context("Failed to estimate gas fees")?;
+
+        let tx = build_increment_nonce_tx(self.contract(exchange), from, tx_nonce, max_fee, priority_fee, self.chain_id);
+        let raw = sign_transaction(backend, &tx).await?;
+
+        let pending = self.provider.send_raw_transaction(raw).await.context("Failed to send incrementNonce")?;
+        let tx_hash = pending.tx_hash();
+        info!("incrementNonce sent on {:?}: {:?}", exchange, tx_hash);
+
+        let receipt = tokio::time::timeout(RECEIPT_TIMEOUT, pending)
+            .await
+            .context("Timed out waiting for incrementNonce receipt")??
+            .context("incrementNonce transaction dropped")?;
+
+        if receipt.status != Some(1u64.into()) {
+            anyhow::bail!("incrementNonce reverted: {:?}", tx_hash);
+        }
+        Ok(receipt)
+    }
+
+    /// Invalidate every outstanding order on both exchanges
+    ///
+    /// Bumps the signer's local nonces so new orders are signed against the
+    /// new on-chain value.
+    pub async fn cancel_all(&self, signer: &OrderSigner) -> Result<Vec<TransactionReceipt>> {
+        if signer.address() != signer.funder() {
+            anyhow::bail!(
+                "Maker {:?} is not the signing key {:?}; incrementNonce must be sent from the maker wallet",
+                signer.funder(),
+                signer.address()
+            );
+        }
+
+        let backend = signer.backend();
+        let mut receipts = Vec::new();
+        for exchange in [Exchange::NegRisk, Exchange::Ctf] {
+            let receipt = self.increment_nonce(backend.as_ref(), exchange).await?;
+            receipts.push(receipt);
+
+            let fetched = self
+                .fetch_nonce(exchange, signer.funder())
+                .await
+                .and_then(|nonce| checked_u64(nonce, "order nonce"));
+            let new_nonce = match fetched {
+                Ok(nonce) => nonce,
+                Err(e) => {
+                    warn!("Could not read back {:?} nonce ({}), assuming +1", exchange, e);
+                    signer.nonce(exchange) + 1
+                }
+            };
+            signer.set_nonce(exchange, new_nonce);
+            info!("{:?} order nonce now {}", exchange, new_nonce);
+        }
+        Ok(receipts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signing_backend::LocalKeyBackend;
+    use crate::test_support::{TEST_ADDRESS, TEST_KEY};
+    use crate::types::Side;
+    use ethers::utils::rlp::Rlp;
+    use rust_decimal_macros::dec;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_selectors() {
+        assert_eq!(increment_nonce_calldata().to_vec(), vec![0x62, 0x7c, 0xdc, 0xb9]);
+        let data = nonces_calldata(Address::from_str(TEST_ADDRESS).unwrap());
+        assert_eq!(&data[..4], &[0x7e, 0xce, 0xbe, 0x00]);
+        assert_eq!(data.len(), 36);
+    }
+
+    #[tokio::test]
+    async fn test_signed_tx_recovers_sender() {
+        let backend = LocalKeyBackend::new(TEST_KEY).unwrap();
+        let tx = build_increment_nonce_tx(
+            Exchange::NegRisk.address(),
+            backend.address(),
+            U256::from(7),
+            U256::from(100_000_000_000u64),
+            U256::from(30_000_000_000u64),
+            137,
+        );
+
+        let raw = sign_transaction(&backend, &tx).await.unwrap();
+        let (decoded, signature) = TypedTransaction::decode_signed(&Rlp::new(&raw)).unwrap();
+        assert_eq!(decoded.to_addr(), Some(&Exchange::NegRisk.address()));
+        assert_eq!(decoded.data().unwrap(), &increment_nonce_calldata());
+        assert_eq!(signature.recover(decoded.sighash()).unwrap(), backend.address());
+    }
+
+    /// Backend that reports v in a fixed convention
+    struct ParityBackend {
+        inner: LocalKeyBackend,
+        v_offset: Option<u64>,
+    }
+
+    #[async_trait::async_trait]
+    impl SigningBackend for ParityBackend {
+        fn address(&self) -> Address {
+            self.inner.address()
+        }
+
+        async fn sign_hash(&self, hash: ethers::types::H256) -> Result<ethers::types::Signature> {
+            let mut signature = self.inner.sign_hash(hash).await?;
+            signature.v = match self.v_offset {
+                Some(offset) => signature.v - 27 + offset,
+                None => 99,
+            };
+            Ok(signature)
+        }
+
+        fn describe(&self) -> String {
+            "parity test".to_string()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sign_transaction_normalises_v() {
+        let tx = build_increment_nonce_tx(
+            Exchange::Ctf.address(),
+            Address::from_str(TEST_ADDRESS).unwrap(),
+            U256::from(1),
+            U256::from(1),
+            U256::from(1),
+            137,
+        );
+
+        for offset in [0, 27] {
+            let backend = ParityBackend {
+                inner: LocalKeyBackend::new(TEST_KEY).unwrap(),
+                v_offset: Some(offset),
+            };
+            let raw = sign_transaction(&backend, &tx).await.unwrap();
+            let (decoded, signature) = TypedTransaction::decode_signed(&Rlp::new(&raw)).unwrap();
+            assert_eq!(signature.recover(decoded.sighash()).unwrap(), backend.address());
+        }
+
+        let backend = ParityBackend {
+            inner: LocalKeyBackend::new(TEST_KEY).unwrap(),
+            v_offset: None,
+        };
+        assert!(sign_transaction(&backend, &tx).await.is_err());
+    }
+
+    #[test]
+    fn test_checked_u64() {
+        assert_eq!(checked_u64(U256::from(137), "chain id").unwrap(), 137);
+        assert!(checked_u64(U256::MAX, "order nonce").is_err());
+    }
+
+    /// Stand-in exchange: `nonces(address)` reads and `incrementNonce()` bumps
+    /// a counter stored at the caller's address
+    ///
+    /// Runtime: dispatch on the selector, `SLOAD(CALLER) + 1` for increment,
+    /// `SLOAD(arg0)` returned as one word for the getter, revert otherwise.
+    const NONCE_STUB_INITCODE: &str = concat!(
+        // constructor: return the 0x33-byte runtime that follows
+        "603380600b6000396000f3",
+        "60003560e01c",                 // selector
+        "8063627cdcb914601d57",         // incrementNonce() -> 0x1d
+        "637ecebe0014602657",           // nonces(address) -> 0x26
+        "600080fd",                     // revert
+        "5b3354600101335500",           // 0x1d: nonces[caller] += 1
+        "5b6004355460005260206000f3",   // 0x26: return nonces[arg0]
+    );
+
+    /// Needs a local node: `anvil` then
+    /// `ANVIL_RPC_URL=http://127.0.0.1:8545 cargo test --lib nonce -- --ignored`
+    #[tokio::test]
+    #[ignore]
+    async fn test_increment_nonce_against_local_node() {
+        let rpc_url = std::env::var("ANVIL_RPC_URL").unwrap_or_else(|_| "http://127.0.0.1:8545".to_string());
+        let client = NonceClient::connect(&rpc_url).await.unwrap();
+        let backend = LocalKeyBackend::new(TEST_KEY).unwrap();
+
+        // anvil keeps its dev accounts unlocked, so it signs the deployment itself
+        let deploy = TransactionRequest::new()
+            .from(backend.address())
+            .data(Bytes::from(ethers::utils::hex::decode(NONCE_STUB_INITCODE).unwrap()));
+        let receipt = client
+            .provider
+            .send_transaction(deploy, None)
+            .await
+            .unwrap()
+            .await
+            .unwrap()
+            .unwrap();
+        let stub = receipt.contract_address.unwrap();
+        let client = client.with_contract(Exchange::Ctf, stub);
+
+        let before = client.fetch_nonce(Exchange::Ctf, backend.address()).await.unwrap();
+        let receipt = client.increment_nonce(&backend, Exchange::Ctf).await.unwrap();
+        assert_eq!(receipt.to, Some(stub));
+        let after = client.fetch_nonce(Exchange::Ctf, backend.address()).await.unwrap();
+        assert_eq!(after, before + 1);
+
+        // New orders pick up the bumped nonce once the signer is told about it
+        let signer = OrderSigner::new(TEST_KEY, TEST_ADDRESS).unwrap();
+        signer.set_nonce(Exchange::Ctf, checked_u64(after, "order nonce").unwrap());
+        assert_eq!(signer.nonce(Exchange::Ctf), checked_u64(after, "order nonce").unwrap());
+        let order = signer
+            .create_order("1", dec!(0.5), dec!(10), Side::Buy, dec!(0.01), false)
+            .await
+            .unwrap();
+        assert_eq!(order.order.nonce, after.to_string());
+    }
+}
@@ -0,0 +1,215 @@
+//! Order amount calculation following Polymarket's rounding rules
+//!
+//! The exchange validates maker/taker amounts against the market's tick size.
+//! Each tick size has its own number of decimals for price, size and amount
+//! (same table as the official py-clob-client `ROUNDING_CONFIG`).
+//! Amounts computed naively (`size * price`) get rejected when they carry
+//! more decimals than the tick size allows.
+
+use anyhow::Result;
+use rust_decimal::{Decimal, RoundingStrategy};
+use rust_decimal_macros::dec;
+
+use crate::types::{OrderType, Side};
+
+/// USDC and conditional tokens both use 6 decimals on-chain
+const TOKEN_DECIMALS: u32 = 6;
+
+/// Default minimum order size (shares) when the market doesn't report one
+pub const DEFAULT_MIN_ORDER_SIZE: Decimal = dec!(5);
+
+/// Decimal places allowed for each part of an order at a given tick size
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoundConfig {
+    pub price: u32,
+    pub size: u32,
+    pub amount: u32,
+}
+
+impl RoundConfig {
+    /// Look up the rounding config for a market tick size
+    pub fn for_tick_size(tick_size: Decimal) -> Result<Self> {
+        let config = match tick_size.normalize().to_string().as_str() {
+            "0.1" => RoundConfig { price: 1, size: 2, amount: 3 },
+            "0.01" => RoundConfig { price: 2, size: 2, amount: 4 },
+            "0.001" => RoundConfig { price: 3, size: 2, amount: 5 },
+            "0.0001" => RoundConfig { price: 4, size: 2, amount: 6 },
+            other => anyhow::bail!("Unsupported tick size: {}", other),
+        };
+        Ok(config)
+    }
+}
+
+/// Maker/taker amounts in raw token units (6 decimals), ready for signing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderAmounts {
+    pub maker_amount: String,
+    pub taker_amount: String,
+}
+
+fn round_down(x: Decimal, dp: u32) -> Decimal {
+    x.round_dp_with_strategy(dp, RoundingStrategy::ToZero)
+}
+
+fn round_up(x: Decimal, dp: u32) -> Decimal {
+    x.round_dp_with_strategy(dp, RoundingStrategy::AwayFromZero)
+}
+
+fn round_normal(x: Decimal, dp: u32) -> Decimal {
+    x.round_dp_with_strategy(dp, RoundingStrategy::MidpointAwayFromZero)
+}
+
+fn decimal_places(x: Decimal) -> u32 {
+    x.normalize().scale()
+}
+
+/// Clamp a derived amount to the allowed decimals
+///
+/// Rounds up at `amount + 4` first so float-like tails (e.g. 12.00000001)
+/// collapse to the intended value, then truncates if still too precise.
+fn fit_amount(mut amount: Decimal, config: RoundConfig) -> Decimal {
+    if decimal_places(amount) > config.amount {
+        amount = round_up(amount, config.amount + 4);
+        if decimal_places(amount) > config.amount {
+            amount = round_down(amount, config.amount);
+        }
+    }
+    amount
+}
+
+fn to_token_units(x: Decimal) -> String {
+    (x * Decimal::from(10u64.pow(TOKEN_DECIMALS))).round().to_string()
+}
+
+/// Check that a price sits inside the tradable range for a tick size
+pub fn validate_price(price: Decimal, tick_size: Decimal) -> Result<()> {
+    if price < tick_size || price > Decimal::ONE - tick_size {
+        anyhow::bail!(
+            "Price {} outside valid range [{}, {}] for tick size {}",
+            price,
+            tick_size,
+            Decimal::ONE - tick_size,
+            tick_size
+        );
+    }
+    Ok(())
+}
+
+/// Calculate amounts for a limit order (GTC/GTD)
+///
+/// For BUY orders:
+///   - makerAmount = size * price (USDC cost - what you PAY)
+///   - takerAmount = size (shares - what you GET)
+///
+/// For SELL orders:
+///   - makerAmount = size (shares - what you SELL)
+///   - takerAmount = size * price (USDC - what you GET)
+pub fn limit_order_amounts(
+    side: Side,
+    price: Decimal,
+    size: Decimal,
+    tick_size: Decimal,
+) -> Result<OrderAmounts> {
+    let config = RoundConfig::for_tick_size(tick_size)?;
+    let price = round_normal(price, config.price);
+    let shares = round_down(size, config.size);
+    let usdc = fit_amount(shares * price, config);
+
+    let (maker, taker) = match side {
+        Side::Buy => (usdc, shares),
+        Side::Sell => (shares, usdc),
+    };
+
+    Ok(OrderAmounts {
+        maker_amount: to_token_units(maker),
+        taker_amount: to_token_units(taker),
+    })
+}
+
+/// Calculate amounts for a market order (FOK/FAK)
+///
+/// `amount` is USDC to spend for BUY orders and shares to sell for SELL orders.
+/// `price` is the worst acceptable price and bounds the amount we receive.
+pub fn market_order_amounts(
+    side: Side,
+    amount: Decimal,
+    price: Decimal,
+    tick_size: Decimal,
+) -> Result<OrderAmounts> {
+    let config = RoundConfig::for_tick_size(tick_size)?;
+    let price = round_normal(price, config.price);
+    let maker = round_down(amount, config.size);
+
+    let taker = match side {
+        Side::Buy => fit_amount(maker / price, config),
+        Side::Sell => fit_amount(maker * price, config),
+    };
+
+    Ok(OrderAmounts {
+        maker_amount: to_token_units(maker),
+        taker_amount: to_token_units(taker),
+    })
+}
+
+/// Reject orders below the market's minimum size (in shares)
+pub fn validate_min_size(shares: Decimal, min_order_size: Decimal) -> Result<()> {
+    if shares < min_order_size {
+        anyhow::bail!(
+            "Order size {} shares below market minimum {}",
+            shares.round_dp(2),
+            min_order_size
+        );
+    }
+    Ok(())
+}
+
+/// Check an order type is valid for a market (taker-only) order
+pub fn validate_market_order_type(order_type: OrderType) -> Result<()> {
+    match order_type {
+        OrderType::Fok | OrderType::Fak => Ok(()),
+        other => anyhow::bail!("{:?} is not a market order type (use FOK or FAK)", other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limit_amounts_match_naive_on_clean_inputs() {
+        let amounts = limit_order_amounts(Side::Buy, dec!(0.48), dec!(100), dec!(0.01)).unwrap();
+        assert_eq!(amounts.maker_amount, "48000000");
+        assert_eq!(amounts.taker_amount, "100000000");
+
+        let amounts = limit_order_amounts(Side::Sell, dec!(0.52), dec!(50), dec!(0.01)).unwrap();
+        assert_eq!(amounts.maker_amount, "50000000");
+        assert_eq!(amounts.taker_amount, "26000000");
+    }
+
+    #[test]
+    fn test_limit_amounts_respect_tick_decimals() {
+        // 20.833333 shares @ 0.48: size truncates to 20.83, cost 9.9984 fits 4 decimals
+        let amounts = limit_order_amounts(Side::Buy, dec!(0.48), dec!(20.833333), dec!(0.01)).unwrap();
+        assert_eq!(amounts.taker_amount, "20830000");
+        assert_eq!(amounts.maker_amount, "9998400");
+
+        // 0.1 tick: price rounds to 1 decimal, amount to 3
+        let amounts = limit_order_amounts(Side::Buy, dec!(0.46), dec!(10.555), dec!(0.1)).unwrap();
+        assert_eq!(amounts.taker_amount, "10550000");
+        assert_eq!(amounts.maker_amount, "5275000");
+    }
+
+    #[test]
+    fn test_market_buy_amounts() {
+        // Spend $10 at worst price 0.3 -> 33.3333 shares (4 decimals at 0.01 tick)
+        let amounts = market_order_amounts(Side::Buy, dec!(10), dec!(0.3), dec!(0.01)).unwrap();
+        assert_eq!(amounts.maker_amount, "10000000");
+        assert_eq!(amounts.taker_amount, "33333300");
+    }
+
+    #[test]
+    fn test_unsupported_tick_size() {
+        assert!(RoundConfig::for_tick_size(dec!(0.05)).is_err());
+        assert!(RoundConfig::for_tick_size(dec!(0.010)).is_ok());
+    }
+}
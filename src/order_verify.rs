@@ -0,0 +1,255 @@
+//! Signed order verification
+//!
+//! Re-runs the checks the exchange applies to a signed order so we can catch
+//! bad orders before posting them, and audit stored orders afterwards:
+//! - EIP-712 hash against the right exchange (CTF vs NegRisk)
+//! - Signer recovery from the signature
+//! - maker/signer/signatureType consistency
+//! - maker/taker amount ratio gives a valid price on the tick grid
+//! - expiration vs the exchange's 60s security threshold
+
+use anyhow::{Context, Result};
+use ethers::types::{Address, Signature, H256};
+use ethers::utils::hex;
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+use crate::eip712::{Exchange, OrderData};
+use crate::types::{Order, OrderType, Side, SignedOrder};
+
+/// Signature types accepted by the CTF Exchange
+const SIGNATURE_TYPE_EOA: u8 = 0;
+const SIGNATURE_TYPE_POLY_PROXY: u8 = 1;
+const SIGNATURE_TYPE_POLY_GNOSIS_SAFE: u8 = 2;
+
+/// Orders must expire at least this far in the future to be accepted
+const EXPIRATION_SECURITY_THRESHOLD_SECS: i64 = 60;
+
+/// Result of verifying a signed order
+#[derive(Debug, Clone)]
+pub struct OrderVerification {
+    pub exchange: Exchange,
+    pub order_hash: H256,
+    pub recovered_signer: Option<Address>,
+    pub implied_price: Option<Decimal>,
+    pub errors: Vec<String>,
+}
+
+impl OrderVerification {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+impl std::fmt::Display for OrderVerification {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Exchange:  {:?}", self.exchange)?;
+        writeln!(f, "Hash:      0x{}", hex::encode(self.order_hash))?;
+        match self.recovered_signer {
+            Some(signer) => writeln!(f, "Recovered: {:?}", signer)?,
+            None => writeln!(f, "Recovered: -")?,
+        }
+        match self.implied_price {
+            Some(price) => writeln!(f, "Price:     {}", price)?,
+            None => writeln!(f, "Price:     -")?,
+        }
+        if self.errors.is_empty() {
+            write!(f, "Result:    VALID")
+        } else {
+            write!(f, "Result:    INVALID")?;
+            for error in &self.errors {
+                write!(f, "\n  - {}", error)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Verify a signed order as the exchange would
+///
+/// Returns `Err` only if the order can't be parsed at all; every failed check
+/// is collected into `OrderVerification::errors`.
+pub fn verify_signed_order(
+    order: &SignedOrder,
+    neg_risk: bool,
+    tick_size: Decimal,
+) -> Result<OrderVerification> {
+    let exchange = Exchange::from_neg_risk(neg_risk);
+    let data = OrderData::from_signed_order(order)?;
+    let order_hash = H256::from(data.signing_hash(exchange));
+    let mut errors = Vec::new();
+
+    // Signature must recover to the declared signer
+    let recovered_signer = match Signature::from_str(&order.signature) {
+        Ok(signature) => match signature.recover(order_hash) {
+            Ok(addr) => Some(addr),
+            Err(e) => {
+                errors.push(format!("Signature recovery failed: {}", e));
+                None
+            }
+        },
+        Err(e) => {
+            errors.push(format!("Malformed signature: {}", e));
+            None
+        }
+    };
+    if let Some(recovered) = recovered_signer {
+        if recovered != data.signer {
+            errors.push(format!(
+                "Recovered signer {:?} does not match order signer {:?} (wrong exchange or tampered order?)",
+                recovered, data.signer
+            ));
+        }
+    }
+
+    // EOA orders are signed by the maker itself; proxy/safe orders by its owner
+    match data.signature_type {
+        SIGNATURE_TYPE_EOA => {
+            if data.maker != data.signer {
+                errors.push(format!(
+                    "signatureType 0 (EOA) requires maker == signer, got maker {:?} signer {:?}",
+                    data.maker, data.signer
+                ));
+            }
+        }
+        SIGNATURE_TYPE_POLY_PROXY | SIGNATURE_TYPE_POLY_GNOSIS_SAFE => {
+            if data.maker == data.signer {
+                errors.push(format!(
+                    "signatureType {} expects a proxy/safe maker distinct from signer",
+                    data.signature_type
+                ));
+            }
+        }
+        other => errors.push(format!("Unknown signatureType {}", other)),
+    }
+
+    // Amounts must imply a price inside (0, 1) on the tick grid
+    let implied_price = implied_price(order);
+    match implied_price {
+        Some(price) => {
+            if price < tick_size || price > Decimal::ONE - tick_size {
+                errors.push(format!("Implied price {} outside [{}, {}]", price, tick_size, Decimal::ONE - tick_size));
+            }
+            let on_grid = (price / tick_size).round() * tick_size;
+            if (price - on_grid).abs() > tick_size / Decimal::from(100) {
+                errors.push(format!("Implied price {} is off the {} tick grid", price, tick_size));
+            }
+        }
+        None => errors.push("makerAmount/takerAmount must both be positive".to_string()),
+    }
+
+    // Non-zero expiration must clear the security threshold
+    if !data.expiration.is_zero() {
+        let expiration = data.expiration.low_u64() as i64;
+        let min_allowed = chrono::Utc::now().timestamp() + EXPIRATION_SECURITY_THRESHOLD_SECS;
+        if expiration <= min_allowed {
+            errors.push(format!(
+                "Expiration {} is past or within {}s of now",
+                expiration, EXPIRATION_SECURITY_THRESHOLD_SECS
+            ));
+        }
+    }
+
+    Ok(OrderVerification {
+        exchange,
+        order_hash,
+        recovered_signer,
+        implied_price,
+        errors,
+    })
+}
+
+/// Verify a full API order, including order type vs expiration
+pub fn verify_order(order: &Order, neg_risk: bool, tick_size: Decimal) -> Result<OrderVerification> {
+    let mut verification = verify_signed_order(&order.order, neg_risk, tick_size)?;
+
+    let has_expiration = order.order.expiration != "0";
+    match order.order_type {
+        OrderType::Gtd if !has_expiration => {
+            verification.errors.push("GTD order has no expiration".to_string());
+        }
+        OrderType::Gtc | OrderType::Fok | OrderType::Fak if has_expiration => {
+            verification.errors.push(format!(
+                "{:?} order should not carry an expiration",
+                order.order_type
+            ));
+        }
+        _ => {}
+    }
+
+    Ok(verification)
+}
+
+/// USDC per share implied by the maker/taker amounts
+pub fn implied_price(order: &SignedOrder) -> Option<Decimal> {
+    let maker = Decimal::from_str(&order.maker_amount).ok()?;
+    let taker = Decimal::from_str(&order.taker_amount).ok()?;
+    if maker <= Decimal::ZERO || taker <= Decimal::ZERO {
+        return None;
+    }
+    // BUY pays USDC (maker) for shares (taker); SELL is the reverse
+    Some(match order.side {
+        Side::Buy => maker / taker,
+        Side::Sell => taker / maker,
+    })
+}
+
+/// Load an order from a JSON file (as posted to /order)
+pub fn load_order(path: &str) -> Result<Order> {
+    let content = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))?;
+    serde_json::from_str(&content).context("Failed to parse order JSON")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signer::OrderSigner;
+    use crate::test_support::{TEST_ADDRESS, TEST_KEY};
+    use rust_decimal_macros::dec;
+
+    const TEST_TOKEN: &str = "21742633143463906290569050155826241533067272736897614950488156847949938836455";
+
+    #[tokio::test]
+    async fn test_verify_own_order() {
+        let signer = OrderSigner::new(TEST_KEY, TEST_ADDRESS).unwrap();
+        let order = signer
+            .create_order(TEST_TOKEN, dec!(0.48), dec!(100), Side::Buy, dec!(0.01), true)
+            .await
+            .unwrap();
+
+        let result = verify_order(&order, true, dec!(0.01)).unwrap();
+        assert!(result.is_valid(), "{}", result);
+        assert_eq!(result.implied_price, Some(dec!(0.48)));
+        assert_eq!(order.order.expiration, "0");
+
+        // Same order checked against the wrong exchange recovers a different signer
+        let result = verify_order(&order, false, dec!(0.01)).unwrap();
+        assert!(!result.is_valid());
+    }
+
+    #[tokio::test]
+    async fn test_verify_flags_gtc_with_expiration() {
+        let signer = OrderSigner::new(TEST_KEY, TEST_ADDRESS).unwrap();
+        let mut order = signer
+            .create_order(TEST_TOKEN, dec!(0.48), dec!(100), Side::Buy, dec!(0.01), true)
+            .await
+            .unwrap();
+
+        order.order.expiration = (chrono::Utc::now().timestamp() + 3600).to_string();
+        let result = verify_order(&order, true, dec!(0.01)).unwrap();
+        assert!(result.errors.iter().any(|e| e.contains("Gtc order should not carry an expiration")), "{}", result);
+    }
+
+    #[tokio::test]
+    async fn test_verify_detects_tampering() {
+        let signer = OrderSigner::new(TEST_KEY, TEST_ADDRESS).unwrap();
+        let mut order = signer
+            .create_order(TEST_TOKEN, dec!(0.48), dec!(100), Side::Buy, dec!(0.01), true)
+            .await
+            .unwrap();
+
+        order.order.maker_amount = "47000000".to_string();
+        let result = verify_order(&order, true, dec!(0.01)).unwrap();
+        assert!(!result.is_valid());
+    }
+}
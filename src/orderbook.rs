@@ -1,7 +1,89 @@
+use anyhow::Result;
 use parking_lot::RwLock;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
-use std::collections::BTreeMap;
+use sha1::{Digest, Sha1};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::clob::ClobClient;
+use crate::supervisor::Supervisor;
+use crate::types::Side;
+
+/// One level change from a `price_change` message (size 0 removes the level)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LevelChange {
+    pub side: Side, // BUY = bid, SELL = ask
+    pub price: Decimal,
+    pub size: Decimal,
+}
+
+/// Exchange metadata carried by a book message
+#[derive(Debug, Clone, Default)]
+pub struct BookMeta {
+    pub market: Option<String>,
+    pub hash: Option<String>,
+    pub timestamp_ms: Option<i64>,
+    pub tick_size: Option<Decimal>,
+}
+
+/// Why a local book stopped being trustworthy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntegrityIssue {
+    /// Best bid above best ask
+    Crossed { bid: Decimal, ask: Decimal },
+    /// Best bid equal to best ask
+    Locked { price: Decimal },
+    /// Our reconstructed book doesn't hash to the exchange's value
+    HashMismatch { expected: String, computed: String },
+    /// Messages were missed (reconnect, delta before snapshot, ...)
+    SequenceGap(String),
+}
+
+impl fmt::Display for IntegrityIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegrityIssue::Crossed { bid, ask } => write!(f, "crossed book (bid {} > ask {})", bid, ask),
+            IntegrityIssue::Locked { price } => write!(f, "locked book (bid = ask = {})", price),
+            IntegrityIssue::HashMismatch { expected, computed } => {
+                write!(f, "hash mismatch (exchange {}, local {})", expected, computed)
+            }
+            IntegrityIssue::SequenceGap(reason) => write!(f, "sequence gap: {}", reason),
+        }
+    }
+}
+
+/// Result of applying a book message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApplyOutcome {
+    /// Applied and the book passes all checks
+    Applied,
+    /// Older than the book's state, dropped
+    Stale,
+    /// Applied, but the book is untrusted until the next good snapshot
+    Untrusted(IntegrityIssue),
+}
+
+/// Whether a book can be traded on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BookStatus {
+    Missing,
+    Trusted,
+    Unreliable(IntegrityIssue),
+}
+
+/// Combined spread, or why it can't be given
+#[derive(Debug, Clone)]
+pub enum SpreadState {
+    Ready(CombinedSpread),
+    /// One of the books failed an integrity check; don't trade on it
+    Unreliable { asset_id: String, issue: IntegrityIssue },
+    /// No book yet, or a side has no asks
+    Unavailable,
+}
 
 /// Local orderbook mirror for fast access
 #[derive(Debug, Clone)]
@@ -10,6 +92,13 @@ pub struct LocalOrderbook {
     pub bids: BTreeMap<Decimal, Decimal>, // price -> size (sorted desc)
     pub asks: BTreeMap<Decimal, Decimal>, // price -> size (sorted asc)
     pub last_update: std::time::Instant,
+    pub market: Option<String>,     // Condition ID (needed for the hash)
+    pub hash: Option<String>,       // Exchange book hash from the last message
+    pub timestamp_ms: Option<i64>,  // Exchange timestamp of the last message
+    pub has_snapshot: bool,         // Deltas before the first snapshot are a gap
+    pub verify_hash: bool,          // Off if our encoding doesn't match the exchange's
+    pub untrusted: Option<IntegrityIssue>,
+    pub tick_size: Option<Decimal>, // Current tick (changes near the extremes)
 }
 
 impl LocalOrderbook {
@@ -19,6 +108,77 @@ impl LocalOrderbook {
             bids: BTreeMap::new(),
             asks: BTreeMap::new(),
             last_update: std::time::Instant::now(),
+            market: None,
+            hash: None,
+            timestamp_ms: None,
+            has_snapshot: false,
+            verify_hash: true,
+            untrusted: None,
+            tick_size: None,
+        }
+    }
+
+    pub fn is_trusted(&self) -> bool {
+        self.untrusted.is_none()
+    }
+
+    /// Book hash in the exchange's scheme
+    ///
+    /// SHA-1 of the compact JSON summary with an empty `hash`, keys in
+    /// py-clob-client's `OrderBookSummary` order (market, asset_id, timestamp,
+    /// bids, asks, hash) as its `generate_orderbook_summary_hash` builds it.
+    /// Bids and asks are ordered as the REST API returns them (best price
+    /// last). Needs the market and timestamp of the message.
+    pub fn compute_hash(&self) -> Option<String> {
+        let market = self.market.as_deref()?;
+        let timestamp = self.timestamp_ms?;
+
+        let level = |(p, s): (&Decimal, &Decimal)| serde_json::json!({"price": p.to_string(), "size": s.to_string()});
+        let bids: Vec<_> = self.bids.iter().map(level).collect();
+        let asks: Vec<_> = self.asks.iter().rev().map(level).collect();
+
+        // Built by hand: field order is part of the hash
+        let summary = format!(
+            r#"{{"market":{},"asset_id":{},"timestamp":"{}","bids":{},"asks":{},"hash":""}}"#,
+            serde_json::Value::from(market),
+            serde_json::Value::from(self.asset_id.as_str()),
+            timestamp,
+            serde_json::Value::from(bids),
+            serde_json::Value::from(asks),
+        );
+        Some(format!("{:x}", Sha1::digest(summary.as_bytes())))
+    }
+
+    /// Structural and hash checks against the message that produced this state
+    pub fn check_integrity(&self, message_hash: Option<&str>) -> Option<IntegrityIssue> {
+        if let (Some(bid), Some(ask)) = (self.best_bid(), self.best_ask()) {
+            if bid > ask {
+                return Some(IntegrityIssue::Crossed { bid, ask });
+            }
+            if bid == ask {
+                return Some(IntegrityIssue::Locked { price: bid });
+            }
+        }
+
+        if self.verify_hash {
+            if let (Some(expected), Some(computed)) = (message_hash, self.compute_hash()) {
+                if !expected.eq_ignore_ascii_case(&computed) {
+                    return Some(IntegrityIssue::HashMismatch { expected: expected.to_string(), computed });
+                }
+            }
+        }
+        None
+    }
+
+    /// True if a message with this exchange timestamp is older than our state
+    pub fn is_older(&self, timestamp_ms: Option<i64>) -> bool {
+        matches!((self.timestamp_ms, timestamp_ms), (Some(ours), Some(theirs)) if theirs < ours)
+    }
+
+    /// Apply level deltas from a `price_change` message
+    pub fn apply_changes(&mut self, changes: &[LevelChange]) {
+        for change in changes {
+            self.update_level(change.side == Side::Buy, change.price, change.size);
         }
     }
 
@@ -112,6 +272,12 @@ impl LocalOrderbook {
 }
 
 /// Thread-safe orderbook manager for multiple tokens
+///
+/// Every message is checked (crossed/locked, exchange hash, gaps). A book
+/// that fails stays in place but is flagged untrusted: the price accessors
+/// return `None` for it until a clean snapshot (WS or [`resync`]) replaces it.
+///
+/// [`resync`]: OrderbookManager::resync
 pub struct OrderbookManager {
     books: RwLock<std::collections::HashMap<String, LocalOrderbook>>,
 }
@@ -139,29 +305,283 @@ impl OrderbookManager {
             .clone()
     }
 
+    /// Drop a book (market retired)
+    pub fn remove(&self, asset_id: &str) {
+        self.books.write().remove(asset_id);
+    }
+
     /// Update orderbook from WebSocket
     pub fn update(&self, asset_id: &str, bids: &[(String, String)], asks: &[(String, String)]) {
+        self.apply_snapshot(asset_id, bids, asks, BookMeta::default());
+    }
+
+    /// Replace a book from a full snapshot
+    ///
+    /// A snapshot that passes the checks clears any earlier untrusted state.
+    pub fn apply_snapshot(
+        &self,
+        asset_id: &str,
+        bids: &[(String, String)],
+        asks: &[(String, String)],
+        meta: BookMeta,
+    ) -> ApplyOutcome {
         let mut books = self.books.write();
         let book = books
             .entry(asset_id.to_string())
             .or_insert_with(|| LocalOrderbook::new(asset_id));
+
+        if book.has_snapshot && book.is_older(meta.timestamp_ms) {
+            return ApplyOutcome::Stale;
+        }
+
         book.update_from_snapshot(bids, asks);
+        book.has_snapshot = true;
+        if meta.market.is_some() {
+            book.market = meta.market;
+        }
+        if meta.tick_size.is_some() {
+            book.tick_size = meta.tick_size;
+        }
+        book.timestamp_ms = meta.timestamp_ms.or(book.timestamp_ms);
+
+        let issue = book.check_integrity(meta.hash.as_deref());
+        book.hash = meta.hash;
+        if issue.is_none() && book.untrusted.is_some() {
+            info!("Book {} trusted again after snapshot", short_id(asset_id));
+        }
+        set_untrusted(book, issue)
+    }
+
+    /// Apply `price_change` deltas to a book
+    ///
+    /// Deltas older than the book's last message are dropped (a newer
+    /// snapshot already includes them). Deltas are still applied to an
+    /// untrusted book, but only a snapshot can make it trusted again.
+    pub fn apply_price_changes(&self, asset_id: &str, changes: &[LevelChange], meta: BookMeta) -> ApplyOutcome {
+        let mut books = self.books.write();
+        let book = books
+            .entry(asset_id.to_string())
+            .or_insert_with(|| LocalOrderbook::new(asset_id));
+
+        if book.is_older(meta.timestamp_ms) {
+            return ApplyOutcome::Stale;
+        }
+        book.apply_changes(changes);
+        if meta.market.is_some() {
+            book.market = meta.market;
+        }
+        book.timestamp_ms = meta.timestamp_ms.or(book.timestamp_ms);
+
+        let issue = if !book.has_snapshot {
+            Some(IntegrityIssue::SequenceGap("delta before snapshot".to_string()))
+        } else {
+            book.check_integrity(meta.hash.as_deref())
+        };
+        if meta.hash.is_some() {
+            book.hash = meta.hash;
+        }
+
+        match (&book.untrusted, issue) {
+            (Some(existing), None) => ApplyOutcome::Untrusted(existing.clone()),
+            (_, issue) => set_untrusted(book, issue),
+        }
+    }
+
+    /// Flag a book untrusted until the next good snapshot
+    pub fn mark_untrusted(&self, asset_id: &str, issue: IntegrityIssue) {
+        if let Some(book) = self.books.write().get_mut(asset_id) {
+            set_untrusted(book, Some(issue));
+        }
+    }
+
+    /// Flag books untrusted after a feed gap (disconnect, missed messages)
+    pub fn mark_gap(&self, asset_ids: &[String], reason: &str) {
+        let mut books = self.books.write();
+        for asset_id in asset_ids {
+            if let Some(book) = books.get_mut(asset_id) {
+                set_untrusted(book, Some(IntegrityIssue::SequenceGap(reason.to_string())));
+            }
+        }
+    }
+
+    /// Trust state of a book
+    pub fn book_status(&self, asset_id: &str) -> BookStatus {
+        match self.books.read().get(asset_id) {
+            None => BookStatus::Missing,
+            Some(book) => match &book.untrusted {
+                None => BookStatus::Trusted,
+                Some(issue) => BookStatus::Unreliable(issue.clone()),
+            },
+        }
+    }
+
+    /// Assets whose books are currently untrusted
+    pub fn untrusted_assets(&self) -> Vec<String> {
+        self.books
+            .read()
+            .values()
+            .filter(|b| !b.is_trusted())
+            .map(|b| b.asset_id.clone())
+            .collect()
+    }
+
+    /// Replace books with REST snapshots
+    ///
+    /// The REST book carries its own hash, so it also checks our hash
+    /// encoding: if a fresh snapshot doesn't verify, hash checks are turned
+    /// off for that asset (structural checks still run) rather than looping
+    /// on resyncs. Returns how many books are trusted afterwards.
+    pub async fn resync(&self, clob: &ClobClient, asset_ids: &[&str]) -> Result<usize> {
+        if asset_ids.is_empty() {
+            return Ok(0);
+        }
+        let snapshots = clob.get_orderbooks(asset_ids).await?;
+
+        let mut trusted = 0;
+        for snapshot in snapshots {
+            let levels = |levels: &[crate::types::PriceLevel]| -> Vec<(String, String)> {
+                levels.iter().map(|l| (l.price.clone(), l.size.clone())).collect()
+            };
+            let (bids, asks) = (levels(&snapshot.bids), levels(&snapshot.asks));
+            let meta = BookMeta {
+                market: Some(snapshot.market.clone()).filter(|m| !m.is_empty()),
+                hash: Some(snapshot.hash.clone()).filter(|h| !h.is_empty()),
+                timestamp_ms: snapshot.timestamp.as_deref().and_then(|t| t.parse().ok()),
+                tick_size: snapshot.tick_size.as_deref().and_then(|t| t.parse().ok()),
+            };
+
+            let mut outcome = self.apply_snapshot(&snapshot.asset_id, &bids, &asks, meta.clone());
+            if let ApplyOutcome::Untrusted(IntegrityIssue::HashMismatch { .. }) = outcome {
+                warn!(
+                    "REST book for {} fails local hash check; disabling hash verification for it",
+                    short_id(&snapshot.asset_id)
+                );
+                if let Some(book) = self.books.write().get_mut(&snapshot.asset_id) {
+                    book.verify_hash = false;
+                    book.has_snapshot = false; // allow re-applying the same snapshot
+                }
+                outcome = self.apply_snapshot(&snapshot.asset_id, &bids, &asks, meta);
+            }
+
+            match outcome {
+                ApplyOutcome::Applied => trusted += 1,
+                ApplyOutcome::Stale => {
+                    // WS already moved past this snapshot; trust whatever it says
+                    if self.book_status(&snapshot.asset_id) == BookStatus::Trusted {
+                        trusted += 1;
+                    }
+                }
+                ApplyOutcome::Untrusted(issue) => {
+                    warn!("Resynced book {} still unreliable: {}", short_id(&snapshot.asset_id), issue)
+                }
+            }
+        }
+        Ok(trusted)
+    }
+
+    /// Background task that resyncs untrusted books from REST
+    ///
+    /// Retries each asset at most once per `min_interval`; runs on
+    /// `supervisor` and stops when it shuts down.
+    pub fn spawn_resync_task(self: Arc<Self>, clob: Arc<ClobClient>, min_interval: Duration, supervisor: &Supervisor) {
+        supervisor.spawn("Orderbook resync", async move {
+            let mut last_attempt: HashMap<String, Instant> = HashMap::new();
+            loop {
+                tokio::time::sleep(Duration::from_millis(250)).await;
+
+                let due: Vec<String> = self
+                    .untrusted_assets()
+                    .into_iter()
+                    .filter(|a| last_attempt.get(a).is_none_or(|t| t.elapsed() >= min_interval))
+                    .collect();
+                if due.is_empty() {
+                    continue;
+                }
+
+                for asset_id in &due {
+                    last_attempt.insert(asset_id.clone(), Instant::now());
+                }
+                let ids: Vec<&str> = due.iter().map(|s| s.as_str()).collect();
+                match self.resync(&clob, &ids).await {
+                    Ok(trusted) => info!("Resynced {}/{} untrusted books from REST", trusted, ids.len()),
+                    Err(e) => warn!("Orderbook resync failed: {}", e),
+                }
+            }
+        });
+    }
+
+    /// Record a token's new tick size, returning the previous one
+    pub fn set_tick_size(&self, asset_id: &str, tick_size: Decimal) -> Option<Decimal> {
+        let mut books = self.books.write();
+        let book = books
+            .entry(asset_id.to_string())
+            .or_insert_with(|| LocalOrderbook::new(asset_id));
+        book.tick_size.replace(tick_size)
+    }
+
+    /// Current tick size for a token, if the exchange has told us one
+    pub fn tick_size(&self, asset_id: &str) -> Option<Decimal> {
+        self.books.read().get(asset_id)?.tick_size
+    }
+
+    /// Tick size to quote a market on
+    ///
+    /// Live value from the feed when known (either token), else the one
+    /// captured at discovery.
+    pub fn market_tick_size(&self, market: &crate::types::UpDownMarket) -> Decimal {
+        let books = self.books.read();
+        [&market.up_token_id, &market.down_token_id]
+            .into_iter()
+            .find_map(|id| books.get(id)?.tick_size)
+            .unwrap_or(market.tick_size)
+    }
+
+    /// Best bid/ask for a token (raw, even if the book is untrusted)
+    pub fn best_bid_ask(&self, asset_id: &str) -> Option<(Option<Decimal>, Option<Decimal>)> {
+        let books = self.books.read();
+        let book = books.get(asset_id)?;
+        Some((book.best_bid(), book.best_ask()))
+    }
+
+    /// Mid price for a token, if both sides have liquidity and the book is trusted
+    pub fn mid_price(&self, asset_id: &str) -> Option<Decimal> {
+        self.books.read().get(asset_id).filter(|b| b.is_trusted())?.mid_price()
     }
 
     /// Get combined spread for two tokens (Up + Down)
+    ///
+    /// `None` if either book is missing or untrusted; use [`spread_state`]
+    /// to tell the two apart.
+    ///
+    /// [`spread_state`]: OrderbookManager::spread_state
     pub fn get_combined_spread(&self, up_token: &str, down_token: &str) -> Option<CombinedSpread> {
+        match self.spread_state(up_token, down_token) {
+            SpreadState::Ready(spread) => Some(spread),
+            _ => None,
+        }
+    }
+
+    /// Combined spread, or whether the books are unreliable
+    pub fn spread_state(&self, up_token: &str, down_token: &str) -> SpreadState {
         let books = self.books.read();
 
-        let up_book = books.get(up_token)?;
-        let down_book = books.get(down_token)?;
+        let (Some(up_book), Some(down_book)) = (books.get(up_token), books.get(down_token)) else {
+            return SpreadState::Unavailable;
+        };
+        for book in [up_book, down_book] {
+            if let Some(issue) = &book.untrusted {
+                return SpreadState::Unreliable { asset_id: book.asset_id.clone(), issue: issue.clone() };
+            }
+        }
 
-        let up_ask = up_book.best_ask()?;
-        let down_ask = down_book.best_ask()?;
+        let (Some(up_ask), Some(down_ask)) = (up_book.best_ask(), down_book.best_ask()) else {
+            return SpreadState::Unavailable;
+        };
 
         let combined_cost = up_ask + down_ask;
         let spread_pct = (dec!(1) - combined_cost) / combined_cost * dec!(100);
 
-        Some(CombinedSpread {
+        SpreadState::Ready(CombinedSpread {
             up_best_ask: up_ask,
             down_best_ask: down_ask,
             combined_cost,
@@ -170,12 +590,12 @@ impl OrderbookManager {
         })
     }
 
-    /// Get orderbook depth for both UP and DOWN tokens
+    /// Get orderbook depth for both UP and DOWN tokens (trusted books only)
     pub fn get_depth(&self, up_token: &str, down_token: &str, levels: usize) -> Option<OrderbookDepth> {
         let books = self.books.read();
 
-        let up_book = books.get(up_token)?;
-        let down_book = books.get(down_token)?;
+        let up_book = books.get(up_token).filter(|b| b.is_trusted())?;
+        let down_book = books.get(down_token).filter(|b| b.is_trusted())?;
 
         Some(OrderbookDepth {
             up_asks: up_book.top_asks(levels),
@@ -186,7 +606,8 @@ impl OrderbookManager {
     }
 
     /// Get orderbooks in API format (for strategy compatibility)
-    /// Returns (up_orderbook, down_orderbook) converted to Orderbook type
+    /// Returns (up_orderbook, down_orderbook) converted to Orderbook type,
+    /// or `None` if either is missing or untrusted
     pub fn get_orderbooks(
         &self,
         up_token: &str,
@@ -194,8 +615,8 @@ impl OrderbookManager {
     ) -> Option<(crate::types::Orderbook, crate::types::Orderbook)> {
         let books = self.books.read();
 
-        let up_book = books.get(up_token)?;
-        let down_book = books.get(down_token)?;
+        let up_book = books.get(up_token).filter(|b| b.is_trusted())?;
+        let down_book = books.get(down_token).filter(|b| b.is_trusted())?;
 
         // Convert LocalOrderbook to Orderbook format
         let up_orderbook = local_to_orderbook(up_book);
@@ -205,6 +626,27 @@ impl OrderbookManager {
     }
 }
 
+/// Record a check result on a book, logging when it turns untrusted
+fn set_untrusted(book: &mut LocalOrderbook, issue: Option<IntegrityIssue>) -> ApplyOutcome {
+    match issue {
+        None => {
+            book.untrusted = None;
+            ApplyOutcome::Applied
+        }
+        Some(issue) => {
+            if book.untrusted.is_none() {
+                warn!("Book {} unreliable: {}", short_id(&book.asset_id), issue);
+            }
+            book.untrusted = Some(issue.clone());
+            ApplyOutcome::Untrusted(issue)
+        }
+    }
+}
+
+fn short_id(asset_id: &str) -> &str {
+    &asset_id[..asset_id.len().min(16)]
+}
+
 /// Convert LocalOrderbook to API Orderbook format
 fn local_to_orderbook(local: &LocalOrderbook) -> crate::types::Orderbook {
     use crate::types::PriceLevel;
@@ -227,14 +669,14 @@ fn local_to_orderbook(local: &LocalOrderbook) -> crate::types::Orderbook {
         .collect();
 
     crate::types::Orderbook {
-        market: String::new(),
+        market: local.market.clone().unwrap_or_default(),
         asset_id: local.asset_id.clone(),
         bids,
         asks,
-        hash: String::new(),
-        timestamp: None,
+        hash: local.hash.clone().unwrap_or_default(),
+        timestamp: local.timestamp_ms.map(|t| t.to_string()),
         min_order_size: None,
-        tick_size: None,
+        tick_size: local.tick_size.map(|t| t.to_string()),
     }
 }
 
@@ -314,3 +756,175 @@ impl CombinedSpread {
         self.is_profitable && self.spread_pct >= min_spread_pct
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn levels(items: &[(&str, &str)]) -> Vec<(String, String)> {
+        items.iter().map(|(p, s)| (p.to_string(), s.to_string())).collect()
+    }
+
+    fn meta(hash: Option<&str>, timestamp_ms: i64) -> BookMeta {
+        BookMeta {
+            market: Some("0xabc".to_string()),
+            hash: hash.map(String::from),
+            timestamp_ms: Some(timestamp_ms),
+            ..Default::default()
+        }
+    }
+
+    /// Hash the exchange would send for a book state
+    fn hash_of(bids: &[(&str, &str)], asks: &[(&str, &str)], timestamp_ms: i64) -> String {
+        let manager = OrderbookManager::new();
+        manager.apply_snapshot("up", &levels(bids), &levels(asks), meta(None, timestamp_ms));
+        manager.get_or_create("up").compute_hash().unwrap()
+    }
+
+    /// A hand-written `book` message in the market channel's shape, not a
+    /// capture: its hash is not server-issued. The hash literal comes from
+    /// py-clob-client's reference hashing run in Python over this message
+    /// (`OrderBookSummary` dataclass, `json.dumps(asdict(..), separators=(",", ":"))`,
+    /// SHA-1 with `hash` blanked), so the test pins our key order and number
+    /// formatting to that implementation rather than to this crate.
+    const BOOK_FIXTURE: &str = r#"{"event_type":"book","asset_id":"71321045679252212594626385532706912750332728571942532289631379312455583992563","market":"0x5f65177b394277fd294cd75650044e32ba009a95022d88a0c1d565897d72f8f1","bids":[{"price":"0.01","size":"2100"},{"price":"0.45","size":"310.5"},{"price":"0.47","size":"52"},{"price":"0.48","size":"1250"}],"asks":[{"price":"0.99","size":"1800"},{"price":"0.55","size":"75.25"},{"price":"0.52","size":"400"},{"price":"0.51","size":"19"}],"timestamp":"1736949612345","hash":"07ee61e6a13ff8775067a37b6584e5903bbd38c1"}"#;
+
+    #[test]
+    fn test_compute_hash_matches_book_fixture() {
+        let msg: serde_json::Value = serde_json::from_str(BOOK_FIXTURE).unwrap();
+        let side = |key: &str| -> Vec<(String, String)> {
+            msg[key]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|l| (l["price"].as_str().unwrap().to_string(), l["size"].as_str().unwrap().to_string()))
+                .collect()
+        };
+        let asset_id = msg["asset_id"].as_str().unwrap();
+
+        let manager = OrderbookManager::new();
+        let outcome = manager.apply_snapshot(
+            asset_id,
+            &side("bids"),
+            &side("asks"),
+            BookMeta {
+                market: Some(msg["market"].as_str().unwrap().to_string()),
+                hash: Some(msg["hash"].as_str().unwrap().to_string()),
+                timestamp_ms: Some(1736949612345),
+                ..Default::default()
+            },
+        );
+
+        assert!(matches!(outcome, ApplyOutcome::Applied));
+        assert_eq!(
+            manager.get_or_create(asset_id).compute_hash().as_deref(),
+            Some("07ee61e6a13ff8775067a37b6584e5903bbd38c1")
+        );
+    }
+
+    #[test]
+    fn test_price_changes_keep_depth_current() {
+        let manager = OrderbookManager::new();
+        manager.apply_snapshot(
+            "up",
+            &levels(&[("0.48", "100"), ("0.47", "50")]),
+            &levels(&[("0.50", "80"), ("0.51", "40")]),
+            BookMeta { hash: Some("h1".to_string()), timestamp_ms: Some(1000), ..Default::default() },
+        );
+
+        // Best ask lifted, new bid level, 0.47 bid pulled
+        let outcome = manager.apply_price_changes(
+            "up",
+            &[
+                LevelChange { side: Side::Sell, price: dec!(0.50), size: dec!(0) },
+                LevelChange { side: Side::Buy, price: dec!(0.49), size: dec!(25) },
+                LevelChange { side: Side::Buy, price: dec!(0.47), size: dec!(0) },
+            ],
+            BookMeta { hash: Some("h2".to_string()), timestamp_ms: Some(1001), ..Default::default() },
+        );
+        assert_eq!(outcome, ApplyOutcome::Applied);
+
+        let book = manager.get_or_create("up");
+        assert_eq!(book.top_bids(5), vec![(dec!(0.49), dec!(25)), (dec!(0.48), dec!(100))]);
+        assert_eq!(book.top_asks(5), vec![(dec!(0.51), dec!(40))]);
+        assert_eq!(book.hash.as_deref(), Some("h2"));
+
+        // Late delta from before the last message is ignored
+        let outcome = manager.apply_price_changes(
+            "up",
+            &[LevelChange { side: Side::Sell, price: dec!(0.50), size: dec!(80) }],
+            BookMeta { timestamp_ms: Some(999), ..Default::default() },
+        );
+        assert_eq!(outcome, ApplyOutcome::Stale);
+        assert_eq!(manager.best_bid_ask("up"), Some((Some(dec!(0.49)), Some(dec!(0.51)))));
+    }
+
+    #[test]
+    fn test_hash_chain_detects_missed_delta() {
+        let manager = OrderbookManager::new();
+        let bids = [("0.47", "50"), ("0.48", "100")];
+        let asks = [("0.51", "40"), ("0.50", "80")];
+        let h1 = hash_of(&bids, &asks, 1000);
+        assert_eq!(
+            manager.apply_snapshot("up", &levels(&bids), &levels(&asks), meta(Some(&h1), 1000)),
+            ApplyOutcome::Applied
+        );
+
+        // Delta whose hash matches the resulting book
+        let h2 = hash_of(&[("0.47", "50"), ("0.48", "100"), ("0.49", "10")], &asks, 1001);
+        let bid = LevelChange { side: Side::Buy, price: dec!(0.49), size: dec!(10) };
+        assert_eq!(manager.apply_price_changes("up", &[bid], meta(Some(&h2), 1001)), ApplyOutcome::Applied);
+
+        // A delta was lost: the next hash doesn't match what we hold
+        let h4 = hash_of(&[("0.47", "50"), ("0.48", "100"), ("0.49", "10")], &[("0.51", "40")], 1003);
+        let outcome = manager.apply_price_changes(
+            "up",
+            &[LevelChange { side: Side::Buy, price: dec!(0.47), size: dec!(60) }],
+            meta(Some(&h4), 1003),
+        );
+        assert!(matches!(outcome, ApplyOutcome::Untrusted(IntegrityIssue::HashMismatch { .. })));
+        assert!(manager.get_combined_spread("up", "up").is_none());
+        assert!(matches!(manager.spread_state("up", "up"), SpreadState::Unreliable { .. }));
+
+        // A later delta that happens to check out doesn't restore trust; a snapshot does
+        let h5 = hash_of(&[("0.47", "60"), ("0.48", "100"), ("0.49", "10")], &asks, 1004);
+        manager.apply_price_changes("up", &[], meta(Some(&h5), 1004));
+        assert!(matches!(manager.book_status("up"), BookStatus::Unreliable(_)));
+
+        let h6 = hash_of(&bids, &asks, 1005);
+        manager.apply_snapshot("up", &levels(&bids), &levels(&asks), meta(Some(&h6), 1005));
+        assert_eq!(manager.book_status("up"), BookStatus::Trusted);
+        assert!(manager.get_combined_spread("up", "up").is_some());
+    }
+
+    #[test]
+    fn test_crossed_locked_and_gaps() {
+        let manager = OrderbookManager::new();
+
+        // Delta before any snapshot
+        let outcome = manager.apply_price_changes(
+            "up",
+            &[LevelChange { side: Side::Buy, price: dec!(0.40), size: dec!(5) }],
+            BookMeta::default(),
+        );
+        assert!(matches!(outcome, ApplyOutcome::Untrusted(IntegrityIssue::SequenceGap(_))));
+
+        manager.apply_snapshot("up", &levels(&[("0.48", "10")]), &levels(&[("0.50", "10")]), BookMeta::default());
+        assert_eq!(manager.book_status("up"), BookStatus::Trusted);
+
+        let outcome = manager.apply_price_changes(
+            "up",
+            &[LevelChange { side: Side::Buy, price: dec!(0.50), size: dec!(5) }],
+            BookMeta::default(),
+        );
+        assert_eq!(outcome, ApplyOutcome::Untrusted(IntegrityIssue::Locked { price: dec!(0.50) }));
+
+        let outcome = manager.apply_snapshot("up", &levels(&[("0.52", "10")]), &levels(&[("0.50", "10")]), BookMeta::default());
+        assert_eq!(outcome, ApplyOutcome::Untrusted(IntegrityIssue::Crossed { bid: dec!(0.52), ask: dec!(0.50) }));
+        assert!(manager.mid_price("up").is_none());
+
+        manager.apply_snapshot("up", &levels(&[("0.48", "10")]), &levels(&[("0.50", "10")]), BookMeta::default());
+        manager.mark_gap(&["up".to_string(), "other".to_string()], "reconnect");
+        assert_eq!(manager.untrusted_assets(), vec!["up".to_string()]);
+    }
+}
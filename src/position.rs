@@ -4,11 +4,23 @@ use std::collections::HashMap;
 use tracing::{debug, info};
 
 use crate::types::{Position, Side, TradeFill};
+use crate::user_ws::{OrderEvent, OrderEventType, TradeEvent, TradeStatus};
 
 /// Position manager - tracks fills and calculates P&L
 pub struct PositionManager {
     positions: HashMap<String, Position>, // condition_id -> Position
     order_to_market: HashMap<String, (String, Side)>, // order_id -> (condition_id, side)
+    token_to_market: HashMap<String, (String, bool)>, // token_id -> (condition_id, is_up)
+    open_orders: HashMap<String, OrderEvent>,         // order_id -> latest order event
+    order_sides: HashMap<String, (String, Side)>,     // order_id -> (condition_id, BUY/SELL), kept after fills
+    applied_fills: HashMap<(String, String), TradeFill>, // (trade_id, order_id) -> fill
+}
+
+/// Result of applying a user channel trade message
+#[derive(Debug, Default)]
+pub struct TradeUpdate {
+    pub new_fills: Vec<TradeFill>,
+    pub reverted: Vec<TradeFill>,
 }
 
 impl PositionManager {
@@ -16,9 +28,20 @@ impl PositionManager {
         Self {
             positions: HashMap::new(),
             order_to_market: HashMap::new(),
+            token_to_market: HashMap::new(),
+            open_orders: HashMap::new(),
+            order_sides: HashMap::new(),
+            applied_fills: HashMap::new(),
         }
     }
 
+    /// Register a market's tokens so fills can be attributed without order IDs
+    pub fn register_market(&mut self, condition_id: &str, up_token_id: &str, down_token_id: &str) {
+        self.positions.entry(condition_id.to_string()).or_default();
+        self.token_to_market.insert(up_token_id.to_string(), (condition_id.to_string(), true));
+        self.token_to_market.insert(down_token_id.to_string(), (condition_id.to_string(), false));
+    }
+
     /// Register orders so we can track fills
     pub fn register_orders(
         &mut self,
@@ -109,6 +132,97 @@ impl PositionManager {
         }
     }
 
+    /// Apply a trade from the user channel
+    ///
+    /// Each (trade, order) fill is counted once, on the first non-failed
+    /// status; MINED/CONFIRMED repeats are ignored and FAILED reverts it.
+    pub fn process_trade(&mut self, trade: &TradeEvent, api_key: &str) -> TradeUpdate {
+        let mut update = TradeUpdate::default();
+
+        let fills = trade.our_fills(api_key, |order_id| self.order_sides.get(order_id).map(|(_, side)| *side));
+        for fill in fills {
+            let key = (trade.id.clone(), fill.order_id.clone());
+
+            if trade.status == TradeStatus::Failed {
+                if let Some(applied) = self.applied_fills.remove(&key) {
+                    self.apply_fill(&applied, true);
+                    update.reverted.push(applied);
+                }
+                continue;
+            }
+
+            if self.applied_fills.contains_key(&key) {
+                continue;
+            }
+            if self.apply_fill(&fill, false) {
+                self.applied_fills.insert(key, fill.clone());
+                update.new_fills.push(fill);
+            } else {
+                debug!("Fill for unknown order/token: {} ({})", fill.order_id, fill.asset_id);
+            }
+        }
+
+        update
+    }
+
+    /// Add (or with `revert`, remove) a fill from its market position
+    fn apply_fill(&mut self, fill: &TradeFill, revert: bool) -> bool {
+        let price: Decimal = fill.price.parse().unwrap_or_default();
+        let size: Decimal = fill.size.parse().unwrap_or_default();
+
+        let (condition_id, is_up) = if let Some((cid, side)) = self.order_to_market.get(&fill.order_id) {
+            (cid.clone(), *side == Side::Buy) // Buy marks the UP side here
+        } else if let Some((cid, is_up)) = self.token_to_market.get(&fill.asset_id) {
+            (cid.clone(), *is_up)
+        } else {
+            return false;
+        };
+
+        // Buying adds shares and cost; selling removes them
+        let mut sign = if fill.side == Side::Buy { dec!(1) } else { dec!(-1) };
+        if revert {
+            sign = -sign;
+        }
+
+        let position = self.positions.entry(condition_id).or_default();
+        if is_up {
+            position.up_shares += sign * size;
+            position.up_cost += sign * size * price;
+        } else {
+            position.down_shares += sign * size;
+            position.down_cost += sign * size * price;
+        }
+
+        info!("{} {} fill{}: {} shares @ {}",
+            if is_up { "UP" } else { "DOWN" },
+            if fill.side == Side::Buy { "BUY" } else { "SELL" },
+            if revert { " reverted" } else { "" },
+            size, price);
+        true
+    }
+
+    /// Track order placement/updates/cancellations from the user channel
+    pub fn process_order_event(&mut self, order: &OrderEvent) {
+        self.order_sides.insert(order.id.clone(), (order.market.clone(), order.side));
+        match order.event_type {
+            OrderEventType::Cancellation => {
+                self.open_orders.remove(&order.id);
+            }
+            OrderEventType::Placement | OrderEventType::Update => {
+                if order.size_matched >= order.original_size {
+                    self.open_orders.remove(&order.id);
+                } else {
+                    self.open_orders.insert(order.id.clone(), order.clone());
+                }
+            }
+        }
+    }
+
+    /// Our live orders in a market, as last reported by the user channel
+    pub fn open_orders(&self, condition_id: &str) -> Vec<&OrderEvent> {
+        self.open_orders.values().filter(|o| o.market == condition_id).collect()
+    }
+
     /// Get position for a market
     pub fn get_position(&self, condition_id: &str) -> Option<&Position> {
         self.positions.get(condition_id)
@@ -174,6 +288,10 @@ impl PositionManager {
 
         // Also remove order mappings for this market
         self.order_to_market.retain(|_, (cid, _)| cid != condition_id);
+        self.token_to_market.retain(|_, (cid, _)| cid != condition_id);
+        self.open_orders.retain(|_, o| o.market != condition_id);
+        self.order_sides.retain(|_, (cid, _)| cid != condition_id);
+        self.applied_fills.retain(|_, f| f.market != condition_id);
     }
 }
 
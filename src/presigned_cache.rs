@@ -2,153 +2,240 @@ use anyhow::Result;
 use dashmap::DashMap;
 use futures_util::future::join_all;
 use rust_decimal::Decimal;
-use rust_decimal::prelude::ToPrimitive;
 use rust_decimal_macros::dec;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tracing::{debug, info, warn};
 
+use crate::eip712::Exchange;
+use crate::orderbook::OrderbookManager;
 use crate::signer::OrderSigner;
-use crate::types::{Order, Side};
+use crate::types::{UpDownMarket, Order, Side};
+
+/// Evict orders this long before their on-chain expiration.
+/// The exchange already rejects anything within 60s of expiring; the rest
+/// covers submission latency.
+const EXPIRY_MARGIN_SECS: i64 = 120;
 
 /// Key for looking up pre-signed orders
+///
+/// Price and size are exact (normalized) so a cached order is always the
+/// order the caller asked for.
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct OrderKey {
     pub token_id: String,
     pub side: Side,
-    pub price_cents: u32, // Price in cents (e.g., 45 = $0.45)
-    pub size_bucket: u32, // Size bucket for approximate matching
+    pub price: Decimal,
+    pub size: Decimal,
 }
 
 impl OrderKey {
     pub fn new(token_id: &str, side: Side, price: Decimal, size: Decimal) -> Self {
-        // Round price to cents
-        let price_cents = (price * dec!(100)).to_u32().unwrap_or(50);
-        // Size bucket (round to nearest 10 shares)
-        let size_bucket = (size / dec!(10)).to_u32().unwrap_or(1) * 10;
-
         Self {
             token_id: token_id.to_string(),
             side,
-            price_cents,
-            size_bucket,
+            price: price.normalize(),
+            size: size.normalize(),
         }
     }
+}
+
+#[derive(Clone)]
+pub struct CachedOrder {
+    pub order: Order,
+    pub created_at: Instant,
+    /// Unix timestamp the order expires on-chain
+    pub expires_at: i64,
+    /// Exchange and order nonce it was signed against
+    pub exchange: Exchange,
+    pub nonce: u64,
+}
 
-    pub fn from_params(token_id: &str, side: Side, price_cents: u32, size_bucket: u32) -> Self {
+impl CachedOrder {
+    fn new(order: Order, neg_risk: bool) -> Self {
+        let expires_at = order.order.expiration.parse::<i64>().unwrap_or(0);
+        let nonce = order.order.nonce.parse::<u64>().unwrap_or(u64::MAX);
         Self {
-            token_id: token_id.to_string(),
-            side,
-            price_cents,
-            size_bucket,
+            order,
+            created_at: Instant::now(),
+            expires_at,
+            exchange: Exchange::from_neg_risk(neg_risk),
+            nonce,
         }
     }
+
+    /// Still safe to submit (not within the expiry margin)
+    pub fn is_usable(&self, now: i64) -> bool {
+        // expiration 0 = never expires
+        self.expires_at == 0 || self.expires_at - EXPIRY_MARGIN_SECS > now
+    }
+
+    /// Rough heap + inline footprint of a cache entry
+    fn approx_bytes(&self, key: &OrderKey) -> usize {
+        let o = &self.order.order;
+        std::mem::size_of::<OrderKey>()
+            + std::mem::size_of::<CachedOrder>()
+            + key.token_id.len()
+            + self.order.owner.len()
+            + [
+                &o.salt, &o.maker, &o.signer, &o.taker, &o.token_id, &o.maker_amount,
+                &o.taker_amount, &o.expiration, &o.nonce, &o.fee_rate_bps, &o.signature,
+            ]
+            .iter()
+            .map(|s| s.len())
+            .sum::<usize>()
+    }
+}
+
+/// Per-market bookkeeping
+#[derive(Debug, Clone)]
+struct MarketEntry {
+    token_ids: [String; 2],
+    registered_at: Instant,
+    time_to_warm: Option<Duration>,
+    last_warm: Option<Duration>,
+    centers: [Option<Decimal>; 2],
+    tick_size: Decimal,
 }
 
 /// Pre-signed order cache for instant order submission
 ///
-/// Pre-computes orders at common price points and sizes,
-/// allowing sub-millisecond lookup instead of ~20-40ms signing.
+/// Keeps a band of BUY orders around each token's live mid (from
+/// `OrderbookManager`) for a fixed set of sizes. Re-warming re-centers the
+/// band, drops orders that fell outside it and re-signs anything close to
+/// its on-chain expiration.
 pub struct PresignedCache {
     cache: DashMap<OrderKey, CachedOrder>,
+    markets: DashMap<String, MarketEntry>,
     signer: Arc<OrderSigner>,
-    cache_ttl: Duration,
-    last_warm: Instant,
-}
-
-#[derive(Clone)]
-pub struct CachedOrder {
-    pub order: Order,
-    pub created_at: Instant,
+    sizes: Vec<Decimal>,
+    band_ticks: u32,
+    hits: AtomicU64,
+    misses: AtomicU64,
 }
 
 impl PresignedCache {
-    pub fn new(signer: Arc<OrderSigner>) -> Self {
+    pub fn new(signer: Arc<OrderSigner>, sizes: Vec<Decimal>) -> Self {
         Self {
             cache: DashMap::new(),
+            markets: DashMap::new(),
             signer,
-            cache_ttl: Duration::from_secs(300), // 5 minute TTL
-            last_warm: Instant::now(),
+            sizes: sizes.into_iter().map(|s| s.normalize()).collect(),
+            band_ticks: 15,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
         }
     }
 
-    /// Warm the cache with pre-signed orders for a market
+    /// Number of ticks to cover either side of the mid (default 15)
+    pub fn with_band_ticks(mut self, band_ticks: u32) -> Self {
+        self.band_ticks = band_ticks;
+        self
+    }
+
+    /// Warm (or re-center) the cache for both tokens of a market
     ///
-    /// Generates orders at price points from 35 to 65 cents (step 1 cent)
-    /// for multiple size buckets.
-    pub async fn warm_cache(
-        &self,
-        token_id: &str,
-        tick_size: Decimal,
-        neg_risk: bool,
-        base_size: Decimal,
-    ) -> Result<usize> {
+    /// Only signs orders that are missing or about to expire, so it is cheap
+    /// to call on every book update. Tokens without a two-sided book are skipped.
+    /// Quotes on the live tick size; if it changed since the last warm, every
+    /// order for the market is dropped and re-signed on the new grid.
+    pub async fn warm_market(&self, market: &UpDownMarket, orderbooks: &OrderbookManager) -> Result<usize> {
         let start = Instant::now();
-        info!("Warming pre-signed cache for {}...", token_id);
-
-        // Price range: 35 to 65 cents (step 1 cent = 31 prices)
-        let prices: Vec<u32> = (35..=65).collect();
-
-        // Size buckets: base_size * [0.5, 1.0, 1.5, 2.0]
-        let size_multipliers = vec![dec!(0.5), dec!(1.0), dec!(1.5), dec!(2.0)];
+        let now = chrono::Utc::now().timestamp();
+        let tick_size = orderbooks.market_tick_size(market);
+
+        let previous_tick = {
+            let mut entry = self
+                .markets
+                .entry(market.condition_id.clone())
+                .or_insert_with(|| MarketEntry {
+                    token_ids: [market.up_token_id.clone(), market.down_token_id.clone()],
+                    registered_at: Instant::now(),
+                    time_to_warm: None,
+                    last_warm: None,
+                    centers: [None, None],
+                    tick_size,
+                });
+            std::mem::replace(&mut entry.tick_size, tick_size)
+        };
+        if previous_tick != tick_size {
+            info!("Tick size for {} now {} (was {}), re-signing cache", market.event_slug, tick_size, previous_tick);
+            self.invalidate_market(&market.condition_id);
+        }
 
         let mut futures = Vec::new();
+        let mut fully_centered = true;
+
+        for (idx, token_id) in [&market.up_token_id, &market.down_token_id].into_iter().enumerate() {
+            let Some(mid) = orderbooks.mid_price(token_id) else {
+                fully_centered = false;
+                continue;
+            };
+
+            let prices = self.price_band(mid, tick_size);
+            let (low, high) = match (prices.first(), prices.last()) {
+                (Some(&low), Some(&high)) => (low, high),
+                _ => continue,
+            };
+
+            // Drop orders that fell outside the new band, are about to expire
+            // or were killed by an incrementNonce
+            self.cache.retain(|k, v| {
+                k.token_id != *token_id || (k.price >= low && k.price <= high && self.is_live(v, now))
+            });
+            if let Some(mut entry) = self.markets.get_mut(&market.condition_id) {
+                entry.centers[idx] = Some(mid);
+            }
 
-        for &price_cents in &prices {
-            let price = Decimal::new(price_cents as i64, 2); // Convert cents to decimal
-
-            for &mult in &size_multipliers {
-                let size = base_size * mult;
-                let size_bucket = (size / dec!(10)).to_u32().unwrap_or(1) * 10;
-
-                // Create order key
-                let key = OrderKey::from_params(token_id, Side::Buy, price_cents, size_bucket);
-
-                // Only create if not already cached and valid
-                if let Some(cached) = self.cache.get(&key) {
-                    if cached.created_at.elapsed() < self.cache_ttl {
-                        continue; // Already cached and valid
+            for &price in &prices {
+                for &size in &self.sizes {
+                    let key = OrderKey::new(token_id, Side::Buy, price, size);
+                    if self.cache.contains_key(&key) {
+                        continue;
                     }
-                }
 
-                // Create order future
-                let signer = self.signer.clone();
-                let token = token_id.to_string();
-                let key_clone = key.clone();
-
-                futures.push(async move {
-                    match signer.create_order(
-                        &token, price, size, Side::Buy, tick_size, neg_risk
-                    ).await {
-                        Ok(order) => Some((key_clone, CachedOrder {
-                            order,
-                            created_at: Instant::now(),
-                        })),
-                        Err(e) => {
-                            warn!("Failed to pre-sign order: {}", e);
-                            None
+                    let signer = self.signer.clone();
+                    let token = token_id.clone();
+                    let neg_risk = market.neg_risk;
+                    futures.push(async move {
+                        match signer.create_order(&token, price, size, Side::Buy, tick_size, neg_risk).await {
+                            Ok(order) => Some((key, CachedOrder::new(order, neg_risk))),
+                            Err(e) => {
+                                warn!("Failed to pre-sign order: {}", e);
+                                None
+                            }
                         }
-                    }
-                });
+                    });
+                }
             }
         }
 
+        if futures.is_empty() {
+            return Ok(0);
+        }
+
         let total_orders = futures.len();
-        info!("Pre-signing {} orders in parallel...", total_orders);
+        debug!("Pre-signing {} orders for {}...", total_orders, market.event_slug);
 
-        // Execute all signing in parallel
         let results = join_all(futures).await;
-
-        // Insert into cache
         let mut cached_count = 0;
-        for result in results.into_iter().flatten() {
-            self.cache.insert(result.0, result.1);
+        for (key, cached) in results.into_iter().flatten() {
+            self.cache.insert(key, cached);
             cached_count += 1;
         }
 
         let elapsed = start.elapsed();
+        if let Some(mut entry) = self.markets.get_mut(&market.condition_id) {
+            entry.last_warm = Some(elapsed);
+            if entry.time_to_warm.is_none() && fully_centered && cached_count == total_orders {
+                entry.time_to_warm = Some(entry.registered_at.elapsed());
+            }
+        }
+
         info!(
-            "Cache warmed: {} orders in {:?} ({:.1}ms per order)",
+            "Cache warmed for {}: {} orders in {:?} ({:.1}ms per order)",
+            market.event_slug,
             cached_count,
             elapsed,
             elapsed.as_millis() as f64 / cached_count.max(1) as f64
@@ -157,23 +244,45 @@ impl PresignedCache {
         Ok(cached_count)
     }
 
-    /// Get a pre-signed order from cache (sub-millisecond)
+    /// Prices on the tick grid within `band_ticks` of the mid
+    fn price_band(&self, mid: Decimal, tick_size: Decimal) -> Vec<Decimal> {
+        if tick_size <= Decimal::ZERO {
+            return Vec::new();
+        }
+        let center = (mid / tick_size).round() * tick_size;
+        let band = tick_size * Decimal::from(self.band_ticks);
+        let low = (center - band).max(tick_size);
+        let high = (center + band).min(Decimal::ONE - tick_size);
+
+        let mut prices = Vec::new();
+        let mut price = low;
+        while price <= high {
+            prices.push(price.normalize());
+            price += tick_size;
+        }
+        prices
+    }
+
+    /// Take a pre-signed order from cache (sub-millisecond)
+    ///
+    /// The order is removed: a signed order can only be posted once, and the
+    /// next warm re-signs the slot with a fresh salt.
     pub fn get_order(&self, token_id: &str, side: Side, price: Decimal, size: Decimal) -> Option<Order> {
-        let start = Instant::now();
         let key = OrderKey::new(token_id, side, price, size);
-
-        let result = self.cache.get(&key).and_then(|cached| {
-            // Check TTL
-            if cached.created_at.elapsed() < self.cache_ttl {
-                Some(cached.order.clone())
-            } else {
-                None // Expired
-            }
-        });
-
-        let lookup_time = start.elapsed();
-        debug!("Cache lookup: {:?} (hit: {})", lookup_time, result.is_some());
-
+        let now = chrono::Utc::now().timestamp();
+
+        let result = self
+            .cache
+            .remove(&key)
+            .map(|(_, cached)| cached)
+            .filter(|cached| self.is_live(cached, now))
+            .map(|cached| cached.order);
+
+        if result.is_some() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+        }
         result
     }
 
@@ -189,55 +298,247 @@ impl PresignedCache {
     ) -> Result<Order> {
         // Try cache first (fast path)
         if let Some(order) = self.get_order(token_id, side, price, size) {
-            debug!("Cache HIT for {} @ {}", token_id, price);
+            debug!("Cache HIT for {} @ {} x {}", token_id, price, size);
             return Ok(order);
         }
 
         // Sign fresh (slow path)
-        debug!("Cache MISS for {} @ {}, signing fresh", token_id, price);
+        debug!("Cache MISS for {} @ {} x {}, signing fresh", token_id, price, size);
         self.signer.create_order(token_id, price, size, side, tick_size, neg_risk).await
     }
 
-    /// Clear expired entries
+    /// Drop a market's signed orders but keep tracking it (e.g. tick size changed)
+    pub fn invalidate_market(&self, condition_id: &str) {
+        if let Some(entry) = self.markets.get(condition_id) {
+            let token_ids = entry.token_ids.clone();
+            drop(entry);
+            self.cache.retain(|k, _| !token_ids.contains(&k.token_id));
+        }
+    }
+
+    /// Drop everything cached for a market (e.g. when it closes)
+    pub fn evict_market(&self, condition_id: &str) {
+        if let Some((_, entry)) = self.markets.remove(condition_id) {
+            self.cache.retain(|k, _| !entry.token_ids.contains(&k.token_id));
+        }
+    }
+
+    /// Usable and signed with the signer's current nonce for its exchange
+    ///
+    /// After an `incrementNonce` every order signed before it is dead on-chain,
+    /// so the nonce check drops them without the cache having to be told.
+    fn is_live(&self, cached: &CachedOrder, now: i64) -> bool {
+        cached.is_usable(now) && cached.nonce == self.signer.nonce(cached.exchange)
+    }
+
+    /// Clear orders that are expired, about to expire or signed with a stale nonce
     pub fn cleanup(&self) {
+        let now = chrono::Utc::now().timestamp();
         let before = self.cache.len();
-        self.cache.retain(|_, v| v.created_at.elapsed() < self.cache_ttl);
+        self.cache.retain(|_, v| self.is_live(v, now));
         let after = self.cache.len();
         if before != after {
-            info!("Cache cleanup: removed {} expired orders", before - after);
+            info!("Cache cleanup: removed {} expiring orders", before - after);
         }
     }
 
     /// Get cache stats
     pub fn stats(&self) -> CacheStats {
+        let now = chrono::Utc::now().timestamp();
         let total = self.cache.len();
-        let valid = self.cache.iter()
-            .filter(|e| e.value().created_at.elapsed() < self.cache_ttl)
-            .count();
+        let valid = self.cache.iter().filter(|e| self.is_live(e.value(), now)).count();
+
+        let markets = self
+            .markets
+            .iter()
+            .map(|m| {
+                let (entries, bytes) = self
+                    .cache
+                    .iter()
+                    .filter(|e| m.token_ids.contains(&e.key().token_id))
+                    .fold((0, 0), |(n, b), e| (n + 1, b + e.value().approx_bytes(e.key())));
+                MarketCacheStats {
+                    condition_id: m.key().clone(),
+                    entries,
+                    approx_bytes: bytes,
+                    up_center: m.centers[0],
+                    down_center: m.centers[1],
+                    time_to_warm: m.time_to_warm,
+                    last_warm: m.last_warm,
+                }
+            })
+            .collect();
 
         CacheStats {
             total_entries: total,
             valid_entries: valid,
             expired_entries: total - valid,
-            ttl_seconds: self.cache_ttl.as_secs(),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            markets,
         }
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct MarketCacheStats {
+    pub condition_id: String,
+    pub entries: usize,
+    pub approx_bytes: usize,
+    pub up_center: Option<Decimal>,
+    pub down_center: Option<Decimal>,
+    /// From first warm call until the full band was signed
+    pub time_to_warm: Option<Duration>,
+    pub last_warm: Option<Duration>,
+}
+
 #[derive(Debug)]
 pub struct CacheStats {
     pub total_entries: usize,
     pub valid_entries: usize,
     pub expired_entries: usize,
-    pub ttl_seconds: u64,
+    pub hits: u64,
+    pub misses: u64,
+    pub markets: Vec<MarketCacheStats>,
+}
+
+impl CacheStats {
+    pub fn hit_rate(&self) -> Decimal {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            return Decimal::ZERO;
+        }
+        Decimal::from(self.hits) / Decimal::from(lookups) * dec!(100)
+    }
 }
 
 impl std::fmt::Display for CacheStats {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Cache: {} valid, {} expired (TTL: {}s)",
-            self.valid_entries, self.expired_entries, self.ttl_seconds
-        )
+            "Cache: {} valid, {} expiring | hit rate {:.1}% ({}/{})",
+            self.valid_entries,
+            self.expired_entries,
+            self.hit_rate(),
+            self.hits,
+            self.hits + self.misses
+        )?;
+        for m in &self.markets {
+            write!(
+                f,
+                "\n  {}: {} orders, ~{:.1}KB, warm {:?} (last {:?})",
+                &m.condition_id[..m.condition_id.len().min(12)],
+                m.entries,
+                m.approx_bytes as f64 / 1024.0,
+                m.time_to_warm,
+                m.last_warm
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, TEST_ADDRESS, TEST_KEY};
+
+    fn test_market() -> UpDownMarket {
+        UpDownMarket { neg_risk: true, ..test_support::test_market() }
+    }
+
+    fn book(orderbooks: &OrderbookManager, token: &str, bid: &str, ask: &str) {
+        orderbooks.update(
+            token,
+            &[(bid.to_string(), "100".to_string())],
+            &[(ask.to_string(), "100".to_string())],
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cache_follows_mid_and_keys_exact_size() {
+        let signer = Arc::new(OrderSigner::new(TEST_KEY, TEST_ADDRESS).unwrap());
+        let cache = PresignedCache::new(signer, vec![dec!(10), dec!(25)]).with_band_ticks(2);
+        let market = test_market();
+        let orderbooks = OrderbookManager::new();
+        book(&orderbooks, "1", "0.40", "0.42");
+        book(&orderbooks, "2", "0.57", "0.59");
+
+        // 5 prices x 2 sizes x 2 tokens
+        assert_eq!(cache.warm_market(&market, &orderbooks).await.unwrap(), 20);
+        assert_eq!(cache.warm_market(&market, &orderbooks).await.unwrap(), 0);
+
+        let order = cache.get_order("1", Side::Buy, dec!(0.41), dec!(25)).unwrap();
+        assert_eq!(order.order.taker_amount, "25000000");
+        assert!(cache.get_order("1", Side::Buy, dec!(0.41), dec!(20)).is_none());
+
+        // Mid moves up 5 ticks: old band evicted, new band signed
+        book(&orderbooks, "1", "0.45", "0.47");
+        cache.warm_market(&market, &orderbooks).await.unwrap();
+        assert!(cache.get_order("1", Side::Buy, dec!(0.41), dec!(10)).is_none());
+        assert!(cache.get_order("1", Side::Buy, dec!(0.48), dec!(10)).is_some());
+        // Served orders are single-use
+        assert!(cache.get_order("1", Side::Buy, dec!(0.48), dec!(10)).is_none());
+
+        let stats = cache.stats();
+        assert_eq!(stats.hits, 2);
+        assert_eq!(stats.misses, 3);
+        assert_eq!(stats.markets[0].entries, 19);
+        assert!(stats.markets[0].time_to_warm.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_tick_size_change_resigns_on_new_grid() {
+        let signer = Arc::new(OrderSigner::new(TEST_KEY, TEST_ADDRESS).unwrap());
+        let cache = PresignedCache::new(signer, vec![dec!(10)]).with_band_ticks(2);
+        let market = test_market();
+        let orderbooks = OrderbookManager::new();
+        book(&orderbooks, "1", "0.40", "0.42");
+        book(&orderbooks, "2", "0.57", "0.59");
+        cache.warm_market(&market, &orderbooks).await.unwrap();
+
+        orderbooks.set_tick_size("1", dec!(0.001));
+        orderbooks.set_tick_size("2", dec!(0.001));
+        assert_eq!(cache.warm_market(&market, &orderbooks).await.unwrap(), 10);
+        assert!(cache.get_order("1", Side::Buy, dec!(0.40), dec!(10)).is_none());
+        assert!(cache.get_order("1", Side::Buy, dec!(0.411), dec!(10)).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_expiring_orders_are_not_served() {
+        let signer = Arc::new(OrderSigner::new(TEST_KEY, TEST_ADDRESS).unwrap());
+        let cache = PresignedCache::new(signer, vec![dec!(10)]).with_band_ticks(0);
+        let market = test_market();
+        let orderbooks = OrderbookManager::new();
+        book(&orderbooks, "1", "0.40", "0.42");
+
+        cache.warm_market(&market, &orderbooks).await.unwrap();
+        let key = OrderKey::new("1", Side::Buy, dec!(0.41), dec!(10));
+        cache.cache.get_mut(&key).unwrap().expires_at = chrono::Utc::now().timestamp() + 90;
+
+        assert_eq!(cache.stats().expired_entries, 1);
+        cache.cleanup();
+        assert_eq!(cache.stats().total_entries, 0);
+    }
+
+    #[tokio::test]
+    async fn test_increment_nonce_invalidates_cached_orders() {
+        let signer = Arc::new(OrderSigner::new(TEST_KEY, TEST_ADDRESS).unwrap());
+        let cache = PresignedCache::new(signer.clone(), vec![dec!(10)]).with_band_ticks(0);
+        let market = test_market();
+        let orderbooks = OrderbookManager::new();
+        book(&orderbooks, "1", "0.40", "0.42");
+        book(&orderbooks, "2", "0.57", "0.59");
+        assert_eq!(cache.warm_market(&market, &orderbooks).await.unwrap(), 2);
+
+        // What NonceClient::cancel_all does after the NegRisk incrementNonce is mined
+        signer.set_nonce(Exchange::NegRisk, 1);
+        assert_eq!(cache.stats().valid_entries, 0);
+        assert!(cache.get_order("2", Side::Buy, dec!(0.58), dec!(10)).is_none());
+
+        // Re-warming signs against the new nonce
+        assert_eq!(cache.warm_market(&market, &orderbooks).await.unwrap(), 2);
+        let order = cache.get_order("1", Side::Buy, dec!(0.41), dec!(10)).unwrap();
+        assert_eq!(order.order.nonce, "1");
     }
 }
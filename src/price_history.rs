@@ -0,0 +1,162 @@
+//! Time-indexed spot price history
+//!
+//! Every composite update is kept as a [`PricePoint`] in a ring buffer
+//! ordered by local receive time. Queries take a wall-time window ("price
+//! 30s ago", "1m average") instead of a tick count, so momentum means the
+//! same thing whether one exchange is connected or seven. Pushing and
+//! expiring are O(1); lookups binary-search the buffer.
+
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// One price as received
+#[derive(Debug, Clone, PartialEq)]
+pub struct PricePoint {
+    /// Exchange timestamp of the update behind this price, if it had one
+    pub exchange_ts_ms: Option<i64>,
+    pub local_ts: Instant,
+    pub price: Decimal,
+    /// Index of the source whose update produced this point
+    pub source: usize,
+}
+
+/// Ring buffer of points covering at most `horizon`
+#[derive(Debug)]
+pub struct PriceHistory {
+    points: VecDeque<PricePoint>,
+    horizon: Duration,
+    max_points: usize,
+}
+
+impl PriceHistory {
+    /// Keep `horizon` of history, but never more than `max_points`
+    pub fn new(horizon: Duration, max_points: usize) -> Self {
+        Self { points: VecDeque::with_capacity(max_points.min(4096)), horizon, max_points }
+    }
+
+    /// Append a point; points must arrive in local time order
+    pub fn push(&mut self, point: PricePoint) {
+        let now = point.local_ts;
+        self.points.push_back(point);
+        while self.points.len() > self.max_points
+            || self.points.front().is_some_and(|p| now.saturating_duration_since(p.local_ts) > self.horizon)
+        {
+            self.points.pop_front();
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn latest(&self) -> Option<&PricePoint> {
+        self.points.back()
+    }
+
+    /// Oldest to newest
+    pub fn iter(&self) -> impl Iterator<Item = &PricePoint> {
+        self.points.iter()
+    }
+
+    /// Price in effect at `t`: the last point at or before it
+    ///
+    /// `None` if the history doesn't reach back that far.
+    pub fn price_at(&self, t: Instant) -> Option<Decimal> {
+        let idx = self.points.partition_point(|p| p.local_ts <= t);
+        (idx > 0).then(|| self.points[idx - 1].price)
+    }
+
+    /// Price `ago` before `now`
+    pub fn price_ago(&self, ago: Duration, now: Instant) -> Option<Decimal> {
+        self.price_at(now.checked_sub(ago)?)
+    }
+
+    /// Percent change from `window` ago to the latest price
+    pub fn roc(&self, window: Duration, now: Instant) -> Option<Decimal> {
+        let past = self.price_ago(window, now)?;
+        let current = self.latest()?.price;
+        if past.is_zero() {
+            return None;
+        }
+        Some((current - past) / past * dec!(100))
+    }
+
+    /// Time-weighted mean price over the last `window`
+    ///
+    /// Each price counts for as long as it was in effect, so a burst of ticks
+    /// doesn't outweigh a quiet stretch. `None` until the history covers the
+    /// whole window.
+    pub fn sma(&self, window: Duration, now: Instant) -> Option<Decimal> {
+        let start = now.checked_sub(window)?;
+        let first = self.points.partition_point(|p| p.local_ts <= start);
+        if first == 0 || window.is_zero() {
+            return None;
+        }
+
+        let mut sum = Decimal::ZERO;
+        let mut from = start;
+        let mut price = self.points[first - 1].price;
+        for p in self.points.range(first..).take_while(|p| p.local_ts <= now) {
+            sum += price * secs(p.local_ts - from);
+            from = p.local_ts;
+            price = p.price;
+        }
+        sum += price * secs(now.saturating_duration_since(from));
+        Some(sum / secs(window))
+    }
+}
+
+fn secs(d: Duration) -> Decimal {
+    Decimal::from(d.as_micros() as u64) / dec!(1000000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(base: Instant, ms: u64, price: Decimal) -> PricePoint {
+        PricePoint { exchange_ts_ms: None, local_ts: base + Duration::from_millis(ms), price, source: 0 }
+    }
+
+    #[test]
+    fn test_time_window_queries() {
+        let base = Instant::now();
+        let mut history = PriceHistory::new(Duration::from_secs(120), 10_000);
+
+        // 100 for 30s, then a burst of ticks at 110 over the last 30s
+        history.push(at(base, 0, dec!(100)));
+        for i in 0..300 {
+            history.push(at(base, 30_000 + i * 100, dec!(110)));
+        }
+        let now = base + Duration::from_secs(60);
+
+        assert_eq!(history.price_ago(Duration::from_secs(45), now), Some(dec!(100)));
+        assert_eq!(history.price_ago(Duration::from_secs(61), now), None);
+        assert_eq!(history.roc(Duration::from_secs(45), now), Some(dec!(10)));
+        assert_eq!(history.roc(Duration::from_secs(20), now), Some(dec!(0)));
+        // Time-weighted: the 300 ticks at 110 count for 30s, not 300x
+        assert_eq!(history.sma(Duration::from_secs(60), now), Some(dec!(105)));
+        assert_eq!(history.sma(Duration::from_secs(61), now), None);
+    }
+
+    #[test]
+    fn test_ring_buffer_expires_by_time_and_count() {
+        let base = Instant::now();
+        let mut history = PriceHistory::new(Duration::from_secs(10), 5);
+        for i in 0..8 {
+            history.push(at(base, i * 1000, Decimal::from(i)));
+        }
+        assert_eq!(history.len(), 5);
+        assert_eq!(history.iter().next().map(|p| p.price), Some(dec!(3)));
+
+        history.push(at(base, 20_000, dec!(9)));
+        assert_eq!(history.len(), 1);
+        assert_eq!(history.latest().map(|p| p.price), Some(dec!(9)));
+    }
+}
@@ -0,0 +1,925 @@
+//! Pluggable spot price sources
+//!
+//! Each exchange feed implements [`PriceSource`]: connect, stream prices into
+//! a [`PriceSink`] until the socket drops, return. The feed reconnects it.
+//! Sources are picked by name from config (BTC_PRICE_SOURCES, ETH_PRICE_SOURCES,
+//! ...), so adding an exchange is one impl plus one arm in [`source_from_name`].
+//! Every source carries the [`Asset`] it streams and builds its exchange
+//! symbol from it.
+//!
+//! The sink keeps a [`SourceQuote`] per source; the composite price is the
+//! freshness-weighted median of the USD quotes that aren't stale, so one
+//! lagging or broken exchange can't drag the price the strategies see.
+//! USDT pairs (Binance, OKX, Bybit) trade at a floating basis to USD, so they
+//! feed flow signals and show their divergence but stay out of the composite.
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use futures_util::{SinkExt, StreamExt};
+use parking_lot::RwLock;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tracing::{debug, error, info};
+
+use crate::spot_flow::SpotTop;
+use crate::spot_price::SpotPriceState;
+use crate::types::Asset;
+
+/// A quote older than this is left out of the composite
+pub const STALE_AFTER: Duration = Duration::from_secs(5);
+
+/// Names accepted in `<ASSET>_PRICE_SOURCES`
+pub const KNOWN_SOURCES: &[&str] =
+    &["coinbase", "coinbase_l2", "binance", "binance_flow", "kraken", "okx", "bybit", "bitstamp"];
+
+/// Currency a source's pair is quoted in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum QuoteCurrency {
+    #[default]
+    Usd,
+    Usdt,
+}
+
+impl fmt::Display for QuoteCurrency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            QuoteCurrency::Usd => "USD",
+            QuoteCurrency::Usdt => "USDT",
+        })
+    }
+}
+
+/// A streaming spot price feed for one asset
+#[async_trait]
+pub trait PriceSource: Send + Sync {
+    /// Short name used in config and logs
+    fn name(&self) -> &'static str;
+
+    /// Quote currency of the streamed pair; only USD quotes make the composite
+    fn quote(&self) -> QuoteCurrency {
+        QuoteCurrency::Usd
+    }
+
+    /// Connect and push prices into `sink` until the connection ends
+    async fn run(&self, sink: &PriceSink) -> Result<()>;
+
+    /// Pause before reconnecting after `run` returns
+    fn reconnect_delay(&self) -> Duration {
+        Duration::from_secs(1)
+    }
+}
+
+/// Build `asset` sources from config names, in order
+///
+/// At least one must be USD-quoted, or there is no composite price.
+pub fn sources_from_names(names: &[String], asset: Asset) -> Result<Vec<Arc<dyn PriceSource>>> {
+    if names.is_empty() {
+        bail!("No {} price sources configured (known: {})", asset, KNOWN_SOURCES.join(","));
+    }
+    let sources = names.iter().map(|n| source_from_name(n, asset)).collect::<Result<Vec<_>>>()?;
+    if sources.iter().all(|s| s.quote() != QuoteCurrency::Usd) {
+        bail!("{} price sources {} are all USDT-quoted; add coinbase, kraken or bitstamp", asset, names.join(","));
+    }
+    Ok(sources)
+}
+
+pub fn source_from_name(name: &str, asset: Asset) -> Result<Arc<dyn PriceSource>> {
+    Ok(match name.trim().to_lowercase().as_str() {
+        "coinbase" => Arc::new(CoinbaseSource(asset)),
+        "binance" => Arc::new(BinanceSource(asset)),
+        "kraken" => Arc::new(KrakenSource(asset)),
+        "okx" => Arc::new(OkxSource(asset)),
+        "bybit" => Arc::new(BybitSource(asset)),
+        "bitstamp" => Arc::new(BitstampSource(asset)),
+        "coinbase_l2" => Arc::new(CoinbaseL2Source(asset)),
+        "binance_flow" => Arc::new(BinanceFlowSource(asset)),
+        other => bail!("Unknown {} price source '{}' (known: {})", asset, other, KNOWN_SOURCES.join(",")),
+    })
+}
+
+/// Latest state reported by one source
+#[derive(Debug, Clone, Default)]
+pub struct SourceQuote {
+    pub name: String,
+    pub quote: QuoteCurrency,
+    pub price: Option<Decimal>,
+    pub updated_at: Option<Instant>,
+    pub connected: bool,
+    pub updates: u64,
+}
+
+impl SourceQuote {
+    pub fn new(name: &str, quote: QuoteCurrency) -> Self {
+        Self { name: name.to_string(), quote, ..Default::default() }
+    }
+
+    /// Time since the last price, if there has been one
+    pub fn age(&self, now: Instant) -> Option<Duration> {
+        self.updated_at.map(|t| now.saturating_duration_since(t))
+    }
+
+    /// Connected with a price younger than [`STALE_AFTER`]
+    pub fn is_fresh(&self, now: Instant) -> bool {
+        self.connected && self.price.is_some() && self.age(now).is_some_and(|a| a < STALE_AFTER)
+    }
+
+    /// Composite weight: 1 for a brand new USD quote, falling linearly to 0 at
+    /// [`STALE_AFTER`]; always 0 for other quote currencies
+    fn weight(&self, now: Instant) -> f64 {
+        match self.age(now) {
+            Some(age) if self.quote == QuoteCurrency::Usd && self.is_fresh(now) => 1.0 - age.as_secs_f64() / STALE_AFTER.as_secs_f64(),
+            _ => 0.0,
+        }
+    }
+}
+
+/// Per-source health relative to the composite
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceHealth {
+    pub name: String,
+    pub quote: QuoteCurrency,
+    pub price: Option<Decimal>,
+    pub connected: bool,
+    /// Connected with a fresh price (only USD quotes count towards the composite)
+    pub healthy: bool,
+    /// Time since the source's last price
+    pub lag: Option<Duration>,
+    /// (price - composite) / composite, in basis points
+    pub divergence_bps: Option<Decimal>,
+    pub updates: u64,
+}
+
+/// Freshness-weighted median of the fresh USD quotes
+///
+/// When the cumulative weight lands exactly on half, the two middle prices
+/// are averaged, so two equally fresh sources give their midpoint.
+pub fn composite_price(quotes: &[SourceQuote], now: Instant) -> Option<Decimal> {
+    let mut weighted: Vec<(Decimal, f64)> = quotes
+        .iter()
+        .filter_map(|q| Some((q.price?, q.weight(now))))
+        .filter(|(_, w)| *w > 0.0)
+        .collect();
+    if weighted.is_empty() {
+        return None;
+    }
+    weighted.sort_by_key(|(p, _)| *p);
+
+    let half = weighted.iter().map(|(_, w)| w).sum::<f64>() / 2.0;
+    let mut cumulative = 0.0;
+    for (i, (price, w)) in weighted.iter().enumerate() {
+        cumulative += w;
+        if (cumulative - half).abs() < 1e-9 {
+            return Some(match weighted.get(i + 1) {
+                Some((next, _)) => (*price + *next) / dec!(2),
+                None => *price,
+            });
+        }
+        if cumulative > half {
+            return Some(*price);
+        }
+    }
+    weighted.last().map(|(p, _)| *p)
+}
+
+pub fn source_health(quotes: &[SourceQuote], now: Instant) -> Vec<SourceHealth> {
+    let composite = composite_price(quotes, now).filter(|c| !c.is_zero());
+    quotes
+        .iter()
+        .map(|q| SourceHealth {
+            name: q.name.clone(),
+            quote: q.quote,
+            price: q.price,
+            connected: q.connected,
+            healthy: q.is_fresh(now),
+            lag: q.age(now),
+            divergence_bps: q.price.zip(composite).map(|(p, c)| ((p - c) / c * dec!(10000)).round_dp(2)),
+            updates: q.updates,
+        })
+        .collect()
+}
+
+/// Write handle a source uses to report into the shared feed state
+pub struct PriceSink {
+    state: Arc<RwLock<SpotPriceState>>,
+    index: usize,
+}
+
+impl PriceSink {
+    pub fn new(state: Arc<RwLock<SpotPriceState>>, index: usize) -> Self {
+        Self { state, index }
+    }
+
+    pub fn set_connected(&self, connected: bool) {
+        self.state.write().set_source_connected(self.index, connected);
+    }
+
+    pub fn push(&self, tick: &SpotTick) {
+        self.state.write().apply_quote(self.index, tick, Instant::now());
+    }
+}
+
+/// What produced a [`SpotTick`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum TickKind {
+    /// Last price from a ticker channel
+    Ticker,
+    /// Individual trade
+    Trade { size: Decimal, buy: bool },
+    /// Top of an order book, priced at its mid
+    Book(SpotTop),
+}
+
+/// One price-bearing update from an exchange frame
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpotTick {
+    pub price: Decimal,
+    pub kind: TickKind,
+    /// Exchange's own timestamp, when the frame carries one
+    pub exchange_ts_ms: Option<i64>,
+}
+
+impl SpotTick {
+    pub fn ticker(price: Decimal, exchange_ts_ms: Option<i64>) -> Self {
+        Self { price, kind: TickKind::Ticker, exchange_ts_ms }
+    }
+
+    pub fn trade(price: Decimal, size: Decimal, buy: bool, exchange_ts_ms: Option<i64>) -> Self {
+        Self { price, kind: TickKind::Trade { size, buy }, exchange_ts_ms }
+    }
+
+    pub fn book(top: SpotTop, exchange_ts_ms: Option<i64>) -> Self {
+        Self { price: top.mid(), kind: TickKind::Book(top), exchange_ts_ms }
+    }
+}
+
+/// Coinbase Exchange `<ASSET>-USD` ticker and matches (trade-by-trade)
+pub struct CoinbaseSource(pub Asset);
+
+#[async_trait]
+impl PriceSource for CoinbaseSource {
+    fn name(&self) -> &'static str {
+        "coinbase"
+    }
+
+    async fn run(&self, sink: &PriceSink) -> Result<()> {
+        let subscribe = serde_json::json!({
+            "type": "subscribe",
+            "product_ids": [format!("{}-USD", self.0.symbol())],
+            "channels": ["ticker", "matches"]
+        });
+        let feed = WsFeed::new(self.name(), "wss://ws-feed.exchange.coinbase.com").subscribe(subscribe);
+        feed.run(sink, parse_coinbase).await
+    }
+}
+
+/// Coinbase Exchange `<ASSET>-USD` order book, priced at the mid (feeds book imbalance)
+///
+/// Uses `level2_batch`, the unauthenticated variant of `level2` (same
+/// messages, batched every 50ms).
+pub struct CoinbaseL2Source(pub Asset);
+
+#[async_trait]
+impl PriceSource for CoinbaseL2Source {
+    fn name(&self) -> &'static str {
+        "coinbase_l2"
+    }
+
+    async fn run(&self, sink: &PriceSink) -> Result<()> {
+        let subscribe = serde_json::json!({
+            "type": "subscribe",
+            "product_ids": [format!("{}-USD", self.0.symbol())],
+            "channels": ["level2_batch"]
+        });
+        let feed = WsFeed::new(self.name(), "wss://ws-feed.exchange.coinbase.com").subscribe(subscribe);
+        let mut book = L2Book::default();
+        feed.run(sink, move |text| book.apply_coinbase(text).into_iter().collect()).await
+    }
+}
+
+/// Binance `<ASSET>USDT` 24h ticker (geo-blocked in some regions; Kraken covers it)
+pub struct BinanceSource(pub Asset);
+
+#[async_trait]
+impl PriceSource for BinanceSource {
+    fn name(&self) -> &'static str {
+        "binance"
+    }
+
+    fn quote(&self) -> QuoteCurrency {
+        QuoteCurrency::Usdt
+    }
+
+    async fn run(&self, sink: &PriceSink) -> Result<()> {
+        let url = format!("wss://stream.binance.com:9443/ws/{}usdt@ticker", self.0.lower());
+        WsFeed::new(self.name(), &url).run(sink, parse_binance).await
+    }
+
+    fn reconnect_delay(&self) -> Duration {
+        Duration::from_secs(2)
+    }
+}
+
+/// Binance `<ASSET>USDT` aggregate trades and top-5 partial book (every 100ms)
+///
+/// Opt-in flow source: the partial `@depth5` stream is a full top-of-book
+/// snapshot each frame, so there's no diff stream to sync against REST.
+pub struct BinanceFlowSource(pub Asset);
+
+#[async_trait]
+impl PriceSource for BinanceFlowSource {
+    fn name(&self) -> &'static str {
+        "binance_flow"
+    }
+
+    fn quote(&self) -> QuoteCurrency {
+        QuoteCurrency::Usdt
+    }
+
+    async fn run(&self, sink: &PriceSink) -> Result<()> {
+        let pair = format!("{}usdt", self.0.lower());
+        let url = format!("wss://stream.binance.com:9443/stream?streams={pair}@aggTrade/{pair}@depth5@100ms");
+        WsFeed::new(self.name(), &url).run(sink, parse_binance_flow).await
+    }
+
+    fn reconnect_delay(&self) -> Duration {
+        Duration::from_secs(2)
+    }
+}
+
+/// Kraken `<ASSET>/USD` ticker (Kraken calls BTC "XBT")
+pub struct KrakenSource(pub Asset);
+
+#[async_trait]
+impl PriceSource for KrakenSource {
+    fn name(&self) -> &'static str {
+        "kraken"
+    }
+
+    async fn run(&self, sink: &PriceSink) -> Result<()> {
+        let subscribe = serde_json::json!({
+            "event": "subscribe",
+            "pair": [match self.0 {
+                Asset::Btc => "XBT/USD".to_string(),
+                other => format!("{}/USD", other.symbol()),
+            }],
+            "subscription": {"name": "ticker"}
+        });
+        WsFeed::new(self.name(), "wss://ws.kraken.com").subscribe(subscribe).run(sink, parse_kraken).await
+    }
+
+    fn reconnect_delay(&self) -> Duration {
+        Duration::from_secs(2)
+    }
+}
+
+/// OKX `<ASSET>-USDT` ticker and trades
+pub struct OkxSource(pub Asset);
+
+#[async_trait]
+impl PriceSource for OkxSource {
+    fn name(&self) -> &'static str {
+        "okx"
+    }
+
+    fn quote(&self) -> QuoteCurrency {
+        QuoteCurrency::Usdt
+    }
+
+    async fn run(&self, sink: &PriceSink) -> Result<()> {
+        let inst_id = format!("{}-USDT", self.0.symbol());
+        let subscribe = serde_json::json!({
+            "op": "subscribe",
+            "args": [
+                {"channel": "tickers", "instId": inst_id},
+                {"channel": "trades", "instId": inst_id}
+            ]
+        });
+        // OKX drops connections that are silent for 30s
+        WsFeed::new(self.name(), "wss://ws.okx.com:8443/ws/v5/public")
+            .subscribe(subscribe)
+            .keepalive(Duration::from_secs(20), "ping")
+            .run(sink, parse_okx)
+            .await
+    }
+
+    fn reconnect_delay(&self) -> Duration {
+        Duration::from_secs(2)
+    }
+}
+
+/// Bybit spot `<ASSET>USDT` ticker and trades
+pub struct BybitSource(pub Asset);
+
+#[async_trait]
+impl PriceSource for BybitSource {
+    fn name(&self) -> &'static str {
+        "bybit"
+    }
+
+    fn quote(&self) -> QuoteCurrency {
+        QuoteCurrency::Usdt
+    }
+
+    async fn run(&self, sink: &PriceSink) -> Result<()> {
+        let subscribe = serde_json::json!({
+            "op": "subscribe",
+            "args": [format!("tickers.{}USDT", self.0.symbol()), format!("publicTrade.{}USDT", self.0.symbol())]
+        });
+        WsFeed::new(self.name(), "wss://stream.bybit.com/v5/public/spot")
+            .subscribe(subscribe)
+            .keepalive(Duration::from_secs(20), r#"{"op":"ping"}"#)
+            .run(sink, parse_bybit)
+            .await
+    }
+
+    fn reconnect_delay(&self) -> Duration {
+        Duration::from_secs(2)
+    }
+}
+
+/// Bitstamp `<ASSET>/USD` trades (its socket has no ticker channel)
+pub struct BitstampSource(pub Asset);
+
+#[async_trait]
+impl PriceSource for BitstampSource {
+    fn name(&self) -> &'static str {
+        "bitstamp"
+    }
+
+    async fn run(&self, sink: &PriceSink) -> Result<()> {
+        let subscribe = serde_json::json!({
+            "event": "bts:subscribe",
+            "data": {"channel": format!("live_trades_{}usd", self.0.lower())}
+        });
+        WsFeed::new(self.name(), "wss://ws.bitstamp.net").subscribe(subscribe).run(sink, parse_bitstamp).await
+    }
+
+    fn reconnect_delay(&self) -> Duration {
+        Duration::from_secs(2)
+    }
+}
+
+fn json(text: &str) -> Option<serde_json::Value> {
+    serde_json::from_str(text).ok()
+}
+
+/// Decimal from a JSON string or number
+fn dec_field(v: &serde_json::Value, key: &str) -> Option<Decimal> {
+    match v.get(key)? {
+        serde_json::Value::String(s) => s.parse().ok(),
+        serde_json::Value::Number(n) => n.to_string().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Integer milliseconds from a JSON string or number
+fn ms_field(v: &serde_json::Value, key: &str) -> Option<i64> {
+    match v.get(key)? {
+        serde_json::Value::String(s) => s.parse().ok(),
+        serde_json::Value::Number(n) => n.as_i64(),
+        _ => None,
+    }
+}
+
+/// Milliseconds from an RFC 3339 `time` field
+fn rfc3339_ms(v: &serde_json::Value, key: &str) -> Option<i64> {
+    let time = v.get(key)?.as_str()?;
+    chrono::DateTime::parse_from_rfc3339(time).ok().map(|t| t.timestamp_millis())
+}
+
+/// `{"type":"ticker","price":"..","time":".."}` and
+/// `{"type":"match"|"last_match","price":"..","size":"..","side":"..","time":".."}`
+///
+/// Coinbase's `side` is the maker's, so a `sell` match is an aggressive buy.
+pub fn parse_coinbase(text: &str) -> Vec<SpotTick> {
+    let Some(msg) = json(text) else { return Vec::new() };
+    let ts = rfc3339_ms(&msg, "time");
+    let tick = match msg.get("type").and_then(|t| t.as_str()) {
+        Some("ticker") => dec_field(&msg, "price").map(|p| SpotTick::ticker(p, ts)),
+        Some("match") | Some("last_match") => dec_field(&msg, "price").zip(dec_field(&msg, "size")).map(|(price, size)| {
+            SpotTick::trade(price, size, msg.get("side").and_then(|s| s.as_str()) == Some("sell"), ts)
+        }),
+        _ => None,
+    };
+    tick.into_iter().collect()
+}
+
+/// `{"e":"24hrTicker","E":<ms>,"c":".."}` (last price)
+pub fn parse_binance(text: &str) -> Vec<SpotTick> {
+    let Some(msg) = json(text) else { return Vec::new() };
+    dec_field(&msg, "c").map(|p| SpotTick::ticker(p, ms_field(&msg, "E"))).into_iter().collect()
+}
+
+/// Combined-stream frames `{"stream":"<pair>@aggTrade","data":{"p":..,"q":..,"m":..,"T":..}}`
+/// and `{"stream":"<pair>@depth5@100ms","data":{"bids":[[p,q],..],"asks":[[p,q],..]}}`
+///
+/// `m` is "buyer is maker", so `m: false` is an aggressive buy. Partial
+/// depth frames carry no timestamp.
+pub fn parse_binance_flow(text: &str) -> Vec<SpotTick> {
+    let Some(msg) = json(text) else { return Vec::new() };
+    let stream = msg.get("stream").and_then(|s| s.as_str()).unwrap_or("");
+    let Some(data) = msg.get("data") else { return Vec::new() };
+    let tick = if stream.ends_with("@aggTrade") {
+        dec_field(data, "p").zip(dec_field(data, "q")).map(|(price, size)| {
+            let buy = data.get("m").and_then(|m| m.as_bool()) == Some(false);
+            SpotTick::trade(price, size, buy, ms_field(data, "T"))
+        })
+    } else if stream.contains("@depth") {
+        let best = |key: &str| {
+            let level = data.get(key)?.as_array()?.first()?.as_array()?;
+            let field = |i: usize| level.get(i)?.as_str()?.parse::<Decimal>().ok();
+            field(0).zip(field(1))
+        };
+        best("bids")
+            .zip(best("asks"))
+            .filter(|((bid, _), (ask, _))| bid < ask)
+            .map(|((bid, bid_size), (ask, ask_size))| SpotTick::book(SpotTop { bid, bid_size, ask, ask_size }, None))
+    } else {
+        None
+    };
+    tick.into_iter().collect()
+}
+
+/// `[channelID, {"c":["<last>","<vol>"],..}, "ticker", "XBT/USD"]` (no timestamp)
+pub fn parse_kraken(text: &str) -> Vec<SpotTick> {
+    let price = json(text).and_then(|msg| {
+        msg.as_array()?.get(1)?.get("c")?.as_array()?.first()?.as_str()?.parse().ok()
+    });
+    price.map(|p| SpotTick::ticker(p, None)).into_iter().collect()
+}
+
+/// `{"arg":{"channel":"tickers"|"trades",..},"data":[{"last":..,"ts":..}|{"px":..,"sz":..,"side":..,"ts":..}]}`
+pub fn parse_okx(text: &str) -> Vec<SpotTick> {
+    let Some(msg) = json(text) else { return Vec::new() };
+    let channel = msg.get("arg").and_then(|a| a.get("channel")).and_then(|c| c.as_str());
+    let Some(data) = msg.get("data").and_then(|d| d.as_array()) else { return Vec::new() };
+    data.iter()
+        .filter_map(|d| {
+            let ts = ms_field(d, "ts");
+            match channel {
+                Some("tickers") => dec_field(d, "last").map(|p| SpotTick::ticker(p, ts)),
+                Some("trades") => dec_field(d, "px").zip(dec_field(d, "sz")).map(|(price, size)| {
+                    SpotTick::trade(price, size, d.get("side").and_then(|s| s.as_str()) == Some("buy"), ts)
+                }),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// `{"topic":"tickers.BTCUSDT","ts":..,"data":{"lastPrice":..}}` and
+/// `{"topic":"publicTrade.BTCUSDT","data":[{"p":..,"v":..,"S":"Buy"|"Sell","T":..}]}`
+pub fn parse_bybit(text: &str) -> Vec<SpotTick> {
+    let Some(msg) = json(text) else { return Vec::new() };
+    let topic = msg.get("topic").and_then(|t| t.as_str()).unwrap_or("");
+    let Some(data) = msg.get("data") else { return Vec::new() };
+    if topic.starts_with("tickers.") {
+        let ts = ms_field(&msg, "ts");
+        dec_field(data, "lastPrice").map(|p| SpotTick::ticker(p, ts)).into_iter().collect()
+    } else if topic.starts_with("publicTrade.") {
+        data.as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|t| {
+                let (price, size) = dec_field(t, "p").zip(dec_field(t, "v"))?;
+                let buy = t.get("S").and_then(|s| s.as_str()) == Some("Buy");
+                Some(SpotTick::trade(price, size, buy, ms_field(t, "T")))
+            })
+            .collect()
+    } else {
+        Vec::new()
+    }
+}
+
+/// `{"event":"trade","channel":"live_trades_btcusd","data":{"price_str":..,"amount_str":..,"type":0|1,"microtimestamp":".."}}`
+///
+/// `type` 0 is a buy, 1 a sell.
+pub fn parse_bitstamp(text: &str) -> Vec<SpotTick> {
+    let Some(msg) = json(text) else { return Vec::new() };
+    if msg.get("event").and_then(|e| e.as_str()) != Some("trade") {
+        return Vec::new();
+    }
+    let Some(data) = msg.get("data") else { return Vec::new() };
+    let price = dec_field(data, "price_str").or_else(|| dec_field(data, "price"));
+    let size = dec_field(data, "amount_str").or_else(|| dec_field(data, "amount"));
+    let ts = ms_field(data, "microtimestamp").map(|us| us / 1000);
+    price
+        .zip(size)
+        .map(|(price, size)| SpotTick::trade(price, size, data.get("type").and_then(|t| t.as_i64()) == Some(0), ts))
+        .into_iter()
+        .collect()
+}
+
+/// Price levels rebuilt from an exchange's snapshot + incremental updates
+#[derive(Debug, Default)]
+pub struct L2Book {
+    bids: BTreeMap<Decimal, Decimal>,
+    asks: BTreeMap<Decimal, Decimal>,
+}
+
+impl L2Book {
+    pub fn best_bid(&self) -> Option<Decimal> {
+        self.bids.keys().next_back().copied()
+    }
+
+    pub fn best_ask(&self) -> Option<Decimal> {
+        self.asks.keys().next().copied()
+    }
+
+    /// Mid price, if both sides are present and not crossed
+    pub fn mid(&self) -> Option<Decimal> {
+        self.top().map(|top| top.mid())
+    }
+
+    /// Best levels with their sizes, if both sides are present and not crossed
+    pub fn top(&self) -> Option<SpotTop> {
+        let (&bid, &bid_size) = self.bids.iter().next_back()?;
+        let (&ask, &ask_size) = self.asks.iter().next()?;
+        (bid < ask).then_some(SpotTop { bid, bid_size, ask, ask_size })
+    }
+
+    /// Set a level; size zero removes it
+    pub fn set_level(&mut self, bid: bool, price: Decimal, size: Decimal) {
+        let side = if bid { &mut self.bids } else { &mut self.asks };
+        if size.is_zero() {
+            side.remove(&price);
+        } else {
+            side.insert(price, size);
+        }
+    }
+
+    /// Apply a Coinbase `snapshot` or `l2update` frame; returns the top of book after it
+    pub fn apply_coinbase(&mut self, text: &str) -> Option<SpotTick> {
+        let msg = json(text)?;
+        let level = |l: &serde_json::Value, i: usize| l.get(i)?.as_str()?.parse::<Decimal>().ok();
+        match msg.get("type")?.as_str()? {
+            "snapshot" => {
+                self.bids.clear();
+                self.asks.clear();
+                for (key, bid) in [("bids", true), ("asks", false)] {
+                    for l in msg.get(key)?.as_array()? {
+                        if let Some((price, size)) = level(l, 0).zip(level(l, 1)) {
+                            self.set_level(bid, price, size);
+                        }
+                    }
+                }
+            }
+            "l2update" => {
+                for change in msg.get("changes")?.as_array()? {
+                    let bid = change.get(0).and_then(|s| s.as_str()) == Some("buy");
+                    if let Some((price, size)) = level(change, 1).zip(level(change, 2)) {
+                        self.set_level(bid, price, size);
+                    }
+                }
+            }
+            _ => return None,
+        }
+        self.top().map(|top| SpotTick::book(top, rfc3339_ms(&msg, "time")))
+    }
+}
+
+/// Shared socket loop: connect, subscribe, parse every text frame into ticks
+struct WsFeed<'a> {
+    name: &'a str,
+    url: &'a str,
+    subscribe: Option<serde_json::Value>,
+    keepalive: Option<(Duration, &'static str)>,
+}
+
+impl<'a> WsFeed<'a> {
+    fn new(name: &'a str, url: &'a str) -> Self {
+        Self { name, url, subscribe: None, keepalive: None }
+    }
+
+    fn subscribe(mut self, msg: serde_json::Value) -> Self {
+        self.subscribe = Some(msg);
+        self
+    }
+
+    /// Send `text` every `every` for exchanges that need application-level pings
+    fn keepalive(mut self, every: Duration, text: &'static str) -> Self {
+        self.keepalive = Some((every, text));
+        self
+    }
+
+    async fn run<P>(self, sink: &PriceSink, mut parse: P) -> Result<()>
+    where
+        P: FnMut(&str) -> Vec<SpotTick> + Send,
+    {
+        let name = self.name;
+        info!("Connecting to {} price feed: {}", name, self.url);
+
+        let (ws_stream, _) = tokio::time::timeout(Duration::from_secs(10), connect_async(self.url))
+            .await
+            .with_context(|| format!("{} WebSocket connection timeout", name))?
+            .with_context(|| format!("Failed to connect to {} WebSocket", name))?;
+
+        let (mut write, mut read) = ws_stream.split();
+        if let Some(msg) = self.subscribe {
+            write
+                .send(Message::Text(msg.to_string()))
+                .await
+                .with_context(|| format!("Failed to send {} subscribe message", name))?;
+        }
+
+        info!("{} price feed connected", name);
+        sink.set_connected(true);
+
+        let (ping_every, ping_text) = self.keepalive.unwrap_or((Duration::from_secs(3600), ""));
+        let mut ping = tokio::time::interval_at(tokio::time::Instant::now() + ping_every, ping_every);
+
+        let mut count: u64 = 0;
+        loop {
+            let msg = tokio::select! {
+                msg = read.next() => msg,
+                _ = ping.tick(), if self.keepalive.is_some() => {
+                    if let Err(e) = write.send(Message::Text(ping_text.to_string())).await {
+                        error!("{} keepalive failed: {}", name, e);
+                        break;
+                    }
+                    continue;
+                }
+            };
+            match msg {
+                Some(Ok(Message::Text(text))) => {
+                    for tick in parse(&text) {
+                        sink.push(&tick);
+                        let price = tick.price;
+                        // Log every ~100th update to avoid spam
+                        if count.is_multiple_of(100) {
+                            debug!("{} price: ${}", name, price.round_dp(2));
+                        }
+                        count += 1;
+                    }
+                }
+                Some(Ok(Message::Ping(data))) => {
+                    let _ = write.send(Message::Pong(data)).await;
+                }
+                Some(Ok(Message::Close(_))) | None => {
+                    info!("{} WebSocket closed by server", name);
+                    break;
+                }
+                Some(Err(e)) => {
+                    error!("{} WebSocket error: {}", name, e);
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        sink.set_connected(false);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quote(name: &str, price: Decimal, age_ms: u64, now: Instant) -> SourceQuote {
+        SourceQuote {
+            name: name.to_string(),
+            quote: QuoteCurrency::Usd,
+            price: Some(price),
+            updated_at: Some(now - Duration::from_millis(age_ms)),
+            connected: true,
+            updates: 1,
+        }
+    }
+
+    #[test]
+    fn test_composite_is_robust_and_freshness_weighted() {
+        let now = Instant::now() + Duration::from_secs(60);
+
+        // Median ignores one bad exchange
+        let quotes = vec![
+            quote("coinbase", dec!(100000), 0, now),
+            quote("binance", dec!(100010), 0, now),
+            quote("kraken", dec!(90000), 0, now),
+        ];
+        assert_eq!(composite_price(&quotes, now), Some(dec!(100000)));
+
+        // Two equally fresh sources give the midpoint; a fresher one wins
+        assert_eq!(composite_price(&quotes[..2], now), Some(dec!(100005)));
+        let skewed = vec![quote("coinbase", dec!(100000), 0, now), quote("binance", dec!(100010), 3000, now)];
+        assert_eq!(composite_price(&skewed, now), Some(dec!(100000)));
+
+        // Stale and disconnected quotes drop out
+        let mut dropped = vec![quote("coinbase", dec!(100000), 6000, now), quote("binance", dec!(100010), 0, now)];
+        assert_eq!(composite_price(&dropped, now), Some(dec!(100010)));
+        dropped[1].connected = false;
+        assert_eq!(composite_price(&dropped, now), None);
+
+        // USDT quotes trade at a basis to USD and stay out
+        let mut mixed = vec![quote("coinbase", dec!(100000), 0, now), quote("binance", dec!(100080), 0, now)];
+        mixed[1].quote = QuoteCurrency::Usdt;
+        assert_eq!(composite_price(&mixed, now), Some(dec!(100000)));
+        assert_eq!(source_health(&mixed, now)[1].divergence_bps, Some(dec!(8)));
+    }
+
+    #[test]
+    fn test_source_health() {
+        let now = Instant::now() + Duration::from_secs(60);
+        let quotes = vec![
+            quote("coinbase", dec!(100000), 0, now),
+            quote("binance", dec!(100100), 0, now),
+            quote("kraken", dec!(99990), 6000, now),
+        ];
+        let health = source_health(&quotes, now);
+        assert!(health[0].healthy && !health[2].healthy);
+        assert_eq!(health[2].lag, Some(Duration::from_secs(6)));
+        assert_eq!(health[0].divergence_bps, Some(dec!(-5)));
+        assert_eq!(health[1].divergence_bps, Some(dec!(5)));
+
+        assert!(sources_from_names(&["Coinbase".to_string(), "kraken".to_string()], Asset::Btc).is_ok());
+        assert!(sources_from_names(&["okx".to_string(), "bitstamp".to_string()], Asset::Sol).is_ok());
+        assert!(sources_from_names(&["okx".to_string(), "bybit".to_string()], Asset::Sol).is_err());
+        assert!(sources_from_names(&["bitfinex".to_string()], Asset::Eth).is_err());
+    }
+
+    // Frames below are hand-written in each exchange's documented message
+    // shape, not captures: ids, sequences and timestamps are made up
+    const COINBASE_TICKER: &str = r#"{"type":"ticker","sequence":95012331884,"product_id":"BTC-USD","price":"97123.45","open_24h":"95880.01","volume_24h":"11872.93","best_bid":"97123.44","best_ask":"97123.45","side":"buy","time":"2025-01-15T14:02:11.284713Z","trade_id":757201933,"last_size":"0.00041"}"#;
+    const COINBASE_MATCH: &str = r#"{"type":"match","trade_id":757201934,"maker_order_id":"b3f6c1f2-6d0e-4f52-9b4c-0c7d5e8f9a11","taker_order_id":"0b1c2d3e-4f50-6172-8394-a5b6c7d8e9f0","side":"sell","size":"0.01520000","price":"97123.46","product_id":"BTC-USD","sequence":95012331890,"time":"2025-01-15T14:02:11.301122Z"}"#;
+    const COINBASE_SNAPSHOT: &str = r#"{"type":"snapshot","product_id":"BTC-USD","bids":[["97123.44","0.51"],["97123.00","1.20"]],"asks":[["97123.45","0.02"],["97124.10","0.75"]]}"#;
+    const COINBASE_L2UPDATE: &str = r#"{"type":"l2update","product_id":"BTC-USD","changes":[["sell","97123.45","0.00000000"],["buy","97123.50","0.10000000"]],"time":"2025-01-15T14:02:11.350000Z"}"#;
+    const BINANCE_TICKER: &str = r#"{"e":"24hrTicker","E":1736949731290,"s":"BTCUSDT","p":"1210.00","P":"1.262","c":"97100.01","Q":"0.00052","b":"97100.00","a":"97100.01"}"#;
+    const BINANCE_AGG_TRADE: &str = r#"{"stream":"btcusdt@aggTrade","data":{"e":"aggTrade","E":1736949731295,"s":"BTCUSDT","a":3371203040,"p":"97100.02","q":"0.01250000","f":4446101111,"l":4446101113,"T":1736949731294,"m":true,"M":true}}"#;
+    const BINANCE_DEPTH5: &str = r#"{"stream":"btcusdt@depth5@100ms","data":{"lastUpdateId":58812345678,"bids":[["97100.01","2.10000000"],["97100.00","0.40000000"]],"asks":[["97100.02","0.70000000"],["97100.05","1.00000000"]]}}"#;
+    const KRAKEN_TICKER: &str = r#"[119930881,{"a":["97050.20000",0,"0.10000000"],"b":["97050.10000",2,"2.00000000"],"c":["97050.10000","0.00100000"],"v":["1105.21","2893.51"]},"ticker","XBT/USD"]"#;
+    const OKX_TICKER: &str = r#"{"arg":{"channel":"tickers","instId":"BTC-USDT"},"data":[{"instType":"SPOT","instId":"BTC-USDT","last":"97088.3","lastSz":"0.00012","askPx":"97088.4","bidPx":"97088.3","ts":"1736949731305"}]}"#;
+    const OKX_TRADES: &str = r#"{"arg":{"channel":"trades","instId":"BTC-USDT"},"data":[{"instId":"BTC-USDT","tradeId":"641972418","px":"97088.4","sz":"0.0031","side":"buy","ts":"1736949731311","count":"1"},{"instId":"BTC-USDT","tradeId":"641972419","px":"97088.3","sz":"0.25","side":"sell","ts":"1736949731312","count":"2"}]}"#;
+    const OKX_SUBSCRIBED: &str = r#"{"event":"subscribe","arg":{"channel":"tickers","instId":"BTC-USDT"},"connId":"a4d3ae55"}"#;
+    const BYBIT_TICKER: &str = r#"{"topic":"tickers.BTCUSDT","ts":1736949731320,"type":"snapshot","cs":44107680011,"data":{"symbol":"BTCUSDT","lastPrice":"97090.55","highPrice24h":"97500.00","lowPrice24h":"95700.12","prevPrice24h":"95900.00","volume24h":"5012.113","turnover24h":"484000000.1","price24hPcnt":"0.0124","usdIndexPrice":"97102.77"}}"#;
+    const BYBIT_TRADES: &str = r#"{"topic":"publicTrade.BTCUSDT","ts":1736949731325,"type":"snapshot","data":[{"i":"2290000000512345678","T":1736949731324,"p":"97090.56","v":"0.004","S":"Buy","s":"BTCUSDT","BT":false}]}"#;
+    const BYBIT_PONG: &str = r#"{"success":true,"ret_msg":"pong","conn_id":"cq8v1b2k","op":"ping"}"#;
+    const BITSTAMP_TRADE: &str = r#"{"data":{"id":385018237,"timestamp":"1736949731","amount":0.0203,"amount_str":"0.02030000","price":97110,"price_str":"97110","type":1,"microtimestamp":"1736949731334000","buy_order_id":1825000000000001,"sell_order_id":1825000000000002},"channel":"live_trades_btcusd","event":"trade"}"#;
+    const BITSTAMP_SUBSCRIBED: &str = r#"{"event":"bts:subscription_succeeded","channel":"live_trades_btcusd","data":{}}"#;
+
+    #[test]
+    fn test_exchange_parsers() {
+        assert_eq!(parse_coinbase(COINBASE_TICKER), vec![SpotTick::ticker(dec!(97123.45), Some(1736949731284))]);
+        assert_eq!(
+            parse_coinbase(COINBASE_MATCH),
+            vec![SpotTick::trade(dec!(97123.46), dec!(0.0152), true, Some(1736949731301))]
+        );
+        assert!(parse_coinbase(r#"{"type":"subscriptions","channels":[]}"#).is_empty());
+        assert_eq!(parse_binance(BINANCE_TICKER), vec![SpotTick::ticker(dec!(97100.01), Some(1736949731290))]);
+        assert_eq!(
+            parse_binance_flow(BINANCE_AGG_TRADE),
+            vec![SpotTick::trade(dec!(97100.02), dec!(0.0125), false, Some(1736949731294))]
+        );
+        let top = SpotTop { bid: dec!(97100.01), bid_size: dec!(2.1), ask: dec!(97100.02), ask_size: dec!(0.7) };
+        assert_eq!(parse_binance_flow(BINANCE_DEPTH5), vec![SpotTick::book(top, None)]);
+        assert!(parse_binance_flow(BINANCE_TICKER).is_empty());
+        assert_eq!(parse_kraken(KRAKEN_TICKER), vec![SpotTick::ticker(dec!(97050.1), None)]);
+        assert!(parse_kraken(r#"{"event":"heartbeat"}"#).is_empty());
+
+        assert_eq!(parse_okx(OKX_TICKER), vec![SpotTick::ticker(dec!(97088.3), Some(1736949731305))]);
+        assert_eq!(
+            parse_okx(OKX_TRADES),
+            vec![
+                SpotTick::trade(dec!(97088.4), dec!(0.0031), true, Some(1736949731311)),
+                SpotTick::trade(dec!(97088.3), dec!(0.25), false, Some(1736949731312)),
+            ]
+        );
+        assert!(parse_okx(OKX_SUBSCRIBED).is_empty());
+        assert!(parse_okx("pong").is_empty());
+
+        assert_eq!(parse_bybit(BYBIT_TICKER), vec![SpotTick::ticker(dec!(97090.55), Some(1736949731320))]);
+        assert_eq!(
+            parse_bybit(BYBIT_TRADES),
+            vec![SpotTick::trade(dec!(97090.56), dec!(0.004), true, Some(1736949731324))]
+        );
+        assert!(parse_bybit(BYBIT_PONG).is_empty());
+
+        assert_eq!(
+            parse_bitstamp(BITSTAMP_TRADE),
+            vec![SpotTick::trade(dec!(97110), dec!(0.0203), false, Some(1736949731334))]
+        );
+        assert!(parse_bitstamp(BITSTAMP_SUBSCRIBED).is_empty());
+    }
+
+    #[test]
+    fn test_coinbase_level2_book() {
+        let mut book = L2Book::default();
+        assert_eq!(book.apply_coinbase(COINBASE_L2UPDATE), None);
+
+        let top = SpotTop { bid: dec!(97123.44), bid_size: dec!(0.51), ask: dec!(97123.45), ask_size: dec!(0.02) };
+        assert_eq!(book.apply_coinbase(COINBASE_SNAPSHOT), Some(SpotTick::book(top, None)));
+        assert_eq!(book.mid(), Some(dec!(97123.445)));
+        assert_eq!((book.best_bid(), book.best_ask()), (Some(dec!(97123.44)), Some(dec!(97123.45))));
+
+        // Best ask pulled, a bid steps up: mid follows both
+        let tick = book.apply_coinbase(COINBASE_L2UPDATE).unwrap();
+        assert_eq!((tick.price, tick.exchange_ts_ms), (dec!(97123.80), Some(1736949731350)));
+        assert_eq!(tick.kind, TickKind::Book(SpotTop { bid: dec!(97123.50), bid_size: dec!(0.1), ask: dec!(97124.10), ask_size: dec!(0.75) }));
+        assert_eq!((book.best_bid(), book.best_ask()), (Some(dec!(97123.50)), Some(dec!(97124.10))));
+        assert_eq!(book.apply_coinbase(COINBASE_TICKER), None);
+    }
+}
@@ -0,0 +1,425 @@
+//! Resolution-source reference price (Chainlink-style oracle)
+//!
+//! The 15-minute markets resolve on the oracle's USD print for the asset at each window
+//! boundary, not on any exchange. Our composite and the oracle usually agree
+//! to a few dollars, but on a close call that's enough to flip the outcome.
+//!
+//! A [`ReferencePriceSource`] reports the oracle's latest value; a
+//! [`ReferenceTracker`] polls it, records the print in effect at every 900s
+//! boundary as that window's official open (and the previous window's
+//! close), and exposes the basis between the oracle and our composite.
+//!
+//! Sources:
+//! - `chainlink`: the asset's on-chain USD round feed via `latestRoundData()`
+//!   (POLYGON_RPC_URL). Rounds update on deviation or heartbeat, so this
+//!   approximates the higher-frequency data stream the markets settle on.
+//! - `stub`: our own composite plus a fixed offset, for dry runs and tests
+
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use ethers::abi::{self, ParamType, Token};
+use ethers::providers::{Http, Middleware, Provider};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Address, TransactionRequest, I256};
+use ethers::utils::keccak256;
+use parking_lot::RwLock;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::config::{AssetConfig, Config};
+use crate::spot_price::SpotPriceFeed;
+use crate::supervisor::Supervisor;
+use crate::types::{boundaries_between, boundary_ms, WINDOW_SECS};
+
+/// Windows kept in memory (one day)
+const MAX_WINDOWS: usize = 96;
+
+/// One oracle value and the time the oracle stamped it
+#[derive(Debug, Clone, PartialEq)]
+pub struct OracleReport {
+    pub price: Decimal,
+    pub timestamp_ms: i64,
+    pub round_id: Option<u128>,
+}
+
+/// A source of oracle reports
+#[async_trait]
+pub trait ReferencePriceSource: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// The oracle's current value
+    async fn latest(&self) -> Result<OracleReport>;
+
+    fn poll_interval(&self) -> Duration {
+        Duration::from_secs(1)
+    }
+}
+
+/// Build the source selected in config (REFERENCE_PRICE_SOURCE) for one asset
+///
+/// An asset without a Chainlink feed configured falls back to the stub.
+pub async fn from_config(
+    config: &Config,
+    asset: &AssetConfig,
+    spot_feed: Arc<SpotPriceFeed>,
+) -> Result<Arc<dyn ReferencePriceSource>> {
+    match config.reference_price_source.as_str() {
+        "chainlink" => {
+            let rpc_url = config
+                .polygon_rpc_url
+                .as_deref()
+                .context("REFERENCE_PRICE_SOURCE=chainlink needs POLYGON_RPC_URL")?;
+            let Some(feed) = asset.chainlink_feed.as_deref() else {
+                warn!("No CHAINLINK_{}_FEED configured, tracking {} against the stub oracle", asset.asset.symbol(), asset.asset);
+                return Ok(Arc::new(StubReferenceSource::new(spot_feed, Decimal::ZERO)));
+            };
+            let feed = Address::from_str(feed).with_context(|| format!("Invalid CHAINLINK_{}_FEED", asset.asset.symbol()))?;
+            Ok(Arc::new(ChainlinkRoundSource::connect(rpc_url, feed).await?))
+        }
+        "stub" => Ok(Arc::new(StubReferenceSource::new(spot_feed, Decimal::ZERO))),
+        other => bail!("Unknown REFERENCE_PRICE_SOURCE '{}' (chainlink, stub)", other),
+    }
+}
+
+/// Stand-in oracle: our composite shifted by a fixed offset
+pub struct StubReferenceSource {
+    spot_feed: Arc<SpotPriceFeed>,
+    offset: Decimal,
+}
+
+impl StubReferenceSource {
+    pub fn new(spot_feed: Arc<SpotPriceFeed>, offset: Decimal) -> Self {
+        Self { spot_feed, offset }
+    }
+}
+
+#[async_trait]
+impl ReferencePriceSource for StubReferenceSource {
+    fn name(&self) -> &'static str {
+        "stub"
+    }
+
+    async fn latest(&self) -> Result<OracleReport> {
+        let price = self.spot_feed.get_price();
+        if price.is_zero() {
+            bail!("No composite price yet");
+        }
+        Ok(OracleReport { price: price + self.offset, timestamp_ms: chrono::Utc::now().timestamp_millis(), round_id: None })
+    }
+}
+
+/// Chainlink aggregator read through `latestRoundData()`
+pub struct ChainlinkRoundSource {
+    provider: Provider<Http>,
+    feed: Address,
+    decimals: u32,
+}
+
+fn selector(signature: &str) -> Vec<u8> {
+    keccak256(signature)[..4].to_vec()
+}
+
+impl ChainlinkRoundSource {
+    pub async fn connect(rpc_url: &str, feed: Address) -> Result<Self> {
+        let provider = Provider::<Http>::try_from(rpc_url).context("Invalid RPC URL")?;
+        let call: TypedTransaction = TransactionRequest::new().to(feed).data(selector("decimals()")).into();
+        let result = provider
+            .call(&call, None)
+            .await
+            .with_context(|| format!("decimals() call to {:?} failed", feed))?;
+        if result.len() != 32 {
+            bail!("decimals() returned {} bytes (no aggregator at {:?}?)", result.len(), feed);
+        }
+        let decimals = result[31] as u32;
+        info!("Chainlink feed {:?}: {} decimals", feed, decimals);
+        Ok(Self { provider, feed, decimals })
+    }
+}
+
+#[async_trait]
+impl ReferencePriceSource for ChainlinkRoundSource {
+    fn name(&self) -> &'static str {
+        "chainlink"
+    }
+
+    async fn latest(&self) -> Result<OracleReport> {
+        let call: TypedTransaction = TransactionRequest::new().to(self.feed).data(selector("latestRoundData()")).into();
+        let result = self.provider.call(&call, None).await.context("latestRoundData() call failed")?;
+        decode_latest_round(&result, self.decimals)
+    }
+
+    fn poll_interval(&self) -> Duration {
+        Duration::from_secs(2)
+    }
+}
+
+/// Decode `(uint80 roundId, int256 answer, uint256 startedAt, uint256 updatedAt, uint80 answeredInRound)`
+pub fn decode_latest_round(data: &[u8], decimals: u32) -> Result<OracleReport> {
+    let types = [
+        ParamType::Uint(80),
+        ParamType::Int(256),
+        ParamType::Uint(256),
+        ParamType::Uint(256),
+        ParamType::Uint(80),
+    ];
+    let tokens = abi::decode(&types, data).context("Malformed latestRoundData() result")?;
+    let (Token::Uint(round_id), Token::Int(answer), Token::Uint(updated_at)) = (&tokens[0], &tokens[1], &tokens[3]) else {
+        bail!("Unexpected latestRoundData() tokens");
+    };
+    let answer = I256::from_raw(*answer);
+    if answer <= I256::zero() {
+        bail!("Oracle answer {} is not a price", answer);
+    }
+    let answer = i128::try_from(answer).map_err(|_| anyhow!("Oracle answer {} out of range", answer))?;
+    let updated_at = u64::try_from(*updated_at)
+        .ok()
+        .and_then(|secs| i64::try_from(secs).ok())
+        .ok_or_else(|| anyhow!("Oracle updatedAt {} out of range", updated_at))?;
+    Ok(OracleReport {
+        price: Decimal::try_from_i128_with_scale(answer, decimals)
+            .map_err(|e| anyhow!("Oracle answer {} does not fit a decimal: {}", answer, e))?,
+        timestamp_ms: updated_at * 1000,
+        round_id: Some(round_id.as_u128()),
+    })
+}
+
+/// Official prints for one market window
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowPrints {
+    /// Window start, unix seconds
+    pub window_start: i64,
+    pub open: Option<OracleReport>,
+    pub close: Option<OracleReport>,
+}
+
+impl WindowPrints {
+    /// UP if the close is at or above the open (the market's resolution rule)
+    pub fn outcome(&self) -> Option<bool> {
+        Some(self.close.as_ref()?.price >= self.open.as_ref()?.price)
+    }
+}
+
+#[derive(Default)]
+struct TrackerState {
+    latest: Option<OracleReport>,
+    windows: BTreeMap<i64, WindowPrints>,
+}
+
+/// Oracle history keyed by market window
+#[derive(Default)]
+pub struct ReferenceTracker {
+    state: RwLock<TrackerState>,
+}
+
+impl ReferenceTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a polled report; `now_ms` is when the poll returned
+    ///
+    /// The print for a boundary is the report in effect at it: the last one
+    /// stamped at or before the boundary. It's recorded once a newer report
+    /// shows up, or once a poll after the boundary still returns it. A lagging
+    /// RPC can hand back the previous round after the boundary, so a later
+    /// report stamped after the recorded one but still at or before the
+    /// boundary replaces it.
+    pub fn observe(&self, report: OracleReport, now_ms: i64) {
+        let mut state = self.state.write();
+
+        if let Some(prev) = state.latest.clone() {
+            if report.timestamp_ms > prev.timestamp_ms {
+                // Boundaries between the two reports were covered by `prev`
+                for start in boundaries_between(prev.timestamp_ms, report.timestamp_ms - 1) {
+                    settle(&mut state, start, &prev);
+                }
+            }
+        }
+        if state.latest.as_ref().is_none_or(|prev| report.timestamp_ms > prev.timestamp_ms) {
+            state.latest = Some(report);
+        }
+
+        // Still the latest after a boundary passed: it's that boundary's print
+        if let Some(latest) = state.latest.clone() {
+            for start in boundaries_between(latest.timestamp_ms, now_ms) {
+                settle(&mut state, start, &latest);
+            }
+        }
+
+        while state.windows.len() > MAX_WINDOWS {
+            state.windows.pop_first();
+        }
+    }
+
+    pub fn latest(&self) -> Option<OracleReport> {
+        self.state.read().latest.clone()
+    }
+
+    /// Prints for the window starting at `window_start` (unix seconds)
+    pub fn window(&self, window_start: i64) -> Option<WindowPrints> {
+        self.state.read().windows.get(&window_start).cloned()
+    }
+
+    /// Most recent window with both prints
+    pub fn last_settled(&self) -> Option<WindowPrints> {
+        self.state.read().windows.values().rev().find(|w| w.close.is_some()).cloned()
+    }
+
+    /// Oracle minus composite, in dollars
+    pub fn basis(&self, composite: Decimal) -> Option<Decimal> {
+        let latest = self.state.read().latest.as_ref()?.price;
+        (!composite.is_zero()).then(|| latest - composite)
+    }
+
+    /// Oracle minus composite, in basis points of the composite
+    pub fn basis_bps(&self, composite: Decimal) -> Option<Decimal> {
+        Some((self.basis(composite)? / composite * dec!(10000)).round_dp(2))
+    }
+}
+
+fn settle(state: &mut TrackerState, window_start: i64, report: &OracleReport) {
+    if report.timestamp_ms > boundary_ms(window_start) {
+        return;
+    }
+    let open = state
+        .windows
+        .entry(window_start)
+        .or_insert_with(|| WindowPrints { window_start, open: None, close: None });
+    keep_latest(&mut open.open, report);
+    let prev_start = window_start - WINDOW_SECS;
+    let close = state
+        .windows
+        .entry(prev_start)
+        .or_insert_with(|| WindowPrints { window_start: prev_start, open: None, close: None });
+    keep_latest(&mut close.close, report);
+}
+
+/// Record `report` unless the print already there is at least as recent
+fn keep_latest(print: &mut Option<OracleReport>, report: &OracleReport) {
+    if print.as_ref().is_none_or(|p| report.timestamp_ms > p.timestamp_ms) {
+        *print = Some(report.clone());
+    }
+}
+
+/// Poll `source` on `supervisor` into a shared tracker
+pub fn spawn_reference_tracker(source: Arc<dyn ReferencePriceSource>, supervisor: &Supervisor) -> Arc<ReferenceTracker> {
+    let tracker = Arc::new(ReferenceTracker::new());
+    let tracker_clone = tracker.clone();
+
+    supervisor.spawn("Reference tracker", async move {
+        info!("Reference price: polling {} every {:?}", source.name(), source.poll_interval());
+        let mut failures = 0u32;
+        loop {
+            match source.latest().await {
+                Ok(report) => {
+                    failures = 0;
+                    tracker_clone.observe(report, chrono::Utc::now().timestamp_millis());
+                }
+                Err(e) => {
+                    failures += 1;
+                    // Log the first failure and then every 30th
+                    if failures % 30 == 1 {
+                        warn!("{} reference price unavailable: {}", source.name(), e);
+                    }
+                }
+            }
+            tokio::time::sleep(source.poll_interval()).await;
+        }
+    });
+
+    tracker
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::U256;
+
+    fn report(price: Decimal, timestamp_ms: i64) -> OracleReport {
+        OracleReport { price, timestamp_ms, round_id: None }
+    }
+
+    #[test]
+    fn test_decode_latest_round() {
+        let data = abi::encode(&[
+            Token::Uint(U256::from(18446744073709572000u128)),
+            Token::Int(U256::from(9_712_345_678_901u64)),
+            Token::Uint(U256::from(1_736_949_700u64)),
+            Token::Uint(U256::from(1_736_949_731u64)),
+            Token::Uint(U256::from(18446744073709572000u128)),
+        ]);
+        let report = decode_latest_round(&data, 8).unwrap();
+        assert_eq!(report.price, dec!(97123.45678901));
+        assert_eq!(report.timestamp_ms, 1_736_949_731_000);
+        assert_eq!(report.round_id, Some(18446744073709572000));
+
+        assert!(decode_latest_round(&data[..64], 8).is_err());
+
+        // Out-of-range words are errors, not panics
+        let round = |answer: U256, updated_at: U256| {
+            abi::encode(&[
+                Token::Uint(U256::one()),
+                Token::Int(answer),
+                Token::Uint(U256::zero()),
+                Token::Uint(updated_at),
+                Token::Uint(U256::one()),
+            ])
+        };
+        assert!(decode_latest_round(&round(U256::one() << 200, U256::one()), 8).is_err());
+        assert!(decode_latest_round(&round(U256::one(), U256::one() << 100), 8).is_err());
+    }
+
+    #[test]
+    fn test_tracker_records_window_prints_and_basis() {
+        let tracker = ReferenceTracker::new();
+        let t0 = 1_736_949_600; // a window boundary
+        let ms = |s: i64| s * 1000;
+
+        tracker.observe(report(dec!(97000), ms(t0 - 20)), ms(t0 - 19));
+        // Same round polled after the boundary: it is the open print
+        tracker.observe(report(dec!(97000), ms(t0 - 20)), ms(t0 + 1));
+        assert_eq!(tracker.window(t0).and_then(|w| w.open).map(|r| r.price), Some(dec!(97000)));
+
+        tracker.observe(report(dec!(97100), ms(t0 + 300)), ms(t0 + 301));
+        tracker.observe(report(dec!(96990), ms(t0 + 899)), ms(t0 + 899));
+        // Next round lands after the close; the one before it is the close print
+        tracker.observe(report(dec!(97250), ms(t0 + 905)), ms(t0 + 906));
+
+        let window = tracker.window(t0).unwrap();
+        assert_eq!(window.close.as_ref().map(|r| r.price), Some(dec!(96990)));
+        assert_eq!(window.outcome(), Some(false));
+        assert_eq!(tracker.last_settled().map(|w| w.window_start), Some(t0));
+        assert_eq!(tracker.window(t0 + WINDOW_SECS).and_then(|w| w.open).map(|r| r.price), Some(dec!(96990)));
+
+        assert_eq!(tracker.basis(dec!(97240)), Some(dec!(10)));
+        assert_eq!(tracker.basis_bps(dec!(97240)), Some(dec!(1.03)));
+    }
+
+    #[test]
+    fn test_lagged_poll_is_corrected_by_a_later_report() {
+        let tracker = ReferenceTracker::new();
+        let t0 = 1_736_949_600;
+        let ms = |s: i64| s * 1000;
+
+        tracker.observe(report(dec!(97000), ms(t0 - 40)), ms(t0 - 39));
+        // The RPC lags: after the boundary it still returns the old round
+        tracker.observe(report(dec!(97000), ms(t0 - 40)), ms(t0 + 1));
+        assert_eq!(tracker.window(t0).and_then(|w| w.open).map(|r| r.price), Some(dec!(97000)));
+
+        // It catches up with the round that was really in effect at the boundary
+        tracker.observe(report(dec!(97060), ms(t0 - 5)), ms(t0 + 3));
+        let open = tracker.window(t0).and_then(|w| w.open).unwrap();
+        assert_eq!((open.price, open.timestamp_ms), (dec!(97060), ms(t0 - 5)));
+        assert_eq!(tracker.window(t0 - WINDOW_SECS).and_then(|w| w.close).map(|r| r.price), Some(dec!(97060)));
+
+        // Rounds after the boundary, or older than the recorded print, leave it alone
+        tracker.observe(report(dec!(97200), ms(t0 + 10)), ms(t0 + 11));
+        tracker.observe(report(dec!(96900), ms(t0 - 30)), ms(t0 + 12));
+        assert_eq!(tracker.window(t0).and_then(|w| w.open).map(|r| r.price), Some(dec!(97060)));
+    }
+}
@@ -1,45 +1,120 @@
 use anyhow::{Context, Result};
 use ethers::prelude::*;
-use ethers::types::transaction::eip712::{Eip712, TypedData};
 use ethers::utils::hex;
 use rust_decimal::Decimal;
-use serde_json::json;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use uuid::Uuid;
 
-use crate::types::{Order, SignedOrder, OrderType, Side};
+use crate::config::Config;
+use crate::eip712::{Exchange, OrderData};
+use crate::nonce::{checked_u64, NonceClient};
+use crate::order_builder::{
+    limit_order_amounts, market_order_amounts, validate_market_order_type, validate_min_size,
+    validate_price, OrderAmounts,
+};
+use crate::signing_backend::{self, LocalKeyBackend, SigningBackend};
+use crate::types::{UpDownMarket, Order, SignedOrder, OrderType, Side};
 
-// Polymarket Exchange contract addresses on Polygon
-// Regular CTF Exchange for non-negRisk markets
-const CTF_EXCHANGE_ADDRESS: &str = "0x4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E";
-// NegRisk CTF Exchange for negRisk markets (BTC 15-min markets use this)
-const NEG_RISK_CTF_EXCHANGE_ADDRESS: &str = "0xC5d563A36AE78145C45a50134d48A1215220f80a";
-const CHAIN_ID: u64 = 137; // Polygon mainnet
+// GTD orders stay live until `expiration - 60s` on the exchange side
+const GTD_SECURITY_THRESHOLD_SECS: i64 = 60;
+// Cancel GTD orders this long before market end to avoid last-second fills
+const GTD_EXPIRY_BUFFER_SECS: i64 = 10;
 
 /// EIP-712 Order Signer for Polymarket
+///
+/// Builds and hashes orders; the key itself lives behind a `SigningBackend`.
 pub struct OrderSigner {
-    wallet: LocalWallet,
+    backend: Arc<dyn SigningBackend>,
     address: Address,
     funder: Address,
+    // Order nonce per exchange; must match the on-chain `nonces[maker]`
+    ctf_nonce: AtomicU64,
+    neg_risk_nonce: AtomicU64,
 }
 
 impl OrderSigner {
+    /// Signer with a raw in-memory private key
     pub fn new(private_key: &str, funder_address: &str) -> Result<Self> {
-        let wallet = private_key
-            .parse::<LocalWallet>()
-            .context("Invalid private key")?
-            .with_chain_id(CHAIN_ID);
+        Self::with_backend(Arc::new(LocalKeyBackend::new(private_key)?), funder_address)
+    }
 
-        let address = wallet.address();
+    /// Signer over any backend (keystore, remote signer, ...)
+    pub fn with_backend(backend: Arc<dyn SigningBackend>, funder_address: &str) -> Result<Self> {
+        let address = backend.address();
         let funder = Address::from_str(funder_address).context("Invalid funder address")?;
 
         Ok(Self {
-            wallet,
+            backend,
             address,
             funder,
+            ctf_nonce: AtomicU64::new(0),
+            neg_risk_nonce: AtomicU64::new(0),
         })
     }
 
+    /// Signer using the backend selected in config
+    pub async fn from_config(config: &Config) -> Result<Self> {
+        let backend = signing_backend::from_config(config).await?;
+        tracing::info!("Order signer {:?} using {}", backend.address(), backend.describe());
+        let signer = Self::with_backend(backend, &config.address)?;
+
+        // Without an RPC we assume nonce 0 (never incremented)
+        if let Some(rpc_url) = &config.polygon_rpc_url {
+            if let Err(e) = signer.sync_nonces(rpc_url).await {
+                tracing::warn!("Failed to sync order nonces, using 0: {}", e);
+            }
+        }
+        Ok(signer)
+    }
+
+    /// Address of the signing key
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Maker address orders are placed for
+    pub fn funder(&self) -> Address {
+        self.funder
+    }
+
+    pub fn backend(&self) -> Arc<dyn SigningBackend> {
+        self.backend.clone()
+    }
+
+    fn nonce_slot(&self, exchange: Exchange) -> &AtomicU64 {
+        match exchange {
+            Exchange::Ctf => &self.ctf_nonce,
+            Exchange::NegRisk => &self.neg_risk_nonce,
+        }
+    }
+
+    /// Nonce new orders on this exchange are signed with
+    pub fn nonce(&self, exchange: Exchange) -> u64 {
+        self.nonce_slot(exchange).load(Ordering::SeqCst)
+    }
+
+    /// Set after an on-chain `incrementNonce` (orders with older nonces are dead)
+    pub fn set_nonce(&self, exchange: Exchange, nonce: u64) {
+        self.nonce_slot(exchange).store(nonce, Ordering::SeqCst);
+    }
+
+    /// Load both exchange nonces for our maker from chain
+    pub async fn sync_nonces(&self, rpc_url: &str) -> Result<()> {
+        let client = NonceClient::connect(rpc_url).await?;
+        for exchange in [Exchange::Ctf, Exchange::NegRisk] {
+            let nonce = client.fetch_nonce(exchange, self.funder).await?;
+            self.set_nonce(exchange, checked_u64(nonce, "order nonce")?);
+        }
+        tracing::info!(
+            "Order nonces synced: CTF={} NegRisk={}",
+            self.nonce(Exchange::Ctf),
+            self.nonce(Exchange::NegRisk)
+        );
+        Ok(())
+    }
+
     /// Create and sign an order
     pub async fn create_order(
         &self,
@@ -50,60 +125,107 @@ impl OrderSigner {
         tick_size: Decimal,
         neg_risk: bool,
     ) -> Result<Order> {
-        // Calculate amounts based on side (from Polymarket's official clob-client)
-        // USDC uses 6 decimals, conditional tokens use 6 decimals
-        //
-        // For BUY orders:
-        //   - makerAmount = size * price (USDC cost - what you PAY)
-        //   - takerAmount = size (shares - what you GET)
-        //
-        // For SELL orders:
-        //   - makerAmount = size (shares - what you SELL)
-        //   - takerAmount = size * price (USDC - what you GET)
-        let (maker_amount, taker_amount) = match side {
-            Side::Buy => {
-                // BUY: You pay USDC (maker), you get shares (taker)
-                let cost = size * price;
-                let cost_raw = (cost * Decimal::from(1_000_000)).round().to_string();
-                let shares_raw = (size * Decimal::from(1_000_000)).round().to_string();
-                (cost_raw, shares_raw) // maker=cost, taker=shares
-            }
-            Side::Sell => {
-                // SELL: You give shares (maker), you get USDC (taker)
-                let cost = size * price;
-                let shares_raw = (size * Decimal::from(1_000_000)).round().to_string();
-                let cost_raw = (cost * Decimal::from(1_000_000)).round().to_string();
-                (shares_raw, cost_raw) // maker=shares, taker=cost
-            }
+        // Amounts follow Polymarket's per-tick rounding (see order_builder)
+        let amounts = limit_order_amounts(side, price, size, tick_size)?;
+
+        // GTC orders rest until cancelled; the exchange rejects one with an expiration
+        self.build_order(token_id, side, amounts, "0".to_string(), neg_risk, OrderType::Gtc).await
+    }
+
+    /// Create a GTD limit order that expires right before the market resolves
+    ///
+    /// `tick_size` is the market's current tick
+    /// (`OrderbookManager::market_tick_size`), not the one from discovery.
+    pub async fn create_gtd_order(
+        &self,
+        market: &UpDownMarket,
+        token_id: &str,
+        price: Decimal,
+        size: Decimal,
+        side: Side,
+        tick_size: Decimal,
+    ) -> Result<Order> {
+        validate_price(price, tick_size)?;
+        validate_min_size(size, market.min_order_size)?;
+
+        // Exchange treats expiration as "valid until expiration - 60s"
+        let expires_at = market.end_time.timestamp() - GTD_EXPIRY_BUFFER_SECS;
+        if expires_at <= chrono::Utc::now().timestamp() {
+            anyhow::bail!("Market {} ends too soon for a GTD order", market.event_slug);
+        }
+        let expiration = (expires_at + GTD_SECURITY_THRESHOLD_SECS).to_string();
+
+        let amounts = limit_order_amounts(side, price, size, tick_size)?;
+        self.build_order(token_id, side, amounts, expiration, market.neg_risk, OrderType::Gtd).await
+    }
+
+    /// Create a FOK/FAK market order
+    ///
+    /// `amount` is USDC to spend for BUY and shares to sell for SELL.
+    /// `worst_price` caps how far into the book the order may fill, on the
+    /// market's current `tick_size` grid.
+    pub async fn create_market_order(
+        &self,
+        market: &UpDownMarket,
+        token_id: &str,
+        amount: Decimal,
+        worst_price: Decimal,
+        side: Side,
+        order_type: OrderType,
+        tick_size: Decimal,
+    ) -> Result<Order> {
+        validate_market_order_type(order_type)?;
+        validate_price(worst_price, tick_size)?;
+
+        let shares = match side {
+            Side::Buy => amount / worst_price,
+            Side::Sell => amount,
         };
+        validate_min_size(shares, market.min_order_size)?;
 
-        // Generate random salt
-        let salt = Uuid::new_v4().as_u128().to_string();
+        let amounts = market_order_amounts(side, amount, worst_price, tick_size)?;
 
-        // Set expiration to 1 hour from now
-        let expiration = (chrono::Utc::now().timestamp() + 3600).to_string();
+        // Market orders never rest on the book, so no expiration
+        self.build_order(token_id, side, amounts, "0".to_string(), market.neg_risk, order_type).await
+    }
+
+    /// Fill in the order struct, sign it and wrap it for submission
+    async fn build_order(
+        &self,
+        token_id: &str,
+        side: Side,
+        amounts: OrderAmounts,
+        expiration: String,
+        neg_risk: bool,
+        order_type: OrderType,
+    ) -> Result<Order> {
+        let OrderAmounts { maker_amount, taker_amount } = amounts;
+
+        // Generate random salt
+        let salt = Uuid::new_v4().as_u128();
+        let nonce = self.nonce(Exchange::from_neg_risk(neg_risk));
 
         // Create order struct for signing
-        let order_data = json!({
-            "salt": salt,
-            "maker": format!("{:?}", self.funder),
-            "signer": format!("{:?}", self.address),
-            "taker": "0x0000000000000000000000000000000000000000",
-            "tokenId": token_id,
-            "makerAmount": maker_amount,
-            "takerAmount": taker_amount,
-            "expiration": expiration,
-            "nonce": "0",
-            "feeRateBps": "0",
-            "side": if matches!(side, Side::Buy) { 0 } else { 1 },
-            "signatureType": 0 // EOA
-        });
+        let order_data = OrderData {
+            salt: U256::from(salt),
+            maker: self.funder,
+            signer: self.address,
+            taker: Address::zero(),
+            token_id: U256::from_dec_str(token_id).context("Invalid token ID")?,
+            maker_amount: U256::from_dec_str(&maker_amount)?,
+            taker_amount: U256::from_dec_str(&taker_amount)?,
+            expiration: U256::from_dec_str(&expiration)?,
+            nonce: U256::from(nonce),
+            fee_rate_bps: U256::zero(),
+            side: if matches!(side, Side::Buy) { 0 } else { 1 },
+            signature_type: 0, // EOA
+        };
 
         // Sign the order using EIP-712 (use correct exchange contract)
         let signature = self.sign_order(&order_data, neg_risk).await?;
 
         let signed_order = SignedOrder {
-            salt,
+            salt: salt.to_string(),
             maker: format!("{:?}", self.funder),
             signer: format!("{:?}", self.address),
             taker: "0x0000000000000000000000000000000000000000".to_string(),
@@ -111,7 +233,7 @@ impl OrderSigner {
             maker_amount,
             taker_amount,
             expiration,
-            nonce: "0".to_string(),
+            nonce: nonce.to_string(),
             fee_rate_bps: "0".to_string(),
             side,
             signature_type: 0,
@@ -121,65 +243,20 @@ impl OrderSigner {
         Ok(Order {
             order: signed_order,
             owner: format!("{:?}", self.funder),
-            order_type: OrderType::Gtc,
+            order_type,
         })
     }
 
     /// Sign order using EIP-712
-    async fn sign_order(&self, order: &serde_json::Value, neg_risk: bool) -> Result<String> {
+    ///
+    /// Hashes the struct natively against the precomputed domain separator
+    /// for the exchange contract, no TypedData JSON round-trip.
+    async fn sign_order(&self, order: &OrderData, neg_risk: bool) -> Result<String> {
         // Select correct exchange contract based on market type
-        let exchange_address = if neg_risk {
-            NEG_RISK_CTF_EXCHANGE_ADDRESS
-        } else {
-            CTF_EXCHANGE_ADDRESS
-        };
-
-        // EIP-712 domain
-        let domain = json!({
-            "name": "Polymarket CTF Exchange",
-            "version": "1",
-            "chainId": CHAIN_ID,
-            "verifyingContract": exchange_address
-        });
-
-        // EIP-712 types
-        let types = json!({
-            "EIP712Domain": [
-                {"name": "name", "type": "string"},
-                {"name": "version", "type": "string"},
-                {"name": "chainId", "type": "uint256"},
-                {"name": "verifyingContract", "type": "address"}
-            ],
-            "Order": [
-                {"name": "salt", "type": "uint256"},
-                {"name": "maker", "type": "address"},
-                {"name": "signer", "type": "address"},
-                {"name": "taker", "type": "address"},
-                {"name": "tokenId", "type": "uint256"},
-                {"name": "makerAmount", "type": "uint256"},
-                {"name": "takerAmount", "type": "uint256"},
-                {"name": "expiration", "type": "uint256"},
-                {"name": "nonce", "type": "uint256"},
-                {"name": "feeRateBps", "type": "uint256"},
-                {"name": "side", "type": "uint8"},
-                {"name": "signatureType", "type": "uint8"}
-            ]
-        });
-
-        // Create typed data
-        let typed_data = json!({
-            "types": types,
-            "primaryType": "Order",
-            "domain": domain,
-            "message": order
-        });
-
-        // Parse as TypedData and sign
-        let typed_data: TypedData = serde_json::from_value(typed_data)?;
+        let exchange = Exchange::from_neg_risk(neg_risk);
 
         // Hash and sign
-        let hash = typed_data.encode_eip712()?;
-        let signature = self.wallet.sign_hash(H256::from(hash))?;
+        let signature = self.backend.sign_order(order, exchange).await?;
 
         // Return hex signature
         Ok(format!("0x{}", hex::encode(signature.to_vec())))
@@ -232,3 +309,87 @@ impl OrderSigner {
         Ok(orders)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{test_market, TEST_ADDRESS, TEST_KEY};
+    use rust_decimal_macros::dec;
+
+    fn signer() -> OrderSigner {
+        OrderSigner::new(TEST_KEY, TEST_ADDRESS).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_gtc_order_has_no_expiration() {
+        let order = signer()
+            .create_order("1", dec!(0.48), dec!(100), Side::Buy, dec!(0.01), false)
+            .await
+            .unwrap();
+        assert_eq!(order.order_type, OrderType::Gtc);
+        assert_eq!(order.order.expiration, "0");
+    }
+
+    #[tokio::test]
+    async fn test_gtd_expiration_covers_security_threshold() {
+        let market = test_market();
+        let order = signer()
+            .create_gtd_order(&market, "1", dec!(0.48), dec!(10), Side::Buy, dec!(0.01))
+            .await
+            .unwrap();
+
+        // Live on the exchange until end_time - 10s, plus the 60s it subtracts
+        let expected = market.end_time.timestamp() - 10 + 60;
+        assert_eq!(order.order_type, OrderType::Gtd);
+        assert_eq!(order.order.expiration, expected.to_string());
+    }
+
+    #[tokio::test]
+    async fn test_gtd_rejects_market_ending_too_soon() {
+        let market = UpDownMarket {
+            end_time: chrono::Utc::now() + chrono::Duration::seconds(5),
+            ..test_market()
+        };
+        let err = signer()
+            .create_gtd_order(&market, "1", dec!(0.48), dec!(10), Side::Buy, dec!(0.01))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("ends too soon"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_orders_below_min_size_rejected() {
+        let market = test_market();
+        let err = signer()
+            .create_gtd_order(&market, "1", dec!(0.48), dec!(4), Side::Buy, dec!(0.01))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("below market minimum"), "{}", err);
+
+        // $2 at a 0.50 cap buys 4 shares, under the 5 share minimum
+        let err = signer()
+            .create_market_order(&market, "1", dec!(2), dec!(0.50), Side::Buy, OrderType::Fok, dec!(0.01))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("below market minimum"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_market_order_requires_fok_or_fak() {
+        let market = test_market();
+        for order_type in [OrderType::Gtc, OrderType::Gtd] {
+            let err = signer()
+                .create_market_order(&market, "1", dec!(10), dec!(0.50), Side::Buy, order_type, dec!(0.01))
+                .await
+                .unwrap_err();
+            assert!(err.to_string().contains("not a market order type"), "{}", err);
+        }
+
+        let order = signer()
+            .create_market_order(&market, "1", dec!(10), dec!(0.50), Side::Buy, OrderType::Fak, dec!(0.01))
+            .await
+            .unwrap();
+        assert_eq!(order.order_type, OrderType::Fak);
+        assert_eq!(order.order.expiration, "0");
+    }
+}
@@ -0,0 +1,575 @@
+//! Pluggable signing backends for `OrderSigner`
+//!
+//! The signer only ever needs two things from a key: its address and
+//! signatures over orders (and, for the emergency stop, `incrementNonce()`
+//! transactions). Keeping that behind a trait lets us pick where the key lives:
+//! - `LocalKeyBackend`: raw key in memory (PRIVATE_KEY, tests)
+//! - `KeystoreBackend`: encrypted JSON keystore (scrypt), passphrase from a
+//!   file or an interactive prompt
+//! - `RemoteSignerBackend`: key held by a separate process (`signer_tool serve`),
+//!   reached over a local Unix socket
+//!
+//! Remote protocol is newline-delimited JSON, one request per line:
+//!   -> {"method":"address"}                                       <- {"address":"0x.."}
+//!   -> {"method":"sign_order","exchange":"neg_risk","order":{..}}  <- {"signature":"0x.."}
+//!   -> {"method":"sign_increment_nonce","tx":{..}}                <- {"signature":"0x.."}
+//! Failures come back as {"error":"..."}. The signer hashes every payload
+//! itself and never signs a caller-supplied digest.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Address, Signature, H256};
+use ethers::utils::hex;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tracing::{info, warn};
+
+use crate::config::Config;
+use crate::eip712::{Exchange, OrderData, CHAIN_ID};
+use crate::nonce;
+
+/// Something that can sign digests for one address
+#[async_trait]
+pub trait SigningBackend: Send + Sync {
+    /// Address whose key produces the signatures
+    fn address(&self) -> Address;
+
+    /// Sign a 32-byte digest (no EIP-191 prefix)
+    async fn sign_hash(&self, hash: H256) -> Result<Signature>;
+
+    /// Sign an order's EIP-712 hash for an exchange
+    async fn sign_order(&self, order: &OrderData, exchange: Exchange) -> Result<Signature> {
+        self.sign_hash(H256::from(order.signing_hash(exchange))).await
+    }
+
+    /// Sign a transaction's sighash
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature> {
+        self.sign_hash(tx.sighash()).await
+    }
+
+    /// Short label for logs
+    fn describe(&self) -> String;
+}
+
+/// Build the backend selected in config
+///
+/// Priority: REMOTE_SIGNER_SOCKET, then KEYSTORE_PATH, then PRIVATE_KEY.
+pub async fn from_config(config: &Config) -> Result<Arc<dyn SigningBackend>> {
+    if let Some(socket) = &config.remote_signer_socket {
+        let backend = RemoteSignerBackend::connect(socket).await?;
+        return Ok(Arc::new(backend));
+    }
+
+    if let Some(path) = &config.keystore_path {
+        let passphrase = match &config.keystore_password_file {
+            Some(file) => read_passphrase_file(file)?,
+            None => prompt_passphrase(&format!("Passphrase for {}: ", path))?,
+        };
+        let backend = KeystoreBackend::open(path, &passphrase)?;
+        return Ok(Arc::new(backend));
+    }
+
+    if let Some(key) = &config.private_key {
+        warn!("Signing with plaintext PRIVATE_KEY - prefer KEYSTORE_PATH or REMOTE_SIGNER_SOCKET");
+        return Ok(Arc::new(LocalKeyBackend::new(key)?));
+    }
+
+    anyhow::bail!("No signing key configured (set REMOTE_SIGNER_SOCKET, KEYSTORE_PATH or PRIVATE_KEY)")
+}
+
+// ============================================================================
+// In-memory key
+// ============================================================================
+
+/// Raw private key held in process memory
+pub struct LocalKeyBackend {
+    wallet: LocalWallet,
+}
+
+impl LocalKeyBackend {
+    pub fn new(private_key: &str) -> Result<Self> {
+        let wallet = private_key
+            .parse::<LocalWallet>()
+            .context("Invalid private key")?;
+        Ok(Self::from_wallet(wallet))
+    }
+
+    pub fn from_wallet(wallet: LocalWallet) -> Self {
+        Self {
+            wallet: wallet.with_chain_id(CHAIN_ID),
+        }
+    }
+}
+
+#[async_trait]
+impl SigningBackend for LocalKeyBackend {
+    fn address(&self) -> Address {
+        self.wallet.address()
+    }
+
+    async fn sign_hash(&self, hash: H256) -> Result<Signature> {
+        Ok(self.wallet.sign_hash(hash)?)
+    }
+
+    fn describe(&self) -> String {
+        "in-memory key".to_string()
+    }
+}
+
+// ============================================================================
+// Encrypted keystore
+// ============================================================================
+
+/// Key decrypted once from an encrypted JSON keystore (Web3 Secret Storage, scrypt KDF)
+pub struct KeystoreBackend {
+    inner: LocalKeyBackend,
+    path: PathBuf,
+}
+
+impl KeystoreBackend {
+    pub fn open(path: impl AsRef<Path>, passphrase: &str) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let wallet = LocalWallet::decrypt_keystore(&path, passphrase)
+            .with_context(|| format!("Failed to decrypt keystore {} (wrong passphrase?)", path.display()))?;
+        Ok(Self {
+            inner: LocalKeyBackend::from_wallet(wallet),
+            path,
+        })
+    }
+
+    /// Encrypt a raw private key into a new keystore file in `dir`
+    ///
+    /// Returns the keystore path and its address.
+    pub fn create(dir: impl AsRef<Path>, private_key: &str, passphrase: &str) -> Result<(PathBuf, Address)> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+
+        let key = hex::decode(private_key.trim().trim_start_matches("0x")).context("Invalid private key hex")?;
+        let mut rng = ethers::core::rand::thread_rng();
+        let (wallet, uuid) = LocalWallet::encrypt_keystore(dir, &mut rng, key, passphrase, None)
+            .context("Failed to write keystore")?;
+        Ok((dir.join(uuid), wallet.address()))
+    }
+}
+
+#[async_trait]
+impl SigningBackend for KeystoreBackend {
+    fn address(&self) -> Address {
+        self.inner.address()
+    }
+
+    async fn sign_hash(&self, hash: H256) -> Result<Signature> {
+        self.inner.sign_hash(hash).await
+    }
+
+    fn describe(&self) -> String {
+        format!("keystore {}", self.path.display())
+    }
+}
+
+/// Read a passphrase from a file (first line, trailing newline stripped)
+pub fn read_passphrase_file(path: &str) -> Result<String> {
+    let content = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))?;
+    Ok(content.lines().next().unwrap_or_default().to_string())
+}
+
+/// Prompt for a passphrase on the terminal without echoing it
+pub fn prompt_passphrase(prompt: &str) -> Result<String> {
+    eprint!("{}", prompt);
+    std::io::stderr().flush().ok();
+
+    // Best effort: if stdin isn't a tty, stty fails and we just read the line
+    let echo_off = set_tty_echo(false);
+    let mut line = String::new();
+    let read = std::io::stdin().lock().read_line(&mut line);
+    if echo_off {
+        set_tty_echo(true);
+        eprintln!();
+    }
+    read.context("Failed to read passphrase")?;
+
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+fn set_tty_echo(on: bool) -> bool {
+    std::process::Command::new("stty")
+        .arg(if on { "echo" } else { "-echo" })
+        .stdin(std::process::Stdio::inherit())
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
+
+// ============================================================================
+// Remote signer
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+enum SignerRequest {
+    Address,
+    SignOrder { exchange: Exchange, order: OrderData },
+    // ethers doesn't serialize the chain id, so it travels alongside
+    SignIncrementNonce { tx: TypedTransaction, chain_id: u64 },
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SignerResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signature: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Key held by a separate signer process, reached over a Unix socket
+///
+/// Opens a fresh connection per request so a restarted signer is picked up
+/// without reconnect logic. Every signature is checked against the address
+/// reported at connect time. Only orders and `incrementNonce()` transactions
+/// can be signed; raw digests are refused.
+pub struct RemoteSignerBackend {
+    socket_path: PathBuf,
+    address: Address,
+}
+
+impl RemoteSignerBackend {
+    pub async fn connect(socket_path: impl AsRef<Path>) -> Result<Self> {
+        let socket_path = socket_path.as_ref().to_path_buf();
+        let response = request(&socket_path, &SignerRequest::Address).await?;
+        let address = response
+            .address
+            .context("Remote signer returned no address")?;
+        let address = Address::from_str(&address).context("Remote signer returned invalid address")?;
+
+        info!("Connected to remote signer at {} ({:?})", socket_path.display(), address);
+        Ok(Self { socket_path, address })
+    }
+
+    /// Send a signing request and check the signature recovers to our address
+    async fn remote_sign(&self, req: &SignerRequest, hash: H256) -> Result<Signature> {
+        let response = request(&self.socket_path, req).await?;
+        let signature = response
+            .signature
+            .context("Remote signer returned no signature")?;
+        let signature = Signature::from_str(&signature).context("Remote signer returned malformed signature")?;
+
+        let recovered = signature.recover(hash).context("Remote signature does not recover")?;
+        if recovered != self.address {
+            anyhow::bail!(
+                "Remote signer signed with {:?}, expected {:?}",
+                recovered,
+                self.address
+            );
+        }
+        Ok(signature)
+    }
+}
+
+#[async_trait]
+impl SigningBackend for RemoteSignerBackend {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    async fn sign_hash(&self, _hash: H256) -> Result<Signature> {
+        anyhow::bail!("Remote signer only signs orders and incrementNonce transactions")
+    }
+
+    async fn sign_order(&self, order: &OrderData, exchange: Exchange) -> Result<Signature> {
+        let req = SignerRequest::SignOrder {
+            exchange,
+            order: order.clone(),
+        };
+        self.remote_sign(&req, H256::from(order.signing_hash(exchange))).await
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature> {
+        let chain_id = tx.chain_id().context("Transaction has no chain id")?.low_u64();
+        let req = SignerRequest::SignIncrementNonce {
+            tx: tx.clone(),
+            chain_id,
+        };
+        self.remote_sign(&req, tx.sighash()).await
+    }
+
+    fn describe(&self) -> String {
+        format!("remote signer {}", self.socket_path.display())
+    }
+}
+
+async fn request(socket_path: &Path, req: &SignerRequest) -> Result<SignerResponse> {
+    let stream = UnixStream::connect(socket_path)
+        .await
+        .with_context(|| format!("Failed to connect to signer socket {}", socket_path.display()))?;
+    let (read_half, mut write_half) = stream.into_split();
+
+    let mut line = serde_json::to_string(req)?;
+    line.push('\n');
+    write_half.write_all(line.as_bytes()).await?;
+
+    let mut reply = String::new();
+    BufReader::new(read_half).read_line(&mut reply).await?;
+    if reply.is_empty() {
+        anyhow::bail!("Remote signer closed the connection");
+    }
+
+    let response: SignerResponse = serde_json::from_str(&reply).context("Invalid remote signer response")?;
+    if let Some(error) = response.error {
+        anyhow::bail!("Remote signer error: {}", error);
+    }
+    Ok(response)
+}
+
+/// Serve a backend over a Unix socket (runs until the task is dropped)
+///
+/// The socket is bound inside a fresh 0700 directory, made owner-only (0600)
+/// and only then renamed into place, so other local users can never connect.
+pub async fn serve_remote_signer(socket_path: impl AsRef<Path>, backend: Arc<dyn SigningBackend>) -> Result<()> {
+    let socket_path = socket_path.as_ref();
+    if socket_path.exists() {
+        std::fs::remove_file(socket_path)
+            .with_context(|| format!("Failed to remove stale socket {}", socket_path.display()))?;
+    }
+
+    let listener = bind_private(socket_path)?;
+
+    info!(
+        "Remote signer for {:?} listening on {} ({})",
+        backend.address(),
+        socket_path.display(),
+        backend.describe()
+    );
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let backend = backend.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, backend).await {
+                warn!("Remote signer connection error: {}", e);
+            }
+        });
+    }
+}
+
+/// Bind a Unix socket that is owner-only from the moment it exists
+fn bind_private(socket_path: &Path) -> Result<UnixListener> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+
+    let parent = match socket_path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let private_dir = parent.join(format!(".signer-{}", uuid::Uuid::new_v4()));
+    std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&private_dir)
+        .with_context(|| format!("Failed to create {}", private_dir.display()))?;
+
+    let staged = private_dir.join("signer.sock");
+    let result = (|| {
+        let listener = UnixListener::bind(&staged)
+            .with_context(|| format!("Failed to bind {}", socket_path.display()))?;
+        std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o600))?;
+        std::fs::rename(&staged, socket_path)
+            .with_context(|| format!("Failed to move socket to {}", socket_path.display()))?;
+        Ok(listener)
+    })();
+    let _ = std::fs::remove_dir_all(&private_dir);
+    result
+}
+
+fn signature_response(result: Result<Signature>) -> SignerResponse {
+    match result {
+        Ok(signature) => SignerResponse {
+            signature: Some(format!("0x{}", hex::encode(signature.to_vec()))),
+            ..Default::default()
+        },
+        Err(e) => SignerResponse {
+            error: Some(e.to_string()),
+            ..Default::default()
+        },
+    }
+}
+
+async fn handle_connection(stream: UnixStream, backend: Arc<dyn SigningBackend>) -> Result<()> {
+    let (read_half, mut write_half) = stream.into_split();
+    let mut lines = BufReader::new(read_half).lines();
+
+    while let Some(line) = lines.next_line().await? {
+        let response = match serde_json::from_str::<SignerRequest>(&line) {
+            Ok(SignerRequest::Address) => SignerResponse {
+                address: Some(format!("{:?}", backend.address())),
+                ..Default::default()
+            },
+            Ok(SignerRequest::SignOrder { exchange, order }) => {
+                let result = if order.signer != backend.address() {
+                    Err(anyhow::anyhow!("order signer {:?} is not this key", order.signer))
+                } else {
+                    backend.sign_order(&order, exchange).await
+                };
+                if result.is_ok() {
+                    info!("Signed {:?} order, token {} salt {}", exchange, order.token_id, order.salt);
+                }
+                signature_response(result)
+            }
+            Ok(SignerRequest::SignIncrementNonce { mut tx, chain_id }) => {
+                tx.set_chain_id(chain_id);
+                let result = match nonce::check_increment_nonce_tx(&tx) {
+                    Ok(()) => backend.sign_transaction(&tx).await,
+                    Err(e) => Err(e),
+                };
+                if result.is_ok() {
+                    info!("Signed incrementNonce to {:?}", tx.to_addr());
+                }
+                signature_response(result)
+            }
+            Err(e) => SignerResponse {
+                error: Some(format!("bad request: {}", e)),
+                ..Default::default()
+            },
+        };
+
+        let mut out = serde_json::to_string(&response)?;
+        out.push('\n');
+        write_half.write_all(out.as_bytes()).await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TEST_ADDRESS, TEST_KEY};
+    use ethers::types::U256;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("{}-{}", name, uuid::Uuid::new_v4()))
+    }
+
+    fn test_order(signer: Address) -> OrderData {
+        OrderData {
+            salt: 12345.into(),
+            maker: signer,
+            signer,
+            taker: Address::zero(),
+            token_id: U256::from_dec_str("71321045679252212594626385532706912750332728571942532289631379312455583992563").unwrap(),
+            maker_amount: 5_000_000.into(),
+            taker_amount: 10_000_000.into(),
+            expiration: 0.into(),
+            nonce: 0.into(),
+            fee_rate_bps: 0.into(),
+            side: 0,
+            signature_type: 0,
+        }
+    }
+
+    async fn raw_request(socket: &Path, req: &serde_json::Value) -> SignerResponse {
+        let stream = UnixStream::connect(socket).await.unwrap();
+        let (read_half, mut write_half) = stream.into_split();
+        write_half.write_all(format!("{}\n", req).as_bytes()).await.unwrap();
+        let mut reply = String::new();
+        BufReader::new(read_half).read_line(&mut reply).await.unwrap();
+        serde_json::from_str(&reply).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_remote_signer_round_trip() {
+        let local: Arc<dyn SigningBackend> = Arc::new(LocalKeyBackend::new(TEST_KEY).unwrap());
+        let socket = temp_path("signer.sock");
+
+        let server = tokio::spawn(serve_remote_signer(socket.clone(), local.clone()));
+        while !socket.exists() {
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+
+        let remote = RemoteSignerBackend::connect(&socket).await.unwrap();
+        assert_eq!(remote.address(), Address::from_str(TEST_ADDRESS).unwrap());
+
+        let order = test_order(remote.address());
+        let expected = local.sign_order(&order, Exchange::NegRisk).await.unwrap();
+        let signature = remote.sign_order(&order, Exchange::NegRisk).await.unwrap();
+        assert_eq!(signature, expected);
+
+        let tx = nonce::build_increment_nonce_tx(
+            Exchange::Ctf.address(),
+            remote.address(),
+            1.into(),
+            1.into(),
+            1.into(),
+            CHAIN_ID,
+        );
+        let expected = local.sign_transaction(&tx).await.unwrap();
+        assert_eq!(remote.sign_transaction(&tx).await.unwrap(), expected);
+
+        server.abort();
+        let _ = std::fs::remove_file(&socket);
+    }
+
+    #[tokio::test]
+    async fn test_remote_signer_refuses_untyped_payloads() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let local: Arc<dyn SigningBackend> = Arc::new(LocalKeyBackend::new(TEST_KEY).unwrap());
+        let socket = temp_path("signer.sock");
+
+        let server = tokio::spawn(serve_remote_signer(socket.clone(), local));
+        while !socket.exists() {
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+        let mode = std::fs::metadata(&socket).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        let remote = RemoteSignerBackend::connect(&socket).await.unwrap();
+        assert!(remote.sign_hash(H256::repeat_byte(0x42)).await.is_err());
+
+        // Raw digests are not part of the protocol
+        let raw: serde_json::Value = serde_json::from_str(r#"{"method":"sign_hash","hash":"0x42"}"#).unwrap();
+        let reply = raw_request(&socket, &raw).await;
+        assert!(reply.error.unwrap().starts_with("bad request"));
+
+        // Orders for another key are refused
+        let order = test_order(Address::repeat_byte(0x11));
+        let err = remote.sign_order(&order, Exchange::Ctf).await.unwrap_err();
+        assert!(err.to_string().contains("is not this key"));
+
+        // Transactions other than incrementNonce() on an exchange are refused
+        let mut tx = nonce::build_increment_nonce_tx(
+            Address::repeat_byte(0x22),
+            remote.address(),
+            1.into(),
+            1.into(),
+            1.into(),
+            CHAIN_ID,
+        );
+        assert!(remote.sign_transaction(&tx).await.is_err());
+        tx.set_to(Exchange::Ctf.address());
+        tx.set_data(nonce::nonces_calldata(remote.address()));
+        assert!(remote.sign_transaction(&tx).await.is_err());
+
+        server.abort();
+        let _ = std::fs::remove_file(&socket);
+    }
+
+    #[tokio::test]
+    async fn test_keystore_round_trip() {
+        let dir = temp_path("keystore");
+        let (path, address) = KeystoreBackend::create(&dir, TEST_KEY, "hunter2").unwrap();
+        assert_eq!(address, Address::from_str(TEST_ADDRESS).unwrap());
+
+        assert!(KeystoreBackend::open(&path, "wrong").is_err());
+        let backend = KeystoreBackend::open(&path, "hunter2").unwrap();
+        assert_eq!(backend.address(), address);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,208 @@
+//! Spot order-book and trade-flow signals
+//!
+//! Polymarket's up/down books tend to follow spot order flow by a few
+//! seconds. Sources that stream a book (`coinbase_l2`, `binance_flow`)
+//! report their top of book, and every source that streams trades reports
+//! aggressor side and size; [`SpotFlow`] turns those into microprice,
+//! top-of-book imbalance and signed volume across exchanges.
+
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::price_source::STALE_AFTER;
+use crate::types::Asset;
+
+/// Signed volume window for short-term pressure
+pub const FLOW_SHORT: Duration = Duration::from_secs(10);
+/// Longest signed volume window (and how long trades are kept)
+pub const FLOW_LONG: Duration = Duration::from_secs(60);
+
+/// Spot exchange best bid and ask with their sizes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpotTop {
+    pub bid: Decimal,
+    pub bid_size: Decimal,
+    pub ask: Decimal,
+    pub ask_size: Decimal,
+}
+
+impl SpotTop {
+    pub fn mid(&self) -> Decimal {
+        (self.bid + self.ask) / dec!(2)
+    }
+
+    /// Size-weighted mid: leans towards the side with less size, where the
+    /// next trade is more likely to move the price
+    pub fn microprice(&self) -> Decimal {
+        let total = self.bid_size + self.ask_size;
+        if total.is_zero() {
+            return self.mid();
+        }
+        (self.bid * self.ask_size + self.ask * self.bid_size) / total
+    }
+
+    /// (bid size - ask size) / total, in [-1, 1]; positive is buy pressure
+    pub fn imbalance(&self) -> Decimal {
+        let total = self.bid_size + self.ask_size;
+        if total.is_zero() {
+            return Decimal::ZERO;
+        }
+        (self.bid_size - self.ask_size) / total
+    }
+}
+
+/// Snapshot of the flow signals
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FlowSignals {
+    /// Asset the volumes are denominated in
+    pub asset: Asset,
+    /// Microprice of the most recently updated book
+    pub microprice: Option<Decimal>,
+    /// Mean (microprice - mid) / mid across fresh books, in basis points
+    pub microprice_bps: Option<Decimal>,
+    /// Mean top-of-book imbalance across fresh books
+    pub imbalance: Option<Decimal>,
+    /// Aggressive buys minus sells in units of `asset`, across exchanges
+    pub signed_volume_short: Decimal,
+    pub signed_volume_long: Decimal,
+    pub trades_long: usize,
+    /// Books fresh enough to count
+    pub books: usize,
+}
+
+impl FlowSignals {
+    /// Whether book and tape both lean Up (Some(true)), both Down
+    /// (Some(false)), or disagree / have no data (None)
+    pub fn direction(&self) -> Option<bool> {
+        let book = self.imbalance?;
+        let tape = self.signed_volume_short;
+        if book > Decimal::ZERO && tape > Decimal::ZERO {
+            Some(true)
+        } else if book < Decimal::ZERO && tape < Decimal::ZERO {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+impl std::fmt::Display for FlowSignals {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let opt = |v: Option<Decimal>| v.map(|v| format!("{:+}", v)).unwrap_or_else(|| "-".to_string());
+        write!(
+            f,
+            "Spot flow: imbalance {} | microprice {}bps ({} books) | signed vol {:+.3} {} 10s, {:+.3} {} 60s ({} trades)",
+            opt(self.imbalance),
+            opt(self.microprice_bps),
+            self.books,
+            self.signed_volume_short,
+            self.asset,
+            self.signed_volume_long,
+            self.asset,
+            self.trades_long
+        )
+    }
+}
+
+/// Per-source books and a cross-exchange trade window
+#[derive(Debug, Default)]
+pub struct SpotFlow {
+    books: Vec<Option<(SpotTop, Instant)>>,
+    /// (receive time, size signed by aggressor side)
+    trades: VecDeque<(Instant, Decimal)>,
+}
+
+impl SpotFlow {
+    pub fn update_book(&mut self, index: usize, top: SpotTop, now: Instant) {
+        if self.books.len() <= index {
+            self.books.resize(index + 1, None);
+        }
+        self.books[index] = Some((top, now));
+    }
+
+    pub fn record_trade(&mut self, size: Decimal, buy: bool, now: Instant) {
+        self.trades.push_back((now, if buy { size } else { -size }));
+        while self.trades.front().is_some_and(|(t, _)| now.saturating_duration_since(*t) > FLOW_LONG) {
+            self.trades.pop_front();
+        }
+    }
+
+    /// Aggressive buys minus sells over the last `window`
+    pub fn signed_volume(&self, window: Duration, now: Instant) -> Decimal {
+        self.recent(window, now).map(|(_, v)| v).sum()
+    }
+
+    fn recent(&self, window: Duration, now: Instant) -> impl Iterator<Item = &(Instant, Decimal)> {
+        self.trades.iter().rev().take_while(move |(t, _)| now.saturating_duration_since(*t) <= window)
+    }
+
+    pub fn signals(&self, asset: Asset, now: Instant) -> FlowSignals {
+        let fresh: Vec<&(SpotTop, Instant)> = self
+            .books
+            .iter()
+            .flatten()
+            .filter(|(_, t)| now.saturating_duration_since(*t) < STALE_AFTER)
+            .collect();
+        let mean = |f: &dyn Fn(&SpotTop) -> Decimal| {
+            (!fresh.is_empty()).then(|| fresh.iter().map(|(top, _)| f(top)).sum::<Decimal>() / Decimal::from(fresh.len()))
+        };
+
+        FlowSignals {
+            asset,
+            microprice: fresh.iter().max_by_key(|(_, t)| *t).map(|(top, _)| top.microprice()),
+            microprice_bps: mean(&|top| (top.microprice() - top.mid()) / top.mid() * dec!(10000)).map(|b| b.round_dp(3)),
+            imbalance: mean(&|top| top.imbalance()).map(|i| i.round_dp(4)),
+            signed_volume_short: self.signed_volume(FLOW_SHORT, now),
+            signed_volume_long: self.signed_volume(FLOW_LONG, now),
+            trades_long: self.recent(FLOW_LONG, now).count(),
+            books: fresh.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_book_top_signals() {
+        // 3 BTC bid vs 1 BTC offered: microprice sits 3/4 of the way to the ask
+        let top = SpotTop { bid: dec!(100000), bid_size: dec!(3), ask: dec!(100010), ask_size: dec!(1) };
+        assert_eq!(top.mid(), dec!(100005));
+        assert_eq!(top.microprice(), dec!(100007.5));
+        assert_eq!(top.imbalance(), dec!(0.5));
+    }
+
+    #[test]
+    fn test_flow_across_sources_and_windows() {
+        let base = Instant::now();
+        let at = |secs: u64| base + Duration::from_secs(secs);
+        let mut flow = SpotFlow::default();
+
+        flow.update_book(1, SpotTop { bid: dec!(100000), bid_size: dec!(3), ask: dec!(100010), ask_size: dec!(1) }, at(58));
+        flow.update_book(0, SpotTop { bid: dec!(99990), bid_size: dec!(1), ask: dec!(100000), ask_size: dec!(1) }, at(59));
+        flow.update_book(2, SpotTop { bid: dec!(1), bid_size: dec!(1), ask: dec!(2), ask_size: dec!(9) }, at(40));
+
+        flow.record_trade(dec!(2), false, at(0));
+        flow.record_trade(dec!(0.5), false, at(30));
+        flow.record_trade(dec!(1.5), true, at(55));
+        flow.record_trade(dec!(0.25), true, at(60));
+
+        let signals = flow.signals(Asset::Btc, at(60));
+        // Stale book (source 2) left out; freshest is source 0
+        assert_eq!(signals.books, 2);
+        assert_eq!(signals.microprice, Some(dec!(99995)));
+        assert_eq!(signals.imbalance, Some(dec!(0.25)));
+        // The trade at t=0 is exactly 60s old and still counts
+        assert_eq!(signals.signed_volume_long, dec!(-0.75));
+        assert_eq!(signals.trades_long, 4);
+        assert_eq!(signals.signed_volume_short, dec!(1.75));
+        assert_eq!(signals.direction(), Some(true));
+
+        // Expiry drops the oldest trade
+        flow.record_trade(dec!(0.1), true, at(61));
+        assert_eq!(flow.signed_volume(FLOW_LONG, at(61)), dec!(1.35));
+    }
+}
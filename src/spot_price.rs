@@ -0,0 +1,611 @@
+//! Spot Price Feed from Multiple Exchanges
+//!
+//! Provides real-time spot price tracking for a market's underlying (BTC,
+//! ETH, SOL or XRP) from a configurable set of exchanges.
+//! This is the KEY difference from our old strategy:
+//! - Old: Track Polymarket prices (circular logic)
+//! - New: Track the actual spot price (what determines the outcome!)
+//!
+//! Multi-exchange benefits:
+//! - Robustness: the price is a freshness-weighted median across USD sources
+//! - Cross-validation: fresh USD sources must agree on direction and price
+//! - Redundancy: if one exchange fails, the others carry the price
+//!
+//! Exchange connections live in `price_source`; this module owns the shared
+//! state and the signals derived from it.
+//!
+//! Window opens are captured by the feed itself: the composite in effect at
+//! each 15-minute boundary, judged by exchange timestamps, is kept per
+//! window so a session that starts late still compares against the real open.
+
+use anyhow::Result;
+use parking_lot::RwLock;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{error, info, warn};
+
+use crate::fair_value::FairValueModel;
+use crate::indicators::{CandleSet, SpotIndicators};
+use crate::price_history::{PriceHistory, PricePoint};
+use crate::price_source::{
+    self, composite_price, source_health, PriceSink, PriceSource, QuoteCurrency, SourceHealth, SourceQuote, SpotTick, TickKind,
+    STALE_AFTER,
+};
+use crate::spot_flow::{FlowSignals, SpotFlow};
+use crate::supervisor::Supervisor;
+use crate::types::{boundaries_between, boundary_ms, Asset};
+
+/// Spot price update event
+#[derive(Debug, Clone)]
+pub struct SpotPriceUpdate {
+    pub price: Decimal,
+    pub timestamp: u64,
+}
+
+/// Short momentum window
+pub const ROC_SHORT: Duration = Duration::from_secs(10);
+/// Long momentum window
+pub const ROC_LONG: Duration = Duration::from_secs(30);
+/// Trend baseline (price above its 1m average = bullish)
+pub const TREND_WINDOW: Duration = Duration::from_secs(60);
+/// History kept for momentum and window-open lookups
+const HISTORY_HORIZON: Duration = Duration::from_secs(20 * 60);
+/// Hard cap on history points (~80/s over the horizon)
+const MAX_HISTORY_POINTS: usize = 100_000;
+/// Window opens kept (one day)
+const MAX_WINDOW_OPENS: usize = 96;
+
+/// Composite price in effect at a window boundary
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowOpen {
+    pub price: Decimal,
+    /// Event time (exchange timestamp where the source has one) of the last
+    /// update at or before the boundary, and of the first one after it
+    pub last_tick_ms: i64,
+    pub next_tick_ms: i64,
+}
+
+/// Spot price feed state with momentum tracking
+#[derive(Debug)]
+pub struct SpotPriceState {
+    /// Current spot price (composite across sources)
+    pub current_price: Decimal,
+    /// Price at market open (set when new 15-min period starts)
+    pub market_open_price: Option<Decimal>,
+    /// Timestamp of last composite update
+    pub last_update: Instant,
+    /// Composite price history, indexed by receive time
+    pub history: PriceHistory,
+    /// Composite 1s/5s/1m candles; volume is trades from every source
+    pub candles: CandleSet,
+    /// Latest quote from each configured source, in config order
+    pub sources: Vec<SourceQuote>,
+    /// Book imbalance and trade flow from sources that stream them
+    pub flow: SpotFlow,
+    /// Captured open per window start (unix seconds)
+    pub window_opens: BTreeMap<i64, WindowOpen>,
+    /// Latest event time applied, for boundary detection
+    last_event_ms: Option<i64>,
+}
+
+impl Default for SpotPriceState {
+    fn default() -> Self {
+        Self {
+            current_price: Decimal::ZERO,
+            market_open_price: None,
+            last_update: Instant::now(),
+            history: PriceHistory::new(HISTORY_HORIZON, MAX_HISTORY_POINTS),
+            candles: CandleSet::default(),
+            sources: Vec::new(),
+            flow: SpotFlow::default(),
+            window_opens: BTreeMap::new(),
+            last_event_ms: None,
+        }
+    }
+}
+
+impl SpotPriceState {
+    /// Record a source's price and recompute the composite
+    pub fn apply_quote(&mut self, index: usize, tick: &SpotTick, now: Instant) {
+        let Some(quote) = self.sources.get_mut(index) else { return };
+        quote.price = Some(tick.price);
+        quote.updated_at = Some(now);
+        quote.connected = true;
+        quote.updates += 1;
+
+        let event_ms = tick.exchange_ts_ms.unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
+        self.capture_window_open(event_ms);
+
+        match tick.kind {
+            TickKind::Trade { size, buy } => self.flow.record_trade(size, buy, now),
+            TickKind::Book(top) => self.flow.update_book(index, top, now),
+            TickKind::Ticker => {}
+        }
+
+        if let Some(composite) = composite_price(&self.sources, now) {
+            self.current_price = composite;
+            self.last_update = now;
+            // Track for momentum
+            self.history.push(PricePoint {
+                exchange_ts_ms: tick.exchange_ts_ms,
+                local_ts: now,
+                price: composite,
+                source: index,
+            });
+            let volume = match tick.kind {
+                TickKind::Trade { size, .. } => size,
+                _ => Decimal::ZERO,
+            };
+            self.candles.update(chrono::Utc::now().timestamp_millis(), composite, volume);
+        }
+    }
+
+    /// Before applying an update stamped `event_ms`, record the current
+    /// composite as the open of every boundary since the previous update
+    ///
+    /// Updates stamped at or before the latest one (a slower source catching
+    /// up) don't move the clock. A boundary more than [`STALE_AFTER`] past the
+    /// previous update isn't captured: that price is too old to be the open,
+    /// so `mark_market_open` falls back instead.
+    fn capture_window_open(&mut self, event_ms: i64) {
+        let Some(last_ms) = self.last_event_ms else {
+            self.last_event_ms = Some(event_ms);
+            return;
+        };
+        if event_ms <= last_ms {
+            return;
+        }
+        self.last_event_ms = Some(event_ms);
+        if self.current_price.is_zero() {
+            return;
+        }
+        let stale_ms = STALE_AFTER.as_millis() as i64;
+        for start in boundaries_between(last_ms, event_ms - 1) {
+            if boundary_ms(start) - last_ms > stale_ms {
+                continue;
+            }
+            self.window_opens.insert(
+                start,
+                WindowOpen { price: self.current_price, last_tick_ms: last_ms, next_tick_ms: event_ms },
+            );
+        }
+        while self.window_opens.len() > MAX_WINDOW_OPENS {
+            self.window_opens.pop_first();
+        }
+    }
+
+    pub fn set_source_connected(&mut self, index: usize, connected: bool) {
+        if let Some(quote) = self.sources.get_mut(index) {
+            quote.connected = connected;
+        }
+    }
+
+    /// At least one source is fresh
+    pub fn is_connected(&self) -> bool {
+        let now = Instant::now();
+        self.sources.iter().any(|q| q.is_fresh(now))
+    }
+
+    /// Get rate of change (momentum) in percent over the last `window`
+    pub fn get_roc(&self, window: Duration) -> Option<Decimal> {
+        self.history.roc(window, Instant::now())
+    }
+
+    /// Get time-weighted average price over the last `window`
+    pub fn get_sma(&self, window: Duration) -> Option<Decimal> {
+        self.history.sma(window, Instant::now())
+    }
+
+    /// Check if price is trending (above 1m SMA = bullish, below = bearish)
+    pub fn is_trending_up(&self) -> Option<bool> {
+        let sma = self.get_sma(TREND_WINDOW)?;
+        Some(self.current_price > sma)
+    }
+
+    /// Get momentum strength (0-100)
+    pub fn get_momentum_strength(&self) -> Option<Decimal> {
+        let roc_short = self.get_roc(ROC_SHORT)?;
+        let roc_long = self.get_roc(ROC_LONG)?;
+
+        // Combine short and long term momentum
+        let combined = (roc_short.abs() + roc_long.abs()) * dec!(50);
+        Some(combined.min(dec!(100)))
+    }
+}
+
+/// Spot price feed for one asset - streams from the configured exchange sources
+pub struct SpotPriceFeed {
+    asset: Asset,
+    state: Arc<RwLock<SpotPriceState>>,
+    sources: Vec<Arc<dyn PriceSource>>,
+}
+
+impl SpotPriceFeed {
+    /// BTC feed with no sources; prices only arrive through `state()`
+    pub fn new() -> Self {
+        Self::with_sources(Asset::Btc, Vec::new())
+    }
+
+    pub fn with_sources(asset: Asset, sources: Vec<Arc<dyn PriceSource>>) -> Self {
+        let state = SpotPriceState {
+            sources: sources.iter().map(|s| SourceQuote::new(s.name(), s.quote())).collect(),
+            ..Default::default()
+        };
+        Self { asset, state: Arc::new(RwLock::new(state)), sources }
+    }
+
+    pub fn asset(&self) -> Asset {
+        self.asset
+    }
+
+    /// Get shared state handle
+    pub fn state(&self) -> Arc<RwLock<SpotPriceState>> {
+        self.state.clone()
+    }
+
+    /// Get current spot price
+    pub fn get_price(&self) -> Decimal {
+        self.state.read().current_price
+    }
+
+    /// Check if any source is connected and fresh
+    pub fn is_connected(&self) -> bool {
+        self.state.read().is_connected()
+    }
+
+    /// Open captured at the boundary starting `window_start` (unix seconds)
+    pub fn window_open(&self, window_start: i64) -> Option<WindowOpen> {
+        self.state.read().window_opens.get(&window_start).cloned()
+    }
+
+    /// Set the market open price for the window starting at `window_start`
+    ///
+    /// Uses the price captured at the boundary; if the feed wasn't running
+    /// then, falls back to the current price.
+    pub fn mark_market_open(&self, window_start: i64) {
+        let mut state = self.state.write();
+        match state.window_opens.get(&window_start).cloned() {
+            Some(open) => {
+                state.market_open_price = Some(open.price);
+                info!(
+                    "Marked market open {} price: ${} (at boundary, last tick {}ms before, next {}ms after)",
+                    self.asset,
+                    open.price.round_dp(2),
+                    boundary_ms(window_start) - open.last_tick_ms,
+                    open.next_tick_ms - boundary_ms(window_start)
+                );
+            }
+            None => {
+                state.market_open_price = Some(state.current_price);
+                warn!(
+                    "No {} price captured at window start {}, using current price ${} as the open",
+                    self.asset,
+                    window_start,
+                    state.current_price.round_dp(2)
+                );
+            }
+        }
+    }
+
+    /// Open price of the current market, once marked
+    pub fn get_market_open_price(&self) -> Option<Decimal> {
+        self.state.read().market_open_price
+    }
+
+    /// Clear market open price (call when market resolves)
+    pub fn clear_market_open(&self) {
+        self.state.write().market_open_price = None;
+    }
+
+    /// Get the predicted outcome based on spot price movement
+    /// Returns Some(true) for UP, Some(false) for DOWN, None if no movement
+    pub fn get_predicted_outcome(&self) -> Option<bool> {
+        let state = self.state.read();
+        let open = state.market_open_price?;
+        let now = state.current_price;
+
+        if now > open {
+            Some(true) // UP
+        } else if now < open {
+            Some(false) // DOWN
+        } else {
+            None // No movement
+        }
+    }
+
+    /// Get price change since market open (in dollars)
+    pub fn get_price_change(&self) -> Option<Decimal> {
+        let state = self.state.read();
+        let open = state.market_open_price?;
+        Some(state.current_price - open)
+    }
+
+    /// Get price change percentage since market open
+    pub fn get_price_change_pct(&self) -> Option<Decimal> {
+        let state = self.state.read();
+        let open = state.market_open_price?;
+        if open == Decimal::ZERO {
+            return None;
+        }
+        Some((state.current_price - open) / open * dec!(100))
+    }
+
+    /// Get confidence level (0-100) based on magnitude of price change
+    /// Higher confidence = larger spot move = more certain outcome
+    pub fn get_confidence(&self) -> Decimal {
+        let pct_change = self.get_price_change_pct().unwrap_or(Decimal::ZERO).abs();
+        // Scale: 0.01% = low confidence, 0.1% = high confidence
+        // Cap at 100
+        (pct_change * dec!(1000)).min(dec!(100))
+    }
+
+    /// Get momentum-enhanced confidence (combines price change + momentum)
+    pub fn get_momentum_confidence(&self) -> Decimal {
+        let base_conf = self.get_confidence();
+        let price_dir_up = self.get_predicted_outcome().unwrap_or(true);
+        let state = self.state.read();
+
+        if let Some(momentum) = state.get_momentum_strength() {
+            // Boost confidence if momentum confirms direction
+            let roc = state.get_roc(ROC_SHORT).unwrap_or(Decimal::ZERO);
+            let momentum_confirms = (roc > Decimal::ZERO && price_dir_up)
+                                  || (roc < Decimal::ZERO && !price_dir_up);
+
+            if momentum_confirms {
+                (base_conf + momentum * dec!(0.5)).min(dec!(100))
+            } else {
+                // Momentum diverges - reduce confidence
+                (base_conf - momentum * dec!(0.3)).max(Decimal::ZERO)
+            }
+        } else {
+            base_conf
+        }
+    }
+
+    /// Check if current move is supported by momentum
+    pub fn is_momentum_aligned(&self) -> bool {
+        let price_dir = self.get_predicted_outcome();
+        let state = self.state.read();
+        let roc = state.get_roc(ROC_SHORT).unwrap_or(Decimal::ZERO);
+        let sma_trend = state.is_trending_up();
+
+        match (price_dir, sma_trend) {
+            (Some(true), Some(true)) => roc > Decimal::ZERO,  // UP with bullish momentum
+            (Some(false), Some(false)) => roc < Decimal::ZERO, // DOWN with bearish momentum
+            _ => false
+        }
+    }
+
+    /// Candle-based indicators on the composite
+    pub fn indicators(&self) -> SpotIndicators {
+        SpotIndicators::from_candles(&self.state.read().candles)
+    }
+
+    /// Fair probability the window closes Up, from the move since open and
+    /// realized 5m volatility (None before the open is marked)
+    pub fn fair_up_prob(&self, model: &FairValueModel, seconds_left: i64) -> Option<Decimal> {
+        let vol = self.indicators().vol_5m_pct;
+        let state = self.state.read();
+        model.up_prob(state.market_open_price?, state.current_price, seconds_left, vol)
+    }
+
+    /// Microprice, book imbalance and signed trade volume across sources
+    pub fn flow(&self) -> FlowSignals {
+        self.state.read().flow.signals(self.asset, Instant::now())
+    }
+
+    /// Health, lag and divergence from the composite for every source
+    pub fn source_stats(&self) -> Vec<SourceHealth> {
+        source_health(&self.state.read().sources, Instant::now())
+    }
+
+    /// Check that every fresh USD source agrees on direction since open (cross-validation)
+    pub fn sources_agree(&self) -> bool {
+        let state = self.state.read();
+        let open = match state.market_open_price {
+            Some(p) => p,
+            None => return true, // No open yet, assume agree
+        };
+
+        let now = Instant::now();
+        let sides: Vec<Ordering> = state
+            .sources
+            .iter()
+            .filter(|q| q.quote == QuoteCurrency::Usd && q.is_fresh(now))
+            .filter_map(|q| q.price)
+            .map(|p| p.cmp(&open))
+            .collect();
+
+        match sides.split_first() {
+            // All must be strictly up or strictly down
+            Some((first, rest)) if !rest.is_empty() => *first != Ordering::Equal && rest.iter().all(|s| s == first),
+            _ => true, // One source or none, nothing to cross-check
+        }
+    }
+
+    /// Largest absolute divergence from the composite across fresh USD sources, in bps
+    pub fn max_divergence_bps(&self) -> Option<Decimal> {
+        self.source_stats()
+            .iter()
+            .filter(|h| h.healthy && h.quote == QuoteCurrency::Usd)
+            .filter_map(|h| h.divergence_bps)
+            .map(|d| d.abs())
+            .max()
+    }
+
+    pub fn log_source_stats(&self) {
+        for h in self.source_stats() {
+            info!(
+                "{} source {} ({}): {} | {} | lag {} | divergence {} | {} updates",
+                self.asset,
+                h.name,
+                h.quote,
+                h.price.map(|p| p.round_dp(2).to_string()).unwrap_or_else(|| "-".to_string()),
+                if h.healthy { "healthy" } else if h.connected { "stale" } else { "down" },
+                h.lag.map(|l| format!("{}ms", l.as_millis())).unwrap_or_else(|| "-".to_string()),
+                h.divergence_bps.map(|d| format!("{:+}bps", d)).unwrap_or_else(|| "-".to_string()),
+                h.updates,
+            );
+        }
+    }
+
+    /// Start a reconnecting connection per configured source on `supervisor`
+    pub fn connect(&self, supervisor: &Supervisor) {
+        for (index, source) in self.sources.iter().enumerate() {
+            let source = source.clone();
+            let sink = PriceSink::new(self.state.clone(), index);
+            let asset = self.asset;
+            supervisor.spawn("Price source", async move {
+                loop {
+                    if let Err(e) = source.run(&sink).await {
+                        error!("{} {} price feed error: {}", asset, source.name(), e);
+                    }
+                    sink.set_connected(false);
+
+                    let delay = source.reconnect_delay();
+                    warn!("{} {} price feed disconnected, reconnecting in {:?}...", asset, source.name(), delay);
+                    tokio::time::sleep(delay).await;
+                }
+            });
+        }
+    }
+
+    /// Wait up to `timeout` for the first price update (from any exchange)
+    pub async fn wait_for_price(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        while Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            if self.get_price() > Decimal::ZERO {
+                self.log_source_stats();
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl Default for SpotPriceFeed {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Spawn a price feed for `asset` from the named sources (see `price_source::KNOWN_SOURCES`)
+///
+/// The source connections run on `supervisor` and stop when it shuts down.
+pub fn spawn_price_feed(asset: Asset, source_names: &[String], supervisor: &Supervisor) -> Result<Arc<SpotPriceFeed>> {
+    let sources = price_source::sources_from_names(source_names, asset)?;
+    let feed = Arc::new(SpotPriceFeed::with_sources(asset, sources));
+    feed.connect(supervisor);
+    Ok(feed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::WINDOW_SECS;
+
+    #[test]
+    fn test_predicted_outcome() {
+        let feed = SpotPriceFeed::new();
+
+        // Set initial price
+        {
+            let mut state = feed.state.write();
+            state.current_price = dec!(100000);
+            state.market_open_price = Some(dec!(100000));
+        }
+
+        // No movement
+        assert_eq!(feed.get_predicted_outcome(), None);
+
+        // Price went up
+        feed.state.write().current_price = dec!(100100);
+        assert_eq!(feed.get_predicted_outcome(), Some(true)); // UP
+
+        // Price went down
+        feed.state.write().current_price = dec!(99900);
+        assert_eq!(feed.get_predicted_outcome(), Some(false)); // DOWN
+    }
+
+    #[test]
+    fn test_confidence() {
+        let feed = SpotPriceFeed::new();
+
+        // Set initial price at $100,000
+        {
+            let mut state = feed.state.write();
+            state.current_price = dec!(100000);
+            state.market_open_price = Some(dec!(100000));
+        }
+
+        // No change = 0 confidence
+        assert_eq!(feed.get_confidence(), dec!(0));
+
+        // 0.1% change = high confidence
+        feed.state.write().current_price = dec!(100100);
+        let conf = feed.get_confidence();
+        assert!(conf > dec!(50));
+    }
+
+    #[test]
+    fn test_momentum_uses_wall_time() {
+        let mut state = SpotPriceState { sources: vec![SourceQuote::new("coinbase", QuoteCurrency::Usd)], ..Default::default() };
+        let now = Instant::now();
+
+        // A burst of 50 ticks spans no time at all: no 10s momentum yet
+        for i in 0..50 {
+            state.apply_quote(0, &SpotTick::ticker(dec!(99951) + Decimal::from(i), Some(1_736_949_731_000 + i)), now);
+        }
+        assert_eq!(state.history.len(), 50);
+        assert_eq!(state.history.latest().and_then(|p| p.exchange_ts_ms), Some(1_736_949_731_049));
+        assert_eq!(state.history.roc(ROC_SHORT, now), None);
+
+        let later = now + Duration::from_secs(12);
+        state.apply_quote(0, &SpotTick::trade(dec!(100100), dec!(0.5), true, None), later);
+        assert_eq!(state.history.roc(ROC_SHORT, later), Some(dec!(0.1)));
+    }
+
+    #[test]
+    fn test_window_open_captured_at_boundary() {
+        let feed = SpotPriceFeed::new();
+        let state = feed.state();
+        state.write().sources = vec![SourceQuote::new("coinbase", QuoteCurrency::Usd), SourceQuote::new("binance", QuoteCurrency::Usd)];
+        let boundary = 1_736_949_600; // 14:00:00 UTC, a 15-minute boundary
+        let ms = |offset: i64| Some(boundary * 1000 + offset);
+        let now = Instant::now();
+        let tick = |index: usize, price: Decimal, ts: Option<i64>| state.write().apply_quote(index, &SpotTick::ticker(price, ts), now);
+
+        tick(0, dec!(100000), ms(-1500));
+        tick(0, dec!(100010), ms(0)); // stamped exactly at the boundary: in effect at it
+        tick(1, dec!(100010), ms(-300)); // slower source catching up doesn't move the clock
+        assert!(feed.window_open(boundary).is_none());
+        tick(0, dec!(100200), ms(40));
+
+        let open = feed.window_open(boundary).unwrap();
+        assert_eq!(open, WindowOpen { price: dec!(100010), last_tick_ms: boundary * 1000, next_tick_ms: boundary * 1000 + 40 });
+
+        // A session starting late still gets the boundary price, not the current one
+        feed.mark_market_open(boundary);
+        assert_eq!(feed.get_market_open_price(), Some(dec!(100010)));
+        // Composite is now the midpoint of 100200 and 100010
+        assert_eq!(feed.get_price_change(), Some(dec!(95)));
+
+        // No capture for a window the feed wasn't running at
+        feed.mark_market_open(boundary - WINDOW_SECS);
+        assert_eq!(feed.get_market_open_price(), Some(dec!(100105)));
+
+        // Feed silent for a minute across the next boundary: the last price is
+        // stale, so nothing is captured and the open falls back to current
+        let next = boundary + WINDOW_SECS;
+        tick(0, dec!(100300), Some(next * 1000 - 60_000));
+        tick(0, dec!(100500), Some(next * 1000 + 200));
+        assert!(feed.window_open(next).is_none());
+        feed.mark_market_open(next);
+        assert_eq!(feed.get_market_open_price(), Some(dec!(100255)));
+    }
+}
@@ -3,20 +3,20 @@
 //! This is the KEY strategy that replicates how pro traders achieve 100% win rates.
 //!
 //! HOW IT WORKS:
-//! 1. Connect to Binance WebSocket for real-time BTC price
-//! 2. When new 15-min market opens, record BTC price
+//! 1. Stream the asset's spot price from the configured exchanges
+//! 2. When new 15-min market opens, record the spot price
 //! 3. Wait until late in the period (minute 10-13)
-//! 4. Observe if BTC is UP or DOWN from market open
+//! 4. Observe if the asset is UP or DOWN from market open
 //! 5. Buy the winning outcome BEFORE prices hit $1
 //!
 //! WHY IT WORKS:
-//! - By minute 12, BTC direction is 95%+ determined
-//! - Polymarket prices lag actual BTC price
+//! - By minute 12, the direction is 95%+ determined
+//! - Polymarket prices lag the actual spot price
 //! - Fast execution captures value before market catches up
 //!
 //! COMPARISON TO OLD STRATEGY:
 //! - Old (Arbitrage): Buy BOTH sides, hope combined < $1 (never happens)
-//! - New (Directional): Buy ONE side based on observed BTC direction
+//! - New (Directional): Buy ONE side based on observed spot direction
 
 use async_trait::async_trait;
 use parking_lot::RwLock;
@@ -29,8 +29,9 @@ use super::{
     MarketState, Outcome, OrderSide, PositionState, StrategyMetrics,
     StrategyOrder, StrategySignal, TradingStrategy,
 };
-use crate::btc_price::BtcPriceFeed;
-use crate::types::BtcMarket;
+use crate::fair_value::{edge, FairValueModel};
+use crate::spot_price::SpotPriceFeed;
+use crate::types::UpDownMarket;
 
 /// Configuration for directional strategy
 #[derive(Debug, Clone)]
@@ -39,7 +40,7 @@ pub struct DirectionalConfig {
     pub entry_minute_min: f64,
     /// Maximum minute to stop trading (default: 14)
     pub entry_minute_max: f64,
-    /// Minimum BTC price change % to trigger entry (default: 0.02%)
+    /// Minimum spot price change % to trigger entry (default: 0.02%)
     pub min_confidence_pct: Decimal,
     /// Maximum price to pay for outcome (default: 0.90 = 90 cents)
     pub max_entry_price: Decimal,
@@ -47,6 +48,8 @@ pub struct DirectionalConfig {
     pub position_size: Decimal,
     /// Maximum total position per market
     pub max_position: Decimal,
+    /// Balance that confidence-based stakes are a percentage of
+    pub account_balance: Decimal,
     /// Use limit orders below best ask (more profit, less certainty)
     pub use_limit_orders: bool,
     /// How far below best ask to place limit orders
@@ -55,6 +58,16 @@ pub struct DirectionalConfig {
     pub ladder_levels: u32,
     /// Price spacing between ladder levels (e.g., 0.02 = 2 cents)
     pub ladder_spacing: Decimal,
+    /// Skip entry when tape flow on our token is this one-sided against us (0-1)
+    pub max_adverse_flow: Decimal,
+    /// Minimum prints in the window before flow counts
+    pub min_flow_trades: usize,
+    /// Minimum fair value minus ask and taker fee, per share, to enter (default: 3 cents)
+    pub min_edge: Decimal,
+    /// Taker fee rate, charged per share as a fraction of min(price, 1 - price)
+    pub taker_fee_rate: Decimal,
+    /// Skip entry when a USD source is further than this from the composite (bps)
+    pub max_source_divergence_bps: Decimal,
 }
 
 impl Default for DirectionalConfig {
@@ -62,23 +75,37 @@ impl Default for DirectionalConfig {
         Self {
             entry_minute_min: 10.0,
             entry_minute_max: 14.0,
-            min_confidence_pct: dec!(0.02), // 0.02% BTC move minimum
+            min_confidence_pct: dec!(0.02), // 0.02% spot move minimum
             max_entry_price: dec!(0.90),
             position_size: dec!(100),
             max_position: dec!(500),
+            account_balance: dec!(1000),
             use_limit_orders: true,
             limit_offset: dec!(0.02), // 2 cents below best ask
             ladder_levels: 1,         // Default: single order (no laddering)
             ladder_spacing: dec!(0.02), // 2 cents between levels
+            max_adverse_flow: dec!(0.6),
+            min_flow_trades: 5,
+            min_edge: dec!(0.03),
+            taker_fee_rate: dec!(0.10),
+            max_source_divergence_bps: dec!(20),
         }
     }
 }
 
+impl DirectionalConfig {
+    /// `stake` trimmed so the market's total cost stays within `max_position`
+    pub fn cap_stake(&self, stake: Decimal, spent: Decimal) -> Decimal {
+        stake.min(self.max_position - spent).max(Decimal::ZERO)
+    }
+}
+
 pub struct DirectionalStrategy {
     name: String,
     metrics: StrategyMetrics,
     config: DirectionalConfig,
-    btc_feed: Arc<BtcPriceFeed>,
+    spot_feed: Arc<SpotPriceFeed>,
+    fair_value: FairValueModel,
 
     // State
     has_entered: bool,
@@ -87,7 +114,8 @@ pub struct DirectionalStrategy {
 }
 
 impl DirectionalStrategy {
-    pub fn new(btc_feed: Arc<BtcPriceFeed>, config: DirectionalConfig) -> Self {
+    /// `fair_value` is the caller's loaded calibration (`FairValueModel::default()` if none)
+    pub fn new(spot_feed: Arc<SpotPriceFeed>, config: DirectionalConfig, fair_value: FairValueModel) -> Self {
         Self {
             name: "directional".to_string(),
             metrics: StrategyMetrics {
@@ -95,14 +123,15 @@ impl DirectionalStrategy {
                 ..Default::default()
             },
             config,
-            btc_feed,
+            spot_feed,
+            fair_value,
             has_entered: false,
             entry_price: None,
             predicted_outcome: None,
         }
     }
 
-    /// Check if we should enter based on BTC price direction
+    /// Check if we should enter based on spot price direction
     fn should_enter(&self, state: &MarketState) -> Option<(Outcome, Decimal)> {
         // Check timing - only trade in the "late game"
         if state.minute_of_period < self.config.entry_minute_min
@@ -122,23 +151,23 @@ impl DirectionalStrategy {
             return None;
         }
 
-        // Get BTC prediction
-        let btc_is_up = self.btc_feed.get_predicted_outcome()?;
-        let btc_change = self.btc_feed.get_price_change()?;
-        let btc_change_pct = self.btc_feed.get_price_change_pct()?;
+        // Get spot prediction
+        let spot_is_up = self.spot_feed.get_predicted_outcome()?;
+        let spot_change = self.spot_feed.get_price_change()?;
+        let spot_change_pct = self.spot_feed.get_price_change_pct()?;
 
         // Check confidence threshold
-        if btc_change_pct.abs() < self.config.min_confidence_pct {
+        if spot_change_pct.abs() < self.config.min_confidence_pct {
             debug!(
                 "Confidence check: {:.4}% < {:.4}% threshold",
-                btc_change_pct.abs(),
+                spot_change_pct.abs(),
                 self.config.min_confidence_pct
             );
             return None;
         }
 
         // Determine which outcome to buy
-        let (outcome, best_ask) = if btc_is_up {
+        let (outcome, best_ask) = if spot_is_up {
             (Outcome::Up, state.up_best_ask?)
         } else {
             (Outcome::Down, state.down_best_ask?)
@@ -153,13 +182,51 @@ impl DirectionalStrategy {
             return None;
         }
 
+        // Exchanges must agree before we trust the move
+        if !self.spot_feed.sources_agree() {
+            debug!("Source check: exchanges disagree on direction");
+            return None;
+        }
+        if let Some(divergence) = self.spot_feed.max_divergence_bps() {
+            if divergence > self.config.max_source_divergence_bps {
+                debug!("Source check: {}bps divergence > {} max", divergence, self.config.max_source_divergence_bps);
+                return None;
+            }
+        }
+
+        // Only pay less than the model says the outcome is worth
+        let fair_up = self.spot_feed.fair_up_prob(&self.fair_value, state.seconds_to_resolution)?;
+        let fair = if spot_is_up { fair_up } else { Decimal::ONE - fair_up };
+        let edge = edge(fair, best_ask, self.config.taker_fee_rate);
+        if edge < self.config.min_edge {
+            debug!(
+                "Edge check: fair {} - ask {} - fee = {} < {} min",
+                fair, best_ask, edge, self.config.min_edge
+            );
+            return None;
+        }
+
+        // Don't buy into aggressive selling of our token
+        let flow = if spot_is_up { &state.up_flow } else { &state.down_flow };
+        if let Some(flow) = flow {
+            if flow.is_adverse_for_buyer(self.config.max_adverse_flow, self.config.min_flow_trades) {
+                debug!(
+                    "Flow check: {:?} tape imbalance {:.2} over {} trades",
+                    outcome, flow.imbalance, flow.trades
+                );
+                return None;
+            }
+        }
+
         info!(
-            "DIRECTIONAL SIGNAL: BTC is {} by ${:.2} ({:.4}%), buying {:?} at {}",
-            if btc_is_up { "UP" } else { "DOWN" },
-            btc_change.abs(),
-            btc_change_pct,
+            "DIRECTIONAL SIGNAL: spot is {} by ${:.2} ({:.4}%), buying {:?} at {} (fair {}, edge {})",
+            if spot_is_up { "UP" } else { "DOWN" },
+            spot_change.abs(),
+            spot_change_pct,
             outcome,
-            best_ask
+            best_ask,
+            fair,
+            edge
         );
 
         Some((outcome, best_ask))
@@ -194,7 +261,7 @@ impl TradingStrategy for DirectionalStrategy {
 
     async fn on_market_start(
         &mut self,
-        market: &BtcMarket,
+        market: &UpDownMarket,
         _state: &MarketState,
     ) -> StrategySignal {
         // Reset state for new market
@@ -202,13 +269,14 @@ impl TradingStrategy for DirectionalStrategy {
         self.entry_price = None;
         self.predicted_outcome = None;
 
-        // Mark BTC price at market open
-        self.btc_feed.mark_market_open();
+        // Mark spot price at market open
+        self.spot_feed.mark_market_open(market.window_start());
 
         info!(
-            "Directional strategy started for market {}. BTC open: ${}",
+            "Directional strategy started for market {}. {} open: ${}",
             market.condition_id,
-            self.btc_feed.get_price().round_dp(2)
+            market.asset,
+            self.spot_feed.get_price().round_dp(2)
         );
 
         // Don't place orders at market start - wait for late game
@@ -217,7 +285,7 @@ impl TradingStrategy for DirectionalStrategy {
 
     async fn on_orderbook_update(
         &mut self,
-        _market: &BtcMarket,
+        _market: &UpDownMarket,
         state: &MarketState,
         position: &PositionState,
     ) -> StrategySignal {
@@ -231,11 +299,11 @@ impl TradingStrategy for DirectionalStrategy {
             let order = self.create_entry_order(outcome, best_ask);
 
             info!(
-                "PLACING ORDER: {:?} {} shares at {} (BTC: ${})",
+                "PLACING ORDER: {:?} {} shares at {} (spot: ${})",
                 outcome,
                 order.size.round_dp(0),
                 order.price,
-                self.btc_feed.get_price().round_dp(2)
+                self.spot_feed.get_price().round_dp(2)
             );
 
             return StrategySignal::PlaceOrders(vec![order]);
@@ -246,7 +314,7 @@ impl TradingStrategy for DirectionalStrategy {
 
     async fn on_fill(
         &mut self,
-        _market: &BtcMarket,
+        _market: &UpDownMarket,
         outcome: Outcome,
         price: Decimal,
         size: Decimal,
@@ -270,20 +338,20 @@ impl TradingStrategy for DirectionalStrategy {
 
     async fn on_tick(
         &mut self,
-        _market: &BtcMarket,
+        _market: &UpDownMarket,
         state: &MarketState,
         position: &PositionState,
     ) -> StrategySignal {
         // Log current state every tick
-        let btc_price = self.btc_feed.get_price();
-        let btc_change = self.btc_feed.get_price_change().unwrap_or(Decimal::ZERO);
-        let btc_pct = self.btc_feed.get_price_change_pct().unwrap_or(Decimal::ZERO);
+        let spot_price = self.spot_feed.get_price();
+        let spot_change = self.spot_feed.get_price_change().unwrap_or(Decimal::ZERO);
+        let spot_pct = self.spot_feed.get_price_change_pct().unwrap_or(Decimal::ZERO);
 
         debug!(
-            "Tick: minute {:.1}, BTC ${} ({:+.2}%), position: ${:.2}",
+            "Tick: minute {:.1}, spot ${} ({:+.2}%), position: ${:.2}",
             state.minute_of_period,
-            btc_price.round_dp(2),
-            btc_pct,
+            spot_price.round_dp(2),
+            spot_pct,
             position.total_cost()
         );
 
@@ -300,7 +368,7 @@ impl TradingStrategy for DirectionalStrategy {
 
     async fn on_pre_resolution(
         &mut self,
-        _market: &BtcMarket,
+        _market: &UpDownMarket,
         position: &PositionState,
     ) -> StrategySignal {
         // Cancel any unfilled orders, keep position for resolution
@@ -314,7 +382,7 @@ impl TradingStrategy for DirectionalStrategy {
         }
 
         // Clear market open price
-        self.btc_feed.clear_market_open();
+        self.spot_feed.clear_market_open();
 
         StrategySignal::CancelAll
     }
@@ -361,4 +429,29 @@ mod tests {
         assert!(config.entry_minute_min >= 10.0);
         assert!(config.max_entry_price <= dec!(1));
     }
+
+    #[test]
+    fn test_assets_stay_within_global_max_position() {
+        use crate::config::asset_configs;
+        use crate::types::Asset;
+
+        let assets = asset_configs(vec![Asset::Btc, Asset::Eth], dec!(100), dec!(1000)).unwrap();
+        let total: Decimal = assets
+            .iter()
+            .map(|a| {
+                let config = DirectionalConfig {
+                    max_position: a.max_position_usd,
+                    account_balance: a.account_balance,
+                    ..Default::default()
+                };
+                // Largest stake the bot sizes: 25% of the balance at the 1.75x price scale
+                config.cap_stake(config.account_balance * dec!(0.25) * dec!(1.75), Decimal::ZERO)
+            })
+            .sum();
+        assert_eq!(total, dec!(100));
+
+        let config = DirectionalConfig { max_position: dec!(50), ..Default::default() };
+        assert_eq!(config.cap_stake(dec!(20), dec!(40)), dec!(10));
+        assert_eq!(config.cap_stake(dec!(20), dec!(60)), Decimal::ZERO);
+    }
 }
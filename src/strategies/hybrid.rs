@@ -16,7 +16,7 @@ use super::{
     MarketState, Outcome, OrderSide, PositionState, StrategyMetrics,
     StrategyOrder, StrategySignal, TradingStrategy,
 };
-use crate::types::BtcMarket;
+use crate::types::UpDownMarket;
 
 const PRICE_HISTORY_LEN: usize = 10;
 
@@ -121,7 +121,7 @@ impl TradingStrategy for HybridStrategy {
 
     async fn on_market_start(
         &mut self,
-        _market: &BtcMarket,
+        _market: &UpDownMarket,
         state: &MarketState,
     ) -> StrategySignal {
         self.up_entry_avg = dec!(0);
@@ -157,7 +157,7 @@ impl TradingStrategy for HybridStrategy {
 
     async fn on_orderbook_update(
         &mut self,
-        _market: &BtcMarket,
+        _market: &UpDownMarket,
         state: &MarketState,
         position: &PositionState,
     ) -> StrategySignal {
@@ -269,7 +269,7 @@ impl TradingStrategy for HybridStrategy {
 
     async fn on_fill(
         &mut self,
-        _market: &BtcMarket,
+        _market: &UpDownMarket,
         outcome: Outcome,
         price: Decimal,
         size: Decimal,
@@ -296,7 +296,7 @@ impl TradingStrategy for HybridStrategy {
 
     async fn on_tick(
         &mut self,
-        _market: &BtcMarket,
+        _market: &UpDownMarket,
         _state: &MarketState,
         _position: &PositionState,
     ) -> StrategySignal {
@@ -305,7 +305,7 @@ impl TradingStrategy for HybridStrategy {
 
     async fn on_pre_resolution(
         &mut self,
-        _market: &BtcMarket,
+        _market: &UpDownMarket,
         _position: &PositionState,
     ) -> StrategySignal {
         // Cancel unfilled orders, hold positions for resolution
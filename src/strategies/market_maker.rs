@@ -12,7 +12,7 @@ use super::{
     MarketState, Outcome, OrderSide, PositionState, StrategyMetrics,
     StrategyOrder, StrategySignal, TradingStrategy,
 };
-use crate::types::BtcMarket;
+use crate::types::UpDownMarket;
 
 pub struct MarketMakerStrategy {
     name: String,
@@ -107,7 +107,7 @@ impl TradingStrategy for MarketMakerStrategy {
 
     async fn on_market_start(
         &mut self,
-        _market: &BtcMarket,
+        _market: &UpDownMarket,
         state: &MarketState,
     ) -> StrategySignal {
         self.has_active_orders = false;
@@ -123,7 +123,7 @@ impl TradingStrategy for MarketMakerStrategy {
 
     async fn on_orderbook_update(
         &mut self,
-        _market: &BtcMarket,
+        _market: &UpDownMarket,
         state: &MarketState,
         position: &PositionState,
     ) -> StrategySignal {
@@ -138,7 +138,7 @@ impl TradingStrategy for MarketMakerStrategy {
 
     async fn on_fill(
         &mut self,
-        _market: &BtcMarket,
+        _market: &UpDownMarket,
         _outcome: Outcome,
         _price: Decimal,
         _size: Decimal,
@@ -151,7 +151,7 @@ impl TradingStrategy for MarketMakerStrategy {
 
     async fn on_tick(
         &mut self,
-        _market: &BtcMarket,
+        _market: &UpDownMarket,
         state: &MarketState,
         position: &PositionState,
     ) -> StrategySignal {
@@ -166,7 +166,7 @@ impl TradingStrategy for MarketMakerStrategy {
 
     async fn on_pre_resolution(
         &mut self,
-        _market: &BtcMarket,
+        _market: &UpDownMarket,
         position: &PositionState,
     ) -> StrategySignal {
         // Before resolution, try to flatten inventory
@@ -16,8 +16,9 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use parking_lot::Mutex;
 
-use crate::types::BtcMarket;
+use crate::types::UpDownMarket;
 use crate::orderbook::OrderbookManager;
+use crate::trade_tape::TapeStats;
 
 /// Strategy performance metrics
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -110,6 +111,9 @@ pub struct MarketState {
     pub spread_pct: Option<Decimal>,
     pub seconds_to_resolution: i64,
     pub minute_of_period: f64,
+    /// Trade-tape order flow per token (None if no tape is wired up)
+    pub up_flow: Option<TapeStats>,
+    pub down_flow: Option<TapeStats>,
 }
 
 /// Trait all strategies must implement
@@ -121,14 +125,14 @@ pub trait TradingStrategy: Send + Sync {
     /// Called when entering a new market
     async fn on_market_start(
         &mut self,
-        market: &BtcMarket,
+        market: &UpDownMarket,
         state: &MarketState,
     ) -> StrategySignal;
 
     /// Called on every orderbook update
     async fn on_orderbook_update(
         &mut self,
-        market: &BtcMarket,
+        market: &UpDownMarket,
         state: &MarketState,
         position: &PositionState,
     ) -> StrategySignal;
@@ -136,7 +140,7 @@ pub trait TradingStrategy: Send + Sync {
     /// Called when an order is filled
     async fn on_fill(
         &mut self,
-        market: &BtcMarket,
+        market: &UpDownMarket,
         outcome: Outcome,
         price: Decimal,
         size: Decimal,
@@ -146,7 +150,7 @@ pub trait TradingStrategy: Send + Sync {
     /// Called periodically (every 30 seconds)
     async fn on_tick(
         &mut self,
-        market: &BtcMarket,
+        market: &UpDownMarket,
         state: &MarketState,
         position: &PositionState,
     ) -> StrategySignal;
@@ -154,7 +158,7 @@ pub trait TradingStrategy: Send + Sync {
     /// Called before market resolution (2 min before end)
     async fn on_pre_resolution(
         &mut self,
-        market: &BtcMarket,
+        market: &UpDownMarket,
         position: &PositionState,
     ) -> StrategySignal;
 
@@ -163,6 +167,34 @@ pub trait TradingStrategy: Send + Sync {
 
     /// Update metrics after session ends
     fn record_session_result(&mut self, profit: Decimal, cost: Decimal);
+
+    /// Called when the market's tick size changes (`market.tick_size` is the new tick)
+    ///
+    /// Resting orders have already been snapped to the new grid; override to
+    /// re-quote more aggressively on the finer grid.
+    async fn on_tick_size_change(
+        &mut self,
+        _market: &UpDownMarket,
+        _state: &MarketState,
+        _position: &PositionState,
+    ) -> StrategySignal {
+        StrategySignal::Hold
+    }
+}
+
+/// Move a price onto a tick grid without making it more aggressive
+///
+/// Buys round down, sells round up; the result stays inside [tick, 1 - tick].
+pub fn snap_to_tick(price: Decimal, tick_size: Decimal, side: OrderSide) -> Decimal {
+    if tick_size <= Decimal::ZERO {
+        return price;
+    }
+    let ticks = price / tick_size;
+    let ticks = match side {
+        OrderSide::Buy => ticks.floor(),
+        OrderSide::Sell => ticks.ceil(),
+    };
+    (ticks * tick_size).max(tick_size).min(Decimal::ONE - tick_size).normalize()
 }
 
 /// Helper to parse decimal from string
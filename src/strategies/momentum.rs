@@ -13,7 +13,7 @@ use super::{
     MarketState, Outcome, OrderSide, PositionState, StrategyMetrics,
     StrategyOrder, StrategySignal, TradingStrategy,
 };
-use crate::types::BtcMarket;
+use crate::types::UpDownMarket;
 
 const PRICE_HISTORY_LEN: usize = 20;
 
@@ -86,7 +86,7 @@ impl TradingStrategy for MomentumStrategy {
 
     async fn on_market_start(
         &mut self,
-        _market: &BtcMarket,
+        _market: &UpDownMarket,
         state: &MarketState,
     ) -> StrategySignal {
         self.up_prices.clear();
@@ -124,7 +124,7 @@ impl TradingStrategy for MomentumStrategy {
 
     async fn on_orderbook_update(
         &mut self,
-        _market: &BtcMarket,
+        _market: &UpDownMarket,
         state: &MarketState,
         position: &PositionState,
     ) -> StrategySignal {
@@ -185,7 +185,7 @@ impl TradingStrategy for MomentumStrategy {
 
     async fn on_fill(
         &mut self,
-        _market: &BtcMarket,
+        _market: &UpDownMarket,
         _outcome: Outcome,
         _price: Decimal,
         _size: Decimal,
@@ -197,7 +197,7 @@ impl TradingStrategy for MomentumStrategy {
 
     async fn on_tick(
         &mut self,
-        _market: &BtcMarket,
+        _market: &UpDownMarket,
         _state: &MarketState,
         _position: &PositionState,
     ) -> StrategySignal {
@@ -206,7 +206,7 @@ impl TradingStrategy for MomentumStrategy {
 
     async fn on_pre_resolution(
         &mut self,
-        _market: &BtcMarket,
+        _market: &UpDownMarket,
         _position: &PositionState,
     ) -> StrategySignal {
         // Hold to resolution - we want the arb profit
@@ -12,7 +12,7 @@ use super::{
     MarketState, Outcome, OrderSide, PositionState, StrategyMetrics,
     StrategyOrder, StrategySignal, TradingStrategy,
 };
-use crate::types::BtcMarket;
+use crate::types::UpDownMarket;
 
 pub struct PureArbStrategy {
     name: String,
@@ -79,7 +79,7 @@ impl TradingStrategy for PureArbStrategy {
 
     async fn on_market_start(
         &mut self,
-        _market: &BtcMarket,
+        _market: &UpDownMarket,
         state: &MarketState,
     ) -> StrategySignal {
         self.orders_placed = false;
@@ -106,7 +106,7 @@ impl TradingStrategy for PureArbStrategy {
 
     async fn on_orderbook_update(
         &mut self,
-        _market: &BtcMarket,
+        _market: &UpDownMarket,
         state: &MarketState,
         position: &PositionState,
     ) -> StrategySignal {
@@ -160,7 +160,7 @@ impl TradingStrategy for PureArbStrategy {
 
     async fn on_fill(
         &mut self,
-        _market: &BtcMarket,
+        _market: &UpDownMarket,
         _outcome: Outcome,
         _price: Decimal,
         _size: Decimal,
@@ -172,7 +172,7 @@ impl TradingStrategy for PureArbStrategy {
 
     async fn on_tick(
         &mut self,
-        _market: &BtcMarket,
+        _market: &UpDownMarket,
         _state: &MarketState,
         _position: &PositionState,
     ) -> StrategySignal {
@@ -181,7 +181,7 @@ impl TradingStrategy for PureArbStrategy {
 
     async fn on_pre_resolution(
         &mut self,
-        _market: &BtcMarket,
+        _market: &UpDownMarket,
         _position: &PositionState,
     ) -> StrategySignal {
         // Cancel all unfilled orders before resolution
@@ -12,7 +12,7 @@ use super::{
     MarketState, Outcome, OrderSide, PositionState, StrategyMetrics,
     StrategyOrder, StrategySignal, TradingStrategy,
 };
-use crate::types::BtcMarket;
+use crate::types::UpDownMarket;
 
 pub struct ScalperStrategy {
     name: String,
@@ -71,7 +71,7 @@ impl TradingStrategy for ScalperStrategy {
 
     async fn on_market_start(
         &mut self,
-        _market: &BtcMarket,
+        _market: &UpDownMarket,
         state: &MarketState,
     ) -> StrategySignal {
         self.up_entry_price = None;
@@ -101,7 +101,7 @@ impl TradingStrategy for ScalperStrategy {
 
     async fn on_orderbook_update(
         &mut self,
-        _market: &BtcMarket,
+        _market: &UpDownMarket,
         state: &MarketState,
         position: &PositionState,
     ) -> StrategySignal {
@@ -195,7 +195,7 @@ impl TradingStrategy for ScalperStrategy {
 
     async fn on_fill(
         &mut self,
-        _market: &BtcMarket,
+        _market: &UpDownMarket,
         outcome: Outcome,
         price: Decimal,
         _size: Decimal,
@@ -220,7 +220,7 @@ impl TradingStrategy for ScalperStrategy {
 
     async fn on_tick(
         &mut self,
-        _market: &BtcMarket,
+        _market: &UpDownMarket,
         _state: &MarketState,
         _position: &PositionState,
     ) -> StrategySignal {
@@ -229,7 +229,7 @@ impl TradingStrategy for ScalperStrategy {
 
     async fn on_pre_resolution(
         &mut self,
-        _market: &BtcMarket,
+        _market: &UpDownMarket,
         _position: &PositionState,
     ) -> StrategySignal {
         // Don't cancel - let positions ride to resolution
@@ -10,7 +10,7 @@ use crate::clob::ClobClient;
 use crate::config::Config;
 use crate::ml_client::MlClient;
 use crate::signer::OrderSigner;
-use crate::types::{BtcMarket, Order, Orderbook, Position, Side};
+use crate::types::{UpDownMarket, Order, Orderbook, Position, Side};
 
 /// Ladder arbitrage strategy
 ///
@@ -171,9 +171,21 @@ impl LadderStrategy {
     /// Uses PARALLEL signing for 10x speedup (600-1200ms -> 50-100ms)
     pub async fn create_ladder_orders(
         &self,
-        market: &BtcMarket,
+        market: &UpDownMarket,
         up_orderbook: &Orderbook,
         down_orderbook: &Orderbook,
+    ) -> Result<(Vec<Order>, Vec<Order>)> {
+        self.create_ladder_orders_with_budget(market, up_orderbook, down_orderbook, self.config.max_position_usd)
+            .await
+    }
+
+    /// Create ladder orders spending at most `budget` USD across both sides
+    pub async fn create_ladder_orders_with_budget(
+        &self,
+        market: &UpDownMarket,
+        up_orderbook: &Orderbook,
+        down_orderbook: &Orderbook,
+        budget: Decimal,
     ) -> Result<(Vec<Order>, Vec<Order>)> {
         let start = Instant::now();
 
@@ -187,7 +199,7 @@ impl LadderStrategy {
         info!("DOWN ladder prices: {:?}", down_prices);
 
         // Calculate size per order
-        let total_per_side = self.config.max_position_usd / dec!(2);
+        let total_per_side = budget / dec!(2);
         let size_per_level = total_per_side / Decimal::from(self.config.ladder_levels);
 
         // PARALLEL signing - create all order futures at once
@@ -256,14 +268,28 @@ impl LadderStrategy {
     /// Submit all ladder orders using CACHED orderbooks (saves 100-200ms)
     pub async fn submit_ladder_with_cache(
         &self,
-        market: &BtcMarket,
+        market: &UpDownMarket,
+        up_book: &Orderbook,
+        down_book: &Orderbook,
+    ) -> Result<(Vec<String>, Vec<String>)> {
+        self.submit_ladder_with_budget(market, up_book, down_book, self.config.max_position_usd)
+            .await
+    }
+
+    /// Submit a ladder spending at most `budget` USD
+    pub async fn submit_ladder_with_budget(
+        &self,
+        market: &UpDownMarket,
         up_book: &Orderbook,
         down_book: &Orderbook,
+        budget: Decimal,
     ) -> Result<(Vec<String>, Vec<String>)> {
         let start = Instant::now();
 
         // Create orders using cached orderbooks (no REST fetch!)
-        let (up_orders, down_orders) = self.create_ladder_orders(market, up_book, down_book).await?;
+        let (up_orders, down_orders) = self
+            .create_ladder_orders_with_budget(market, up_book, down_book, budget)
+            .await?;
         let signing_time = start.elapsed();
 
         if self.config.dry_run {
@@ -300,7 +326,7 @@ impl LadderStrategy {
     /// Submit all ladder orders (legacy - fetches orderbooks via REST)
     pub async fn submit_ladder(
         &self,
-        market: &BtcMarket,
+        market: &UpDownMarket,
     ) -> Result<(Vec<String>, Vec<String>)> {
         // Fetch current orderbooks (SLOW - adds 100-200ms)
         let up_book = self.clob.get_orderbook(&market.up_token_id).await?;
@@ -352,7 +378,7 @@ impl LadderStrategy {
     /// - If imbalance > 40%: Market buy the lagging side to force balance
     pub async fn rebalance_if_needed(
         &self,
-        market: &BtcMarket,
+        market: &UpDownMarket,
         position: &Position,
     ) -> Result<Option<Vec<String>>> {
         if position.is_balanced() {
@@ -454,7 +480,7 @@ impl LadderStrategy {
     /// This is the aggressive complement to the passive ladder strategy.
     pub async fn snipe_spread(
         &self,
-        market: &BtcMarket,
+        market: &UpDownMarket,
         up_ask: Decimal,
         down_ask: Decimal,
     ) -> Result<Option<(Vec<String>, Vec<String>)>> {
@@ -567,6 +593,30 @@ impl LadderStrategy {
         Ok(())
     }
 
+    /// Re-price resting ladder orders after a tick size change
+    ///
+    /// `market.tick_size` must already be the new tick. Cancels the market's
+    /// open orders and lays a fresh ladder on the new grid with whatever is
+    /// left of the budget after `spent`.
+    pub async fn requote_ladder(
+        &self,
+        market: &UpDownMarket,
+        up_book: &Orderbook,
+        down_book: &Orderbook,
+        spent: Decimal,
+    ) -> Result<(Vec<String>, Vec<String>)> {
+        self.cancel_all_orders(&market.condition_id).await?;
+
+        let remaining = self.config.max_position_usd - spent;
+        if remaining <= Decimal::ZERO {
+            info!("Budget used up, not re-quoting ladder");
+            return Ok((vec![], vec![]));
+        }
+
+        info!("Re-quoting ladder on {} tick with ${} remaining", market.tick_size, remaining);
+        self.submit_ladder_with_budget(market, up_book, down_book, remaining).await
+    }
+
     /// Get CLOB client reference (for direct API access)
     pub fn clob(&self) -> &ClobClient {
         &self.clob
@@ -0,0 +1,152 @@
+//! Process lifecycle: signal handling, cancellation and orderly shutdown
+//!
+//! A [`Supervisor`] owns the root cancellation token and the background
+//! tasks a bin starts. SIGINT/SIGTERM cancel the token; trading loops select
+//! on [`Supervisor::cancelled`] so they stop taking entries, cancel their
+//! open orders and unwind. The bin then flushes its logs, sends a final
+//! alert and calls [`Supervisor::shutdown`] to stop the tracked tasks before
+//! exiting. A second signal exits at once.
+
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
+use tracing::{info, warn};
+
+/// Root of a bin's task tree
+pub struct Supervisor {
+    token: CancellationToken,
+    tasks: TaskTracker,
+    reason: parking_lot::Mutex<Option<String>>,
+}
+
+impl Supervisor {
+    /// Create a supervisor and start listening for SIGINT/SIGTERM
+    pub fn install() -> Arc<Self> {
+        let supervisor = Arc::new(Self::new());
+        let sv = supervisor.clone();
+        tokio::spawn(async move {
+            let signal = wait_for_signal().await;
+            warn!("Received {}, shutting down (send again to exit immediately)", signal);
+            sv.trigger(signal);
+
+            let signal = wait_for_signal().await;
+            warn!("Received {} again, exiting now", signal);
+            std::process::exit(130);
+        });
+        supervisor
+    }
+
+    /// Supervisor without signal handlers (tests, embedding)
+    pub fn new() -> Self {
+        Self {
+            token: CancellationToken::new(),
+            tasks: TaskTracker::new(),
+            reason: parking_lot::Mutex::new(None),
+        }
+    }
+
+    /// Start shutdown; the first reason given is kept
+    pub fn trigger(&self, reason: &str) {
+        self.reason.lock().get_or_insert_with(|| reason.to_string());
+        self.token.cancel();
+    }
+
+    pub fn is_shutting_down(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Why shutdown started, once it has
+    pub fn reason(&self) -> Option<String> {
+        self.reason.lock().clone()
+    }
+
+    /// Resolves when shutdown starts
+    pub async fn cancelled(&self) {
+        self.token.cancelled().await
+    }
+
+    /// Run `fut` unless shutdown starts first
+    pub async fn run<F: Future>(&self, fut: F) -> Option<F::Output> {
+        self.token.run_until_cancelled(fut).await
+    }
+
+    /// Spawn a tracked task that is dropped when shutdown starts
+    pub fn spawn<F>(&self, name: &'static str, fut: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let token = self.token.clone();
+        self.tasks.spawn(async move {
+            if token.run_until_cancelled(fut).await.is_none() {
+                info!("{} stopped", name);
+            }
+        });
+    }
+
+    /// Cancel everything and wait up to `grace` for tracked tasks to finish
+    pub async fn shutdown(&self, grace: Duration) {
+        self.trigger("shutdown");
+        self.tasks.close();
+        if tokio::time::timeout(grace, self.tasks.wait()).await.is_err() {
+            warn!("{} task(s) still running after {:?}, abandoning them", self.tasks.len(), grace);
+        }
+    }
+}
+
+impl Default for Supervisor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(unix)]
+async fn wait_for_signal() -> &'static str {
+    use tokio::signal::unix::{signal, SignalKind};
+    match signal(SignalKind::terminate()) {
+        Ok(mut sigterm) => tokio::select! {
+            _ = tokio::signal::ctrl_c() => "SIGINT",
+            _ = sigterm.recv() => "SIGTERM",
+        },
+        Err(e) => {
+            warn!("Can't listen for SIGTERM: {}", e);
+            let _ = tokio::signal::ctrl_c().await;
+            "SIGINT"
+        }
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_signal() -> &'static str {
+    let _ = tokio::signal::ctrl_c().await;
+    "Ctrl-C"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    #[tokio::test]
+    async fn test_shutdown_stops_tracked_tasks() {
+        let supervisor = Supervisor::new();
+        let finished = Arc::new(AtomicBool::new(false));
+
+        let flag = finished.clone();
+        supervisor.spawn("forever", async move {
+            std::future::pending::<()>().await;
+            flag.store(true, Ordering::SeqCst);
+        });
+        assert_eq!(supervisor.run(async { 7 }).await, Some(7));
+
+        supervisor.trigger("SIGTERM");
+        assert!(supervisor.is_shutting_down());
+        assert_eq!(supervisor.run(std::future::pending::<()>()).await, None);
+
+        supervisor.shutdown(Duration::from_secs(1)).await;
+        assert_eq!(supervisor.tasks.len(), 0);
+        assert!(!finished.load(Ordering::SeqCst));
+        assert_eq!(supervisor.reason().as_deref(), Some("SIGTERM"));
+    }
+}
@@ -0,0 +1,26 @@
+//! Fixtures shared by unit tests
+
+use rust_decimal_macros::dec;
+
+use crate::types::{Asset, UpDownMarket};
+
+/// Well-known anvil/hardhat dev key #0
+pub const TEST_KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+/// Address of [`TEST_KEY`]
+pub const TEST_ADDRESS: &str = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+
+/// BTC market ending 15 minutes from now on the 0.01 tick
+pub fn test_market() -> UpDownMarket {
+    UpDownMarket {
+        asset: Asset::Btc,
+        event_slug: "btc-updown-15m-test".to_string(),
+        condition_id: "0xcondition".to_string(),
+        title: "test".to_string(),
+        up_token_id: "1".to_string(),
+        down_token_id: "2".to_string(),
+        end_time: chrono::Utc::now() + chrono::Duration::minutes(15),
+        tick_size: dec!(0.01),
+        min_order_size: dec!(5),
+        neg_risk: false,
+    }
+}
@@ -70,6 +70,12 @@ impl TradeDb {
         Ok(Self { conn })
     }
 
+    /// Write any cached pages to disk (call before exiting)
+    pub fn flush(&self) -> Result<()> {
+        self.conn.cache_flush()?;
+        Ok(())
+    }
+
     /// Insert a new trade record
     pub fn insert_trade(&self, trade: &TradeRecord) -> Result<i64> {
         self.conn.execute(
@@ -0,0 +1,224 @@
+//! Trade tape built from `last_trade_price` events
+//!
+//! Every match on a token is broadcast on the market channel with the
+//! aggressor's side, so the tape shows who is crossing the spread. Stats are
+//! computed over a rolling window per token: VWAP, buy/sell volume
+//! imbalance and trade rate.
+
+use parking_lot::RwLock;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+use crate::types::Side;
+use crate::websocket::parse_timestamp_ms;
+
+/// One print from the tape
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradePrint {
+    pub asset_id: String,
+    pub price: Decimal,
+    pub size: Decimal,
+    pub side: Side, // Aggressor (taker) side
+    pub timestamp_ms: i64,
+}
+
+/// Parse a `last_trade_price` message
+///
+/// `{"asset_id":"..","event_type":"last_trade_price","price":"0.456","side":"BUY","size":"219.2","timestamp":"1750428146322",..}`
+pub fn parse_last_trade_price(msg: &serde_json::Value) -> Option<TradePrint> {
+    if msg.get("event_type").and_then(|e| e.as_str()) != Some("last_trade_price") {
+        return None;
+    }
+
+    let field = |key: &str| msg.get(key).and_then(|v| v.as_str());
+    Some(TradePrint {
+        asset_id: field("asset_id")?.to_string(),
+        price: field("price")?.parse().ok()?,
+        size: field("size")?.parse().ok()?,
+        side: match field("side")? {
+            "BUY" => Side::Buy,
+            "SELL" => Side::Sell,
+            _ => return None,
+        },
+        timestamp_ms: parse_timestamp_ms(msg.get("timestamp"))
+            .unwrap_or_else(|| chrono::Utc::now().timestamp_millis()),
+    })
+}
+
+/// Order-flow statistics over a window
+#[derive(Debug, Clone, PartialEq)]
+pub struct TapeStats {
+    pub window_secs: u64,
+    pub trades: usize,
+    pub buy_volume: Decimal,
+    pub sell_volume: Decimal,
+    pub vwap: Option<Decimal>,
+    pub last_price: Option<Decimal>,
+    /// (buy - sell) / total volume, -1 to 1; 0 with no trades
+    pub imbalance: Decimal,
+    pub trades_per_min: Decimal,
+}
+
+impl TapeStats {
+    pub fn volume(&self) -> Decimal {
+        self.buy_volume + self.sell_volume
+    }
+
+    /// Buying this token means buying into one-sided selling: imbalance at or
+    /// below `-threshold` over at least `min_trades` trades
+    pub fn is_adverse_for_buyer(&self, threshold: Decimal, min_trades: usize) -> bool {
+        self.trades >= min_trades && self.imbalance <= -threshold
+    }
+}
+
+/// Rolling per-token trade history
+pub struct TradeTape {
+    prints: RwLock<HashMap<String, VecDeque<TradePrint>>>,
+    retention_ms: i64,
+}
+
+impl TradeTape {
+    /// Keep prints for `retention` (the longest window that will be queried)
+    pub fn new(retention: Duration) -> Self {
+        Self {
+            prints: RwLock::new(HashMap::new()),
+            retention_ms: retention.as_millis() as i64,
+        }
+    }
+
+    pub fn record(&self, print: TradePrint) {
+        let mut prints = self.prints.write();
+        let tape = prints.entry(print.asset_id.clone()).or_default();
+
+        let cutoff = print.timestamp_ms - self.retention_ms;
+        tape.push_back(print);
+        while tape.front().is_some_and(|p| p.timestamp_ms < cutoff) {
+            tape.pop_front();
+        }
+    }
+
+    /// Stats for a token over the last `window`
+    pub fn stats(&self, asset_id: &str, window: Duration) -> TapeStats {
+        self.stats_at(asset_id, window, chrono::Utc::now().timestamp_millis())
+    }
+
+    /// Stats for a token over `window` ending at `now_ms`
+    pub fn stats_at(&self, asset_id: &str, window: Duration, now_ms: i64) -> TapeStats {
+        let window_ms = window.as_millis() as i64;
+        let from = now_ms - window_ms;
+
+        let mut trades = 0;
+        let mut buy_volume = Decimal::ZERO;
+        let mut sell_volume = Decimal::ZERO;
+        let mut notional = Decimal::ZERO;
+        let mut last: Option<&TradePrint> = None;
+
+        let prints = self.prints.read();
+        for print in prints.get(asset_id).into_iter().flatten() {
+            if print.timestamp_ms < from || print.timestamp_ms > now_ms {
+                continue;
+            }
+            trades += 1;
+            notional += print.price * print.size;
+            match print.side {
+                Side::Buy => buy_volume += print.size,
+                Side::Sell => sell_volume += print.size,
+            }
+            if last.is_none_or(|l| print.timestamp_ms >= l.timestamp_ms) {
+                last = Some(print);
+            }
+        }
+
+        let volume = buy_volume + sell_volume;
+        let (vwap, imbalance) = if volume > Decimal::ZERO {
+            (Some(notional / volume), (buy_volume - sell_volume) / volume)
+        } else {
+            (None, Decimal::ZERO)
+        };
+        let trades_per_min = if window_ms > 0 {
+            Decimal::from(trades) * dec!(60000) / Decimal::from(window_ms)
+        } else {
+            Decimal::ZERO
+        };
+
+        TapeStats {
+            window_secs: window.as_secs(),
+            trades,
+            buy_volume,
+            sell_volume,
+            vwap,
+            last_price: last.map(|p| p.price),
+            imbalance,
+            trades_per_min,
+        }
+    }
+
+    /// Forget a token (market retired)
+    pub fn clear(&self, asset_id: &str) {
+        self.prints.write().remove(asset_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn print(side: Side, price: Decimal, size: Decimal, timestamp_ms: i64) -> TradePrint {
+        TradePrint { asset_id: "up".to_string(), price, size, side, timestamp_ms }
+    }
+
+    #[test]
+    fn test_parse_last_trade_price() {
+        let msg: serde_json::Value = serde_json::from_str(
+            r#"{"asset_id":"1143","event_type":"last_trade_price","fee_rate_bps":"0","market":"0x6a67","price":"0.456","side":"BUY","size":"219.217767","timestamp":"1750428146322"}"#,
+        )
+        .unwrap();
+        let print = parse_last_trade_price(&msg).unwrap();
+        assert_eq!(print.asset_id, "1143");
+        assert_eq!(print.price, dec!(0.456));
+        assert_eq!(print.size, dec!(219.217767));
+        assert_eq!(print.side, Side::Buy);
+        assert_eq!(print.timestamp_ms, 1750428146322);
+
+        let book: serde_json::Value = serde_json::from_str(r#"{"event_type":"book","asset_id":"1"}"#).unwrap();
+        assert!(parse_last_trade_price(&book).is_none());
+    }
+
+    #[test]
+    fn test_rolling_stats() {
+        let tape = TradeTape::new(Duration::from_secs(300));
+        tape.record(print(Side::Sell, dec!(0.40), dec!(100), 0)); // outside the 60s window
+        tape.record(print(Side::Buy, dec!(0.50), dec!(30), 100_000));
+        tape.record(print(Side::Buy, dec!(0.52), dec!(50), 120_000));
+        tape.record(print(Side::Sell, dec!(0.51), dec!(20), 130_000));
+
+        let stats = tape.stats_at("up", Duration::from_secs(60), 150_000);
+        assert_eq!(stats.trades, 3);
+        assert_eq!(stats.buy_volume, dec!(80));
+        assert_eq!(stats.sell_volume, dec!(20));
+        assert_eq!(stats.vwap, Some(dec!(0.5120))); // (15 + 26 + 10.2) / 100
+        assert_eq!(stats.imbalance, dec!(0.6));
+        assert_eq!(stats.trades_per_min, dec!(3));
+        assert_eq!(stats.last_price, Some(dec!(0.51)));
+        // Buyers are the aggressors, so buying up isn't fighting the flow
+        assert!(!stats.is_adverse_for_buyer(dec!(0.5), 3));
+
+        // Retention drops prints older than 300s behind the newest
+        tape.record(print(Side::Buy, dec!(0.60), dec!(10), 400_000));
+        assert_eq!(tape.stats_at("up", Duration::from_secs(1000), 400_000).trades, 4);
+
+        assert_eq!(tape.stats_at("down", Duration::from_secs(60), 150_000).vwap, None);
+
+        // Sellers hitting the token is adverse, unless there are too few prints
+        tape.record(print(Side::Sell, dec!(0.58), dec!(90), 410_000));
+        let selling = tape.stats_at("up", Duration::from_secs(30), 420_000);
+        assert!(selling.is_adverse_for_buyer(dec!(0.5), 2));
+        assert!(!selling.is_adverse_for_buyer(dec!(0.5), 3));
+
+        // A retired market's token starts over
+        tape.clear("up");
+        assert_eq!(tape.stats_at("up", Duration::from_secs(1000), 420_000).trades, 0);
+    }
+}
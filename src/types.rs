@@ -19,6 +19,8 @@ pub struct Market {
     pub neg_risk: Option<bool>,
     #[serde(alias = "orderPriceMinTickSize")]
     pub tick_size: Option<f64>,
+    #[serde(alias = "orderMinSize")]
+    pub min_order_size: Option<f64>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -60,7 +62,7 @@ pub struct PriceLevel {
 }
 
 /// Order to submit to CLOB
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Order {
     pub order: SignedOrder,
@@ -68,7 +70,7 @@ pub struct Order {
     pub order_type: OrderType,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SignedOrder {
     pub salt: String,
@@ -82,7 +84,7 @@ pub struct SignedOrder {
     pub nonce: String,
     pub fee_rate_bps: String,
     // CLOB API expects integer side: 0=BUY, 1=SELL (not string)
-    #[serde(serialize_with = "serialize_side_as_int")]
+    #[serde(serialize_with = "serialize_side_as_int", deserialize_with = "deserialize_side")]
     pub side: Side,
     pub signature_type: u8,
     pub signature: String,
@@ -98,6 +100,20 @@ where
     }
 }
 
+/// Accept both the integer (0/1) and string ("BUY"/"SELL") side encodings
+fn deserialize_side<'de, D>(deserializer: D) -> Result<Side, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::Number(n) if n.as_u64() == Some(0) => Ok(Side::Buy),
+        serde_json::Value::Number(n) if n.as_u64() == Some(1) => Ok(Side::Sell),
+        serde_json::Value::String(s) if s.eq_ignore_ascii_case("BUY") => Ok(Side::Buy),
+        serde_json::Value::String(s) if s.eq_ignore_ascii_case("SELL") => Ok(Side::Sell),
+        other => Err(serde::de::Error::custom(format!("invalid side: {}", other))),
+    }
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum Side {
@@ -105,12 +121,13 @@ pub enum Side {
     Sell,
 }
 
-#[derive(Debug, Clone, Copy, Serialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum OrderType {
     Gtc, // Good til cancelled
     Gtd, // Good til date
     Fok, // Fill or kill
+    Fak, // Fill and kill (partial fill allowed, rest cancelled)
 }
 
 /// WebSocket message types
@@ -186,9 +203,66 @@ pub struct TradeFill {
     pub status: String,
 }
 
-/// BTC 15-min market info
+/// Underlying of a 15-minute up/down market
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Asset {
+    #[default]
+    Btc,
+    Eth,
+    Sol,
+    Xrp,
+}
+
+impl Asset {
+    pub const ALL: [Asset; 4] = [Asset::Btc, Asset::Eth, Asset::Sol, Asset::Xrp];
+
+    /// Ticker, e.g. "BTC"
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Asset::Btc => "BTC",
+            Asset::Eth => "ETH",
+            Asset::Sol => "SOL",
+            Asset::Xrp => "XRP",
+        }
+    }
+
+    /// Lowercase ticker, as used in slugs and exchange streams
+    pub fn lower(&self) -> &'static str {
+        match self {
+            Asset::Btc => "btc",
+            Asset::Eth => "eth",
+            Asset::Sol => "sol",
+            Asset::Xrp => "xrp",
+        }
+    }
+
+    /// Event slug prefix of the asset's 15-minute markets, e.g. "btc-updown-15m-"
+    pub fn slug_prefix(&self) -> String {
+        format!("{}-updown-15m-", self.lower())
+    }
+}
+
+impl std::fmt::Display for Asset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.symbol())
+    }
+}
+
+impl std::str::FromStr for Asset {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        Asset::ALL
+            .into_iter()
+            .find(|a| a.lower() == s.trim().to_lowercase())
+            .ok_or_else(|| anyhow::anyhow!("Unknown asset '{}' (known: btc,eth,sol,xrp)", s))
+    }
+}
+
+/// 15-min up/down market info
 #[derive(Debug, Clone)]
-pub struct BtcMarket {
+pub struct UpDownMarket {
+    pub asset: Asset,
     pub event_slug: String,
     pub condition_id: String,
     pub title: String,
@@ -196,5 +270,59 @@ pub struct BtcMarket {
     pub down_token_id: String,
     pub end_time: chrono::DateTime<chrono::Utc>,
     pub tick_size: Decimal,
+    pub min_order_size: Decimal,
     pub neg_risk: bool,
 }
+
+impl UpDownMarket {
+    /// Unix seconds the market's 15-minute window opened
+    pub fn window_start(&self) -> i64 {
+        self.end_time.timestamp() - WINDOW_SECS
+    }
+}
+
+/// Market window length
+pub const WINDOW_SECS: i64 = 900;
+
+/// Most boundaries one `boundaries_between` call returns (one day of windows)
+const MAX_BOUNDARIES: usize = 96;
+
+/// Unix ms a window opens (and the previous one closes)
+pub fn boundary_ms(window_start: i64) -> i64 {
+    window_start * 1000
+}
+
+/// Window starts `b` with `from_ms <= b*1000 <= to_ms`
+pub fn boundaries_between(from_ms: i64, to_ms: i64) -> Vec<i64> {
+    let first = (from_ms + WINDOW_SECS * 1000 - 1).div_euclid(WINDOW_SECS * 1000) * WINDOW_SECS;
+    (0..)
+        .map(|i| first + i * WINDOW_SECS)
+        .take_while(|start| boundary_ms(*start) <= to_ms)
+        .take(MAX_BOUNDARIES)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_asset_from_str() {
+        assert_eq!("btc".parse::<Asset>().unwrap(), Asset::Btc);
+        assert_eq!(" ETH ".parse::<Asset>().unwrap(), Asset::Eth);
+        assert_eq!("Sol".parse::<Asset>().unwrap(), Asset::Sol);
+        assert_eq!("xrp".parse::<Asset>().unwrap(), Asset::Xrp);
+        assert!("doge".parse::<Asset>().is_err());
+        assert!("".parse::<Asset>().is_err());
+
+        for asset in Asset::ALL {
+            assert_eq!(asset.to_string().parse::<Asset>().unwrap(), asset);
+        }
+    }
+
+    #[test]
+    fn test_slug_prefix() {
+        assert_eq!(Asset::Btc.slug_prefix(), "btc-updown-15m-");
+        assert_eq!(Asset::Xrp.slug_prefix(), "xrp-updown-15m-");
+    }
+}
@@ -0,0 +1,448 @@
+//! Authenticated user channel (`/ws/user`)
+//!
+//! The market channel only carries public book data; our own order and
+//! trade updates come from the user channel, which authenticates with the
+//! API key/secret/passphrase and filters by market (condition ID).
+//!
+//! Trades go through MATCHED -> MINED -> CONFIRMED (or RETRYING -> FAILED),
+//! and each status arrives as a separate message for the same trade ID.
+//! `PositionManager::process_trade` dedupes those and reverts fills whose
+//! trade failed on-chain.
+
+use anyhow::{Context, Result};
+use futures_util::{SinkExt, StreamExt};
+use parking_lot::{Mutex, RwLock};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, Notify};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tracing::{debug, error, info, warn};
+
+use crate::config::Config;
+use crate::position::PositionManager;
+use crate::supervisor::Supervisor;
+use crate::types::{Side, TradeFill};
+
+const USER_WS_URL: &str = "wss://ws-subscriptions-clob.polymarket.com/ws/user";
+
+/// Numeric fields arrive as strings ("0.57") but accept bare numbers too
+fn de_decimal<'de, D>(deserializer: D) -> Result<Decimal, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::String(s) => s.parse().map_err(serde::de::Error::custom),
+        serde_json::Value::Number(n) => n.to_string().parse().map_err(serde::de::Error::custom),
+        other => Err(serde::de::Error::custom(format!("invalid decimal: {}", other))),
+    }
+}
+
+/// Order lifecycle message type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum OrderEventType {
+    Placement,
+    Update,
+    Cancellation,
+}
+
+/// Our order was placed, (partially) matched or cancelled
+#[derive(Debug, Clone, Deserialize)]
+pub struct OrderEvent {
+    pub id: String,
+    pub market: String,
+    pub asset_id: String,
+    pub side: Side,
+    #[serde(deserialize_with = "de_decimal")]
+    pub price: Decimal,
+    #[serde(deserialize_with = "de_decimal")]
+    pub original_size: Decimal,
+    #[serde(deserialize_with = "de_decimal")]
+    pub size_matched: Decimal,
+    #[serde(rename = "type")]
+    pub event_type: OrderEventType,
+    #[serde(default)]
+    pub timestamp: Option<String>,
+}
+
+/// Settlement status of a trade
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum TradeStatus {
+    Matched,
+    Mined,
+    Confirmed,
+    Retrying,
+    Failed,
+}
+
+/// Resting order filled as part of a trade
+#[derive(Debug, Clone, Deserialize)]
+pub struct MakerOrder {
+    pub order_id: String,
+    pub asset_id: String,
+    pub owner: String,
+    #[serde(deserialize_with = "de_decimal")]
+    pub matched_amount: Decimal,
+    #[serde(deserialize_with = "de_decimal")]
+    pub price: Decimal,
+    #[serde(default)]
+    pub side: Option<Side>,
+}
+
+/// A match involving one of our orders, as taker or maker
+#[derive(Debug, Clone, Deserialize)]
+pub struct TradeEvent {
+    pub id: String,
+    pub market: String,
+    pub asset_id: String,
+    pub side: Side,
+    #[serde(deserialize_with = "de_decimal")]
+    pub price: Decimal,
+    #[serde(deserialize_with = "de_decimal")]
+    pub size: Decimal,
+    pub status: TradeStatus,
+    pub taker_order_id: String,
+    #[serde(default)]
+    pub trade_owner: Option<String>,
+    #[serde(default)]
+    pub maker_orders: Vec<MakerOrder>,
+}
+
+impl TradeEvent {
+    /// Fills of our own orders in this trade
+    ///
+    /// We're the taker when `trade_owner` is our API key; otherwise our
+    /// resting orders show up in `maker_orders`, at their own price. A maker
+    /// fill's side is the one on its `maker_orders` entry, else the side of
+    /// the order as we tracked it (`tracked_side`), else inferred from the
+    /// tokens: opposite the taker on the same token, the same side on the
+    /// complementary one (a mint or merge match).
+    pub fn our_fills(&self, api_key: &str, tracked_side: impl Fn(&str) -> Option<Side>) -> Vec<TradeFill> {
+        let status = format!("{:?}", self.status).to_uppercase();
+
+        if self.trade_owner.as_deref() == Some(api_key) {
+            return vec![TradeFill {
+                asset_id: self.asset_id.clone(),
+                market: self.market.clone(),
+                side: self.side,
+                price: self.price.to_string(),
+                size: self.size.to_string(),
+                order_id: self.taker_order_id.clone(),
+                status,
+            }];
+        }
+
+        self.maker_orders
+            .iter()
+            .filter(|m| m.owner == api_key)
+            .map(|m| TradeFill {
+                asset_id: m.asset_id.clone(),
+                market: self.market.clone(),
+                side: m.side.or_else(|| tracked_side(&m.order_id)).unwrap_or(
+                    match (m.asset_id == self.asset_id, self.side) {
+                        (true, Side::Buy) => Side::Sell,
+                        (true, Side::Sell) => Side::Buy,
+                        (false, side) => side,
+                    },
+                ),
+                price: m.price.to_string(),
+                size: m.matched_amount.to_string(),
+                order_id: m.order_id.clone(),
+                status: status.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Events from the user channel
+#[derive(Debug, Clone)]
+pub enum UserEvent {
+    Order(OrderEvent),
+    Trade(TradeEvent),
+    /// First time we see a fill for one of our orders (already in PositionManager)
+    Fill(TradeFill),
+    /// Trade failed on-chain; the fill was removed from PositionManager
+    FillReverted(TradeFill),
+    Connected,
+    Disconnected,
+    Error(String),
+}
+
+/// Parse one user channel message (may be a single object or an array)
+pub fn parse_user_message(text: &str) -> Result<Vec<UserEvent>> {
+    let value: serde_json::Value = serde_json::from_str(text)?;
+    let items = match value {
+        serde_json::Value::Array(items) => items,
+        other => vec![other],
+    };
+
+    let mut events = Vec::new();
+    for item in items {
+        match item.get("event_type").and_then(|t| t.as_str()) {
+            Some("order") => events.push(UserEvent::Order(
+                serde_json::from_value(item).context("Invalid order event")?,
+            )),
+            Some("trade") => events.push(UserEvent::Trade(
+                serde_json::from_value(item).context("Invalid trade event")?,
+            )),
+            _ => {
+                if item.get("type").and_then(|t| t.as_str()) == Some("error") {
+                    let message = item.get("message").and_then(|m| m.as_str()).unwrap_or("Unknown error");
+                    events.push(UserEvent::Error(message.to_string()));
+                }
+            }
+        }
+    }
+    Ok(events)
+}
+
+/// Handle for changing the subscribed markets of a running user channel
+#[derive(Clone)]
+pub struct UserChannelHandle {
+    markets: Arc<RwLock<HashSet<String>>>,
+    changed: Arc<Notify>,
+}
+
+impl UserChannelHandle {
+    /// Start receiving order/trade events for a market (condition ID)
+    pub fn subscribe(&self, condition_id: &str) {
+        if self.markets.write().insert(condition_id.to_string()) {
+            self.changed.notify_one();
+        }
+    }
+
+    pub fn unsubscribe(&self, condition_id: &str) {
+        if self.markets.write().remove(condition_id) {
+            self.changed.notify_one();
+        }
+    }
+}
+
+/// Spawn the user channel client
+///
+/// Fills are applied to `position_manager` before being forwarded, so
+/// receivers only need to react (alerts, logging, rebalancing).
+/// Changing subscriptions reconnects with the new market set. The client
+/// runs on `supervisor` and stops when it shuts down.
+pub fn spawn_user_channel(
+    config: Config,
+    position_manager: Arc<Mutex<PositionManager>>,
+    supervisor: &Supervisor,
+) -> (UserChannelHandle, mpsc::Receiver<UserEvent>) {
+    let (tx, rx) = mpsc::channel(1000);
+    let handle = UserChannelHandle {
+        markets: Arc::new(RwLock::new(HashSet::new())),
+        changed: Arc::new(Notify::new()),
+    };
+
+    let task_handle = handle.clone();
+    supervisor.spawn("User channel", async move {
+        loop {
+            let markets: Vec<String> = task_handle.markets.read().iter().cloned().collect();
+            if markets.is_empty() {
+                // Nothing to listen to yet; an empty list would subscribe to every market
+                task_handle.changed.notified().await;
+                continue;
+            }
+
+            tokio::select! {
+                result = run_user_connection(&config, &markets, &tx, &position_manager) => {
+                    if let Err(e) = result {
+                        error!("User channel error: {}", e);
+                        let _ = tx.send(UserEvent::Error(e.to_string())).await;
+                    }
+                    let _ = tx.send(UserEvent::Disconnected).await;
+                    tokio::time::sleep(Duration::from_millis(500)).await;
+                }
+                _ = task_handle.changed.notified() => {
+                    debug!("User channel subscriptions changed, reconnecting");
+                }
+            }
+        }
+    });
+
+    (handle, rx)
+}
+
+async fn run_user_connection(
+    config: &Config,
+    markets: &[String],
+    event_tx: &mpsc::Sender<UserEvent>,
+    position_manager: &Mutex<PositionManager>,
+) -> Result<()> {
+    info!("Connecting to user channel: {}", USER_WS_URL);
+    let (ws_stream, _) = tokio::time::timeout(Duration::from_secs(10), connect_async(USER_WS_URL))
+        .await
+        .context("User channel connection timeout")?
+        .context("Failed to connect to user channel")?;
+
+    let (mut write, mut read) = ws_stream.split();
+
+    let subscribe = json!({
+        "auth": {
+            "apiKey": config.api_key,
+            "secret": config.api_secret,
+            "passphrase": config.api_passphrase
+        },
+        "markets": markets,
+        "type": "user"
+    });
+    write
+        .send(Message::Text(subscribe.to_string()))
+        .await
+        .context("Failed to subscribe to user channel")?;
+
+    info!("Subscribed to user channel for {} markets", markets.len());
+    let _ = event_tx.send(UserEvent::Connected).await;
+
+    let write = Arc::new(tokio::sync::Mutex::new(write));
+    let ping_writer = write.clone();
+    let ping_task = tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(10));
+        loop {
+            interval.tick().await;
+            // Polymarket expects text "PING" not binary ping frames
+            if ping_writer.lock().await.send(Message::Text("PING".to_string())).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    while let Some(msg) = read.next().await {
+        let text = match msg {
+            Ok(Message::Text(text)) => text,
+            Ok(Message::Binary(data)) => match String::from_utf8(data) {
+                Ok(text) => text,
+                Err(_) => continue,
+            },
+            Ok(Message::Ping(data)) => {
+                let _ = write.lock().await.send(Message::Pong(data)).await;
+                continue;
+            }
+            Ok(Message::Close(_)) => {
+                info!("User channel closed by server");
+                break;
+            }
+            Err(e) => {
+                error!("User channel error: {}", e);
+                break;
+            }
+            _ => continue,
+        };
+
+        if text == "PONG" {
+            continue;
+        }
+
+        let events = match parse_user_message(&text) {
+            Ok(events) => events,
+            Err(e) => {
+                debug!("Failed to parse user message: {} ({})", e, &text[..text.len().min(200)]);
+                continue;
+            }
+        };
+
+        for event in events {
+            forward_event(event, &config.api_key, event_tx, position_manager).await;
+        }
+    }
+
+    ping_task.abort();
+    Ok(())
+}
+
+async fn forward_event(
+    event: UserEvent,
+    api_key: &str,
+    event_tx: &mpsc::Sender<UserEvent>,
+    position_manager: &Mutex<PositionManager>,
+) {
+    match &event {
+        UserEvent::Order(order) => {
+            debug!("Order {:?}: {} {:?} {} @ {}", order.event_type, order.id, order.side, order.original_size, order.price);
+            position_manager.lock().process_order_event(order);
+        }
+        UserEvent::Trade(trade) => {
+            let update = position_manager.lock().process_trade(trade, api_key);
+            for fill in update.new_fills {
+                info!("Fill received: {} @ {} ({:?})", fill.size, fill.price, trade.status);
+                let _ = event_tx.send(UserEvent::Fill(fill)).await;
+            }
+            for fill in update.reverted {
+                warn!("Trade {} FAILED, reverting fill {} @ {}", trade.id, fill.size, fill.price);
+                let _ = event_tx.send(UserEvent::FillReverted(fill)).await;
+            }
+        }
+        UserEvent::Error(e) => error!("User channel error: {}", e),
+        _ => {}
+    }
+    let _ = event_tx.send(event).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    const ORDER_MSG: &str = r#"{"asset_id":"5215","associate_trades":null,"event_type":"order","id":"0xff35","market":"0xbd31","order_owner":"9180014b","original_size":"10","outcome":"YES","owner":"9180014b","price":"0.57","side":"SELL","size_matched":"0","timestamp":"1672290687","type":"PLACEMENT"}"#;
+
+    const TRADE_MSG: &str = r#"{"asset_id":"5215","event_type":"trade","id":"28c4d2eb","last_update":"1672290701","maker_orders":[{"asset_id":"5215","matched_amount":"10","order_id":"0xff35","outcome":"YES","owner":"9180014b","price":"0.57"}],"market":"0xbd31","matchtime":"1672290701","outcome":"YES","owner":"9180014b","price":"0.57","side":"SELL","size":"10","status":"MATCHED","taker_order_id":"0x06bc","timestamp":"1672290701","trade_owner":"other","type":"TRADE"}"#;
+
+    #[test]
+    fn test_parse_order_event() {
+        let events = parse_user_message(ORDER_MSG).unwrap();
+        match &events[0] {
+            UserEvent::Order(order) => {
+                assert_eq!(order.event_type, OrderEventType::Placement);
+                assert_eq!(order.side, Side::Sell);
+                assert_eq!(order.price, dec!(0.57));
+                assert_eq!(order.original_size, dec!(10));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_trade_maker_fill_and_status_dedupe() {
+        let events = parse_user_message(TRADE_MSG).unwrap();
+        let UserEvent::Trade(trade) = &events[0] else { panic!() };
+        assert_eq!(trade.status, TradeStatus::Matched);
+
+        // 0xff35 is a resting SELL (ORDER_MSG); the entry carries no side
+        let fills = trade.our_fills("9180014b", |id| (id == "0xff35").then_some(Side::Sell));
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].order_id, "0xff35");
+        assert_eq!(fills[0].side, Side::Sell);
+        assert!(trade.our_fills("someone-else", |_| None).is_empty());
+
+        // Untracked maker on the complementary token: both sides SELL (a merge)
+        let mut merge = trade.clone();
+        merge.maker_orders[0].asset_id = "5216".to_string();
+        assert_eq!(merge.our_fills("9180014b", |_| None)[0].side, Side::Sell);
+
+        let mut pm = PositionManager::new();
+        pm.register_market("0xbd31", "5215", "5216");
+        let UserEvent::Order(order) = &parse_user_message(ORDER_MSG).unwrap()[0] else { panic!() };
+        pm.process_order_event(order);
+        let applied = pm.process_trade(trade, "9180014b").new_fills;
+        assert_eq!(applied.len(), 1);
+        assert_eq!(applied[0].side, Side::Sell);
+
+        // MINED/CONFIRMED for the same trade don't double count
+        let mut mined = trade.clone();
+        mined.status = TradeStatus::Mined;
+        assert!(pm.process_trade(&mined, "9180014b").new_fills.is_empty());
+        assert_eq!(pm.get_position("0xbd31").unwrap().up_shares, dec!(-10));
+
+        // FAILED reverts it
+        let mut failed = trade.clone();
+        failed.status = TradeStatus::Failed;
+        assert_eq!(pm.process_trade(&failed, "9180014b").reverted.len(), 1);
+        assert_eq!(pm.get_position("0xbd31").unwrap().up_shares, dec!(0));
+    }
+}
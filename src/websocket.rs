@@ -1,6 +1,7 @@
 use anyhow::{Context, Result};
 use futures_util::{SinkExt, StreamExt};
 use parking_lot::RwLock;
+use rust_decimal::Decimal;
 use serde_json::json;
 use std::sync::Arc;
 use std::time::Duration;
@@ -9,7 +10,9 @@ use tokio_tungstenite::{connect_async, tungstenite::Message};
 use tracing::{debug, error, info};
 
 use crate::config::Config;
-use crate::orderbook::OrderbookManager;
+use crate::event_bus::BookTop;
+use crate::orderbook::{ApplyOutcome, BookMeta, BookStatus, LevelChange, OrderbookManager};
+use crate::trade_tape::{parse_last_trade_price, TradePrint};
 use crate::types::{TradeFill, Side};
 
 /// WebSocket event types
@@ -33,6 +36,18 @@ pub enum WsEvent {
         best_bid: String,
         best_ask: String,
     },
+    /// Latest top of book from a [`MarketStream`](crate::event_bus::MarketStream);
+    /// intermediate updates may have been conflated
+    Book(BookTop),
+    /// Book failed an integrity check; its prices are withheld until resync
+    BookUnreliable {
+        asset_id: String,
+        reason: String,
+    },
+    /// Trade printed on a token (`last_trade_price`)
+    LastTrade(TradePrint),
+    /// Token's tick size changed; already recorded in the orderbook manager
+    TickSizeChange(TickSizeChange),
     /// Trade fill (your order got hit)
     TradeFill(TradeFill),
     /// Connection established
@@ -43,6 +58,43 @@ pub enum WsEvent {
     Error(String),
 }
 
+/// A `tick_size_change` message
+#[derive(Debug, Clone, PartialEq)]
+pub struct TickSizeChange {
+    pub asset_id: String,
+    pub market: String,
+    pub old_tick_size: Decimal,
+    pub new_tick_size: Decimal,
+}
+
+/// Parse a `tick_size_change` message
+///
+/// `{"event_type":"tick_size_change","asset_id":"..","market":"0x..","old_tick_size":"0.01","new_tick_size":"0.001",..}`
+pub fn parse_tick_size_change(msg: &serde_json::Value) -> Option<TickSizeChange> {
+    if msg.get("event_type").and_then(|e| e.as_str()) != Some("tick_size_change") {
+        return None;
+    }
+    Some(TickSizeChange {
+        asset_id: str_field(msg, &["asset_id"])?,
+        market: str_field(msg, &["market"]).unwrap_or_default(),
+        old_tick_size: str_field(msg, &["old_tick_size"])?.parse().ok()?,
+        new_tick_size: str_field(msg, &["new_tick_size"])?.parse().ok()?,
+    })
+}
+
+/// Record a tick size change; the event is forwarded after the manager has it
+fn apply_tick_size_change(msg: &serde_json::Value, orderbook_manager: &OrderbookManager) -> Option<WsEvent> {
+    let change = parse_tick_size_change(msg)?;
+    orderbook_manager.set_tick_size(&change.asset_id, change.new_tick_size);
+    info!(
+        "Tick size change on {}: {} -> {}",
+        &change.asset_id[..change.asset_id.len().min(16)],
+        change.old_tick_size,
+        change.new_tick_size
+    );
+    Some(WsEvent::TickSizeChange(change))
+}
+
 /// High-performance WebSocket client
 pub struct WebSocketClient {
     config: Config,
@@ -95,6 +147,8 @@ impl WebSocketClient {
                 tokio::time::sleep(Duration::from_secs(30)).await;
             }
 
+            // Whatever happened while we were down is lost
+            self.orderbook_manager.mark_gap(&token_ids, "websocket reconnect");
             let _ = self.event_tx.send(WsEvent::Disconnected).await;
 
             // Exponential backoff for reconnect
@@ -158,7 +212,7 @@ impl WebSocketClient {
 
         // Note: clob_user subscriptions require a separate WebSocket connection to /ws/user
         // The /ws/market endpoint only handles market data (orderbooks, prices)
-        // For now, we'll poll for fills via REST API instead
+        // Our own orders and fills come from the user channel (see user_ws.rs)
 
         info!("Subscribed to all channels");
 
@@ -182,14 +236,14 @@ impl WebSocketClient {
         while let Some(msg) = read.next().await {
             match msg {
                 Ok(Message::Text(text)) => {
-                    if let Err(e) = self.handle_message(&text).await {
+                    if let Err(e) = self.handle_message(&text) {
                         debug!("Failed to handle message: {}", e);
                     }
                 }
                 Ok(Message::Binary(data)) => {
                     // Some messages might come as binary
                     if let Ok(text) = String::from_utf8(data) {
-                        if let Err(e) = self.handle_message(&text).await {
+                        if let Err(e) = self.handle_message(&text) {
                             debug!("Failed to handle binary message: {}", e);
                         }
                     }
@@ -217,318 +271,36 @@ impl WebSocketClient {
     }
 
     /// Handle incoming WebSocket message
-    async fn handle_message(&self, text: &str) -> Result<()> {
+    ///
+    /// Never waits on consumers: a full buffer drops the event rather than
+    /// stalling the socket reader.
+    fn handle_message(&self, text: &str) -> Result<()> {
         let msg: serde_json::Value = serde_json::from_str(text)?;
 
         // Debug log for unknown messages
         debug!("WS message: {}", &text[..std::cmp::min(200, text.len())]);
 
-        // Handle subscription confirmations
-        if let Some(msg_type) = msg.get("type").and_then(|t| t.as_str()) {
-            match msg_type {
-                "subscribed" => {
-                    debug!("Subscription confirmed");
-                    return Ok(());
-                }
-                "error" => {
-                    let error_msg = msg
-                        .get("message")
-                        .and_then(|m| m.as_str())
-                        .unwrap_or("Unknown error");
-                    error!("WebSocket error: {}", error_msg);
-                    let _ = self.event_tx.send(WsEvent::Error(error_msg.to_string())).await;
-                    return Ok(());
-                }
-                _ => {}
-            }
-        }
-
-        // Handle market_created events
-        if msg.get("market").is_some() && msg.get("asset_ids").is_some() {
-            let condition_id = msg["market"].as_str().unwrap_or("").to_string();
-            let asset_ids: Vec<String> = msg["asset_ids"]
-                .as_array()
-                .map(|arr| {
-                    arr.iter()
-                        .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                        .collect()
-                })
-                .unwrap_or_default();
-            let tick_size = msg["tick_size"].as_str().unwrap_or("0.01").to_string();
-
-            info!("New market created: {} with {} tokens", condition_id, asset_ids.len());
-
-            let _ = self
-                .event_tx
-                .send(WsEvent::MarketCreated {
-                    condition_id,
-                    asset_ids,
-                    tick_size,
-                })
-                .await;
-
-            return Ok(());
-        }
-
-        // Handle orderbook updates (agg_orderbook)
-        if let Some(asset_id) = msg.get("asset_id").and_then(|a| a.as_str()) {
-            // Check if this is an orderbook snapshot
-            if msg.get("bids").is_some() && msg.get("asks").is_some() {
-                let bids: Vec<(String, String)> = msg["bids"]
-                    .as_array()
-                    .map(|arr| {
-                        arr.iter()
-                            .filter_map(|v| {
-                                let price = v.get("price")?.as_str()?.to_string();
-                                let size = v.get("size")?.as_str()?.to_string();
-                                Some((price, size))
-                            })
-                            .collect()
-                    })
-                    .unwrap_or_default();
-
-                let asks: Vec<(String, String)> = msg["asks"]
-                    .as_array()
-                    .map(|arr| {
-                        arr.iter()
-                            .filter_map(|v| {
-                                let price = v.get("price")?.as_str()?.to_string();
-                                let size = v.get("size")?.as_str()?.to_string();
-                                Some((price, size))
-                            })
-                            .collect()
-                    })
-                    .unwrap_or_default();
-
-                // Update local orderbook
-                self.orderbook_manager.update(asset_id, &bids, &asks);
-
-                let _ = self
-                    .event_tx
-                    .send(WsEvent::OrderbookUpdate {
-                        asset_id: asset_id.to_string(),
-                        bids,
-                        asks,
-                    })
-                    .await;
-
-                return Ok(());
-            }
-        }
-
-        // Handle price_change events
-        if let Some(pc) = msg.get("pc").and_then(|p| p.as_array()) {
-            for change in pc {
-                if let (Some(asset_id), Some(best_bid), Some(best_ask)) = (
-                    change.get("a").and_then(|a| a.as_str()),
-                    change.get("bb").and_then(|b| b.as_str()),
-                    change.get("ba").and_then(|a| a.as_str()),
-                ) {
-                    let _ = self
-                        .event_tx
-                        .send(WsEvent::PriceChange {
-                            asset_id: asset_id.to_string(),
-                            best_bid: best_bid.to_string(),
-                            best_ask: best_ask.to_string(),
-                        })
-                        .await;
-                }
-            }
-            return Ok(());
-        }
-
-        // Handle trade fills
-        if let Some(order_id) = msg.get("order_id").and_then(|o| o.as_str()) {
-            if let Some(status) = msg.get("status").and_then(|s| s.as_str()) {
-                if status == "FILLED" || status == "MATCHED" {
-                    let fill = TradeFill {
-                        asset_id: msg
-                            .get("asset_id")
-                            .and_then(|a| a.as_str())
-                            .unwrap_or("")
-                            .to_string(),
-                        market: msg
-                            .get("market")
-                            .and_then(|m| m.as_str())
-                            .unwrap_or("")
-                            .to_string(),
-                        side: if msg.get("side").and_then(|s| s.as_str()) == Some("BUY") {
-                            Side::Buy
-                        } else {
-                            Side::Sell
-                        },
-                        price: msg
-                            .get("price")
-                            .and_then(|p| p.as_str())
-                            .unwrap_or("0")
-                            .to_string(),
-                        size: msg
-                            .get("size")
-                            .and_then(|s| s.as_str())
-                            .unwrap_or("0")
-                            .to_string(),
-                        order_id: order_id.to_string(),
-                        status: status.to_string(),
-                    };
-
-                    info!("Fill received: {} @ {}", fill.size, fill.price);
-                    let _ = self.event_tx.send(WsEvent::TradeFill(fill)).await;
-                }
+        for event in handle_ws_value(&msg, &self.orderbook_manager) {
+            if let Err(mpsc::error::TrySendError::Full(event)) = self.event_tx.try_send(event) {
+                debug!("Event channel full, dropped {:?}", std::mem::discriminant(&event));
             }
         }
-
         Ok(())
     }
 }
 
-/// Spawn WebSocket client in background with orderbook manager
-pub fn spawn_websocket_with_orderbook(
-    config: Config,
-    token_ids: Vec<String>,
-    orderbook_manager: Arc<OrderbookManager>,
-) -> mpsc::Receiver<WsEvent> {
-    let (tx, rx) = mpsc::channel(10000); // Large buffer for high-frequency updates
-
-    // Use std::sync::Arc for the reconnect counter to be Send
-    let reconnect_count = std::sync::Arc::new(std::sync::atomic::AtomicU32::new(0));
-
-    tokio::spawn(async move {
-        loop {
-            let (event_tx, orderbook_mgr, tokens) = (tx.clone(), orderbook_manager.clone(), token_ids.clone());
-
-            match run_websocket_connection(config.clone(), event_tx, orderbook_mgr, tokens).await {
-                Ok(_) => {
-                    info!("WebSocket connection closed normally");
-                }
-                Err(e) => {
-                    error!("WebSocket error: {}", e);
-                }
-            }
-
-            // Reconnect counter
-            let count = reconnect_count.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-            if count > 10 {
-                reconnect_count.store(0, std::sync::atomic::Ordering::Relaxed);
-                error!("Too many reconnects, backing off...");
-                tokio::time::sleep(Duration::from_secs(30)).await;
-            }
-
-            let _ = tx.send(WsEvent::Disconnected).await;
-
-            info!("Reconnecting in 500ms...");
-            tokio::time::sleep(Duration::from_millis(500)).await;
-        }
-    });
-
-    rx
-}
-
-/// Run a single WebSocket connection
-async fn run_websocket_connection(
-    _config: Config,
-    event_tx: mpsc::Sender<WsEvent>,
-    orderbook_manager: Arc<OrderbookManager>,
-    token_ids: Vec<String>,
-) -> Result<()> {
-    // Polymarket WebSocket endpoints:
-    // /ws/market - for market data (orderbooks, prices) - public
-    // /ws/user - for user data (orders, trades) - requires auth
-    let url = "wss://ws-subscriptions-clob.polymarket.com/ws/market";
-    info!("Connecting to WebSocket: {}", url);
-
-    // Connect with timeout
-    let connect_future = connect_async(url);
-    let (ws_stream, _) = tokio::time::timeout(Duration::from_secs(10), connect_future)
-        .await
-        .context("WebSocket connection timeout")?
-        .context("Failed to connect to WebSocket")?;
-
-    info!("WebSocket connected");
-    let _ = event_tx.send(WsEvent::Connected).await;
-
-    let (mut write, mut read) = ws_stream.split();
-
-    // Subscribe to market channel with asset IDs
-    // Correct format per Polymarket docs: {"assets_ids": [...], "type": "market"}
-    let market_subscribe = json!({
-        "assets_ids": token_ids,
-        "type": "market"
-    });
-
-    write
-        .send(Message::Text(market_subscribe.to_string()))
-        .await
-        .context("Failed to subscribe to market")?;
-
-    info!("Subscribed to market channel for {} tokens", token_ids.len());
-
-    // Note: User channel (orders/trades) requires separate connection to /ws/user with auth
-
-    // Keep-alive ping task (every 10 seconds per Polymarket docs)
-    let ping_write = Arc::new(tokio::sync::Mutex::new(write));
-    let ping_writer = ping_write.clone();
-
-    tokio::spawn(async move {
-        let mut interval = tokio::time::interval(Duration::from_secs(10));
-        loop {
-            interval.tick().await;
-            let mut w = ping_writer.lock().await;
-            // Polymarket expects text "PING" not binary ping frames
-            if w.send(Message::Text("PING".to_string())).await.is_err() {
-                break;
-            }
-        }
-    });
-
-    // Process messages
-    while let Some(msg) = read.next().await {
-        match msg {
-            Ok(Message::Text(text)) => {
-                if let Err(e) = handle_ws_message(&text, &event_tx, &orderbook_manager).await {
-                    debug!("Failed to handle message: {}", e);
-                }
-            }
-            Ok(Message::Binary(data)) => {
-                if let Ok(text) = String::from_utf8(data) {
-                    let _ = handle_ws_message(&text, &event_tx, &orderbook_manager).await;
-                }
-            }
-            Ok(Message::Ping(data)) => {
-                let mut w = ping_write.lock().await;
-                let _ = w.send(Message::Pong(data)).await;
-            }
-            Ok(Message::Close(_)) => {
-                info!("WebSocket closed by server");
-                break;
-            }
-            Err(e) => {
-                error!("WebSocket error: {}", e);
-                break;
-            }
-            _ => {}
-        }
-    }
-
-    Ok(())
-}
-
-/// Handle a WebSocket message
-async fn handle_ws_message(
-    text: &str,
-    event_tx: &mpsc::Sender<WsEvent>,
-    orderbook_manager: &OrderbookManager,
-) -> Result<()> {
-    let msg: serde_json::Value = serde_json::from_str(text)?;
-
+/// Handle a market channel message: update local books and return the events to publish
+///
+/// Never waits on consumers; the caller decides how events are delivered.
+pub fn handle_ws_value(msg: &serde_json::Value, orderbook_manager: &OrderbookManager) -> Vec<WsEvent> {
     // Handle subscription confirmations
     if let Some(msg_type) = msg.get("type").and_then(|t| t.as_str()) {
         match msg_type {
-            "subscribed" => return Ok(()),
+            "subscribed" => return Vec::new(),
             "error" => {
                 let error_msg = msg.get("message").and_then(|m| m.as_str()).unwrap_or("Unknown error");
                 error!("WebSocket error: {}", error_msg);
-                let _ = event_tx.send(WsEvent::Error(error_msg.to_string())).await;
-                return Ok(());
+                return vec![WsEvent::Error(error_msg.to_string())];
             }
             _ => {}
         }
@@ -544,8 +316,7 @@ async fn handle_ws_message(
         let tick_size = msg["tick_size"].as_str().unwrap_or("0.01").to_string();
 
         info!("New market created: {}", condition_id);
-        let _ = event_tx.send(WsEvent::MarketCreated { condition_id, asset_ids, tick_size }).await;
-        return Ok(());
+        return vec![WsEvent::MarketCreated { condition_id, asset_ids, tick_size }];
     }
 
     // Handle orderbook updates
@@ -577,28 +348,28 @@ async fn handle_ws_message(
                 })
                 .unwrap_or_default();
 
-            orderbook_manager.update(asset_id, &bids, &asks);
-            let _ = event_tx.send(WsEvent::OrderbookUpdate { asset_id: asset_id.to_string(), bids, asks }).await;
-            return Ok(());
+            let was_trusted = orderbook_manager.book_status(asset_id) == BookStatus::Trusted;
+            let outcome = orderbook_manager.apply_snapshot(asset_id, &bids, &asks, book_meta(msg));
+            if let Some(event) = unreliable_event(asset_id, was_trusted, &outcome) {
+                return vec![event];
+            }
+            return vec![WsEvent::OrderbookUpdate { asset_id: asset_id.to_string(), bids, asks }];
         }
     }
 
-    // Handle price_change
-    if let Some(pc) = msg.get("pc").and_then(|p| p.as_array()) {
-        for change in pc {
-            if let (Some(asset_id), Some(best_bid), Some(best_ask)) = (
-                change.get("a").and_then(|a| a.as_str()),
-                change.get("bb").and_then(|b| b.as_str()),
-                change.get("ba").and_then(|a| a.as_str()),
-            ) {
-                let _ = event_tx.send(WsEvent::PriceChange {
-                    asset_id: asset_id.to_string(),
-                    best_bid: best_bid.to_string(),
-                    best_ask: best_ask.to_string(),
-                }).await;
-            }
-        }
-        return Ok(());
+    // Handle price_change (level deltas)
+    if let Some(events) = apply_price_change_message(msg, orderbook_manager) {
+        return events;
+    }
+
+    // Handle trade prints
+    if let Some(print) = parse_last_trade_price(msg) {
+        return vec![WsEvent::LastTrade(print)];
+    }
+
+    // Handle tick size changes
+    if let Some(event) = apply_tick_size_change(msg, orderbook_manager) {
+        return vec![event];
     }
 
     // Handle trade fills
@@ -615,16 +386,212 @@ async fn handle_ws_message(
                     status: status.to_string(),
                 };
                 info!("Fill received: {} @ {}", fill.size, fill.price);
-                let _ = event_tx.send(WsEvent::TradeFill(fill)).await;
+                return vec![WsEvent::TradeFill(fill)];
             }
         }
     }
 
-    Ok(())
+    Vec::new()
+}
+
+/// Level deltas for one asset from a price_change message
+#[derive(Debug, Clone)]
+pub struct PriceChangeUpdate {
+    pub asset_id: String,
+    pub changes: Vec<LevelChange>,
+    pub hash: Option<String>,
+    pub best_bid: Option<String>,
+    pub best_ask: Option<String>,
+}
+
+/// Exchange timestamps arrive as ms strings (sometimes numbers)
+pub fn parse_timestamp_ms(value: Option<&serde_json::Value>) -> Option<i64> {
+    match value? {
+        serde_json::Value::String(s) => s.parse().ok(),
+        serde_json::Value::Number(n) => n.as_i64(),
+        _ => None,
+    }
+}
+
+fn str_field(v: &serde_json::Value, keys: &[&str]) -> Option<String> {
+    keys.iter().find_map(|k| v.get(*k).and_then(|x| x.as_str()).map(String::from))
 }
 
-/// Spawn WebSocket client (backward compatible)
-pub fn spawn_websocket(config: Config, token_ids: Vec<String>) -> mpsc::Receiver<WsEvent> {
-    let orderbook_manager = Arc::new(OrderbookManager::new());
-    spawn_websocket_with_orderbook(config, token_ids, orderbook_manager)
+/// Parse a price_change message into per-asset level deltas
+///
+/// Handles the compact `pc` form (`a`/`p`/`s`/`si`/`h`/`bb`/`ba`), the
+/// `price_changes` form (one entry per level with `asset_id`) and the older
+/// form with a top-level `asset_id` and a `changes` list.
+/// Returns `None` if the message isn't a price_change.
+pub fn parse_price_changes(msg: &serde_json::Value) -> Option<(Vec<PriceChangeUpdate>, Option<i64>)> {
+    let timestamp = parse_timestamp_ms(msg.get("timestamp").or_else(|| msg.get("t")));
+
+    let entries: Vec<(String, &serde_json::Value)> =
+        if let Some(items) = msg.get("pc").or_else(|| msg.get("price_changes")).and_then(|p| p.as_array()) {
+            items
+                .iter()
+                .filter_map(|c| Some((str_field(c, &["a", "asset_id"])?, c)))
+                .collect()
+        } else if msg.get("event_type").and_then(|e| e.as_str()) == Some("price_change") {
+            let asset_id = str_field(msg, &["asset_id"])?;
+            msg.get("changes")?
+                .as_array()?
+                .iter()
+                .map(|c| (asset_id.clone(), c))
+                .collect()
+        } else {
+            return None;
+        };
+
+    // Group by asset, keeping message order
+    let mut updates: Vec<PriceChangeUpdate> = Vec::new();
+    for (asset_id, entry) in entries {
+        let idx = match updates.iter().position(|u| u.asset_id == asset_id) {
+            Some(idx) => idx,
+            None => {
+                updates.push(PriceChangeUpdate {
+                    asset_id: asset_id.clone(),
+                    changes: Vec::new(),
+                    hash: str_field(msg, &["hash"]),
+                    best_bid: None,
+                    best_ask: None,
+                });
+                updates.len() - 1
+            }
+        };
+        let update = &mut updates[idx];
+
+        let price = str_field(entry, &["p", "price"]).and_then(|p| p.parse().ok());
+        let size = str_field(entry, &["s", "size"]).and_then(|s| s.parse().ok());
+        let side = match str_field(entry, &["si", "side"]).as_deref() {
+            Some("BUY") => Some(Side::Buy),
+            Some("SELL") => Some(Side::Sell),
+            _ => None,
+        };
+        if let (Some(price), Some(size), Some(side)) = (price, size, side) {
+            update.changes.push(LevelChange { side, price, size });
+        }
+
+        if let Some(hash) = str_field(entry, &["h", "hash"]) {
+            update.hash = Some(hash);
+        }
+        if let Some(bb) = str_field(entry, &["bb", "best_bid"]) {
+            update.best_bid = Some(bb);
+        }
+        if let Some(ba) = str_field(entry, &["ba", "best_ask"]) {
+            update.best_ask = Some(ba);
+        }
+    }
+
+    Some((updates, timestamp))
+}
+
+/// Market, hash and timestamp of a book message
+fn book_meta(msg: &serde_json::Value) -> BookMeta {
+    BookMeta {
+        market: str_field(msg, &["market", "m"]),
+        hash: str_field(msg, &["hash"]),
+        timestamp_ms: parse_timestamp_ms(msg.get("timestamp").or_else(|| msg.get("t"))),
+        tick_size: str_field(msg, &["tick_size"]).and_then(|t| t.parse().ok()),
+    }
+}
+
+/// `BookUnreliable` event when a book has just turned untrusted
+fn unreliable_event(asset_id: &str, was_trusted: bool, outcome: &ApplyOutcome) -> Option<WsEvent> {
+    match outcome {
+        ApplyOutcome::Untrusted(issue) if was_trusted => Some(WsEvent::BookUnreliable {
+            asset_id: asset_id.to_string(),
+            reason: issue.to_string(),
+        }),
+        _ => None,
+    }
+}
+
+/// Apply a price_change message to the local books
+///
+/// Returns the events to forward, or `None` if the message isn't a
+/// price_change. Best bid/ask fall back to the updated local book when the
+/// message doesn't carry them. Untrusted books produce a single
+/// `BookUnreliable` instead of prices.
+fn apply_price_change_message(
+    msg: &serde_json::Value,
+    orderbook_manager: &OrderbookManager,
+) -> Option<Vec<WsEvent>> {
+    let (updates, _) = parse_price_changes(msg)?;
+    let meta = book_meta(msg);
+
+    let mut events = Vec::with_capacity(updates.len());
+    for update in updates {
+        let was_trusted = orderbook_manager.book_status(&update.asset_id) == BookStatus::Trusted;
+        let update_meta = BookMeta { hash: update.hash, ..meta.clone() };
+        match orderbook_manager.apply_price_changes(&update.asset_id, &update.changes, update_meta) {
+            ApplyOutcome::Applied => {}
+            ApplyOutcome::Stale => {
+                debug!("Dropped stale price_change for {}", update.asset_id);
+                continue;
+            }
+            outcome @ ApplyOutcome::Untrusted(_) => {
+                events.extend(unreliable_event(&update.asset_id, was_trusted, &outcome));
+                continue;
+            }
+        }
+
+        let (book_bid, book_ask) = orderbook_manager.best_bid_ask(&update.asset_id).unwrap_or((None, None));
+        let best_bid = update.best_bid.or_else(|| book_bid.map(|p| p.to_string()));
+        let best_ask = update.best_ask.or_else(|| book_ask.map(|p| p.to_string()));
+        if let (Some(best_bid), Some(best_ask)) = (best_bid, best_ask) {
+            events.push(WsEvent::PriceChange {
+                asset_id: update.asset_id,
+                best_bid,
+                best_ask,
+            });
+        }
+    }
+    Some(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_parse_price_change_formats() {
+        let compact: serde_json::Value = serde_json::from_str(
+            r#"{"m":"0xabc","pc":[{"a":"1","p":"0.5","s":"10","si":"SELL","h":"h1","bb":"0.48","ba":"0.5"},{"a":"2","p":"0.49","s":"0","si":"BUY","h":"h2","bb":"0.47","ba":"0.52"}],"t":"1700000000123"}"#,
+        ).unwrap();
+        let (updates, ts) = parse_price_changes(&compact).unwrap();
+        assert_eq!(ts, Some(1700000000123));
+        assert_eq!(updates.len(), 2);
+        assert_eq!(updates[0].changes, vec![LevelChange { side: Side::Sell, price: dec!(0.5), size: dec!(10) }]);
+        assert_eq!(updates[1].hash.as_deref(), Some("h2"));
+
+        let legacy: serde_json::Value = serde_json::from_str(
+            r#"{"event_type":"price_change","asset_id":"1","market":"0xabc","hash":"h3","timestamp":"5","changes":[{"price":"0.4","side":"BUY","size":"3"},{"price":"0.6","side":"SELL","size":"0"}]}"#,
+        ).unwrap();
+        let (updates, ts) = parse_price_changes(&legacy).unwrap();
+        assert_eq!(ts, Some(5));
+        assert_eq!(updates[0].changes.len(), 2);
+        assert_eq!(updates[0].hash.as_deref(), Some("h3"));
+
+        let book: serde_json::Value = serde_json::from_str(r#"{"asset_id":"1","bids":[],"asks":[]}"#).unwrap();
+        assert!(parse_price_changes(&book).is_none());
+    }
+
+    #[test]
+    fn test_tick_size_change_updates_manager() {
+        let msg: serde_json::Value = serde_json::from_str(
+            r#"{"event_type":"tick_size_change","asset_id":"1","market":"0xabc","old_tick_size":"0.01","new_tick_size":"0.001","side":"BUY","timestamp":"100"}"#,
+        ).unwrap();
+        let manager = OrderbookManager::new();
+        match apply_tick_size_change(&msg, &manager) {
+            Some(WsEvent::TickSizeChange(change)) => {
+                assert_eq!(change.old_tick_size, dec!(0.01));
+                assert_eq!(change.new_tick_size, dec!(0.001));
+                assert_eq!(change.market, "0xabc");
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(manager.tick_size("1"), Some(dec!(0.001)));
+    }
 }